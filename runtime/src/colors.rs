@@ -0,0 +1,170 @@
+//! Whether the outputs are colored, decided as NodeJS does from the
+//! environment and the terminal.
+//!
+//! The number of colors supported by the terminal is guessed from the
+//! environment variables, as `getColorDepth()` of `node:tty`:
+//!
+//! - `FORCE_COLOR`: '', '1' or 'true' for 16 colors, '2' for 256, '3' for
+//!   16 millions and anything else for none. It enables the colors even if
+//!   the output isn't a terminal.
+//! - `NODE_DISABLE_COLORS`, `NO_COLOR` or `TERM=dumb` disable them.
+//! - Otherwise the terminal is recognized by `TMUX`, `CI`, `TERM_PROGRAM`,
+//!   `COLORTERM` and `TERM`.
+
+use std::env;
+
+// The color depths, in bits per pixel.
+const COLORS_2: u8 = 1;
+const COLORS_16: u8 = 4;
+const COLORS_256: u8 = 8;
+const COLORS_16M: u8 = 24;
+
+/// The continuous integration services with colored logs, used when `CI`
+/// is set.
+const CI_ENVS: &[(&str, u8)] = &[
+    ("APPVEYOR", COLORS_256),
+    ("BUILDKITE", COLORS_256),
+    ("CIRCLECI", COLORS_16M),
+    ("DRONE", COLORS_256),
+    ("GITEA_ACTIONS", COLORS_16M),
+    ("GITHUB_ACTIONS", COLORS_16M),
+    ("GITLAB_CI", COLORS_256),
+    ("TRAVIS", COLORS_256),
+];
+
+/// The terminals recognized by their `TERM`.
+const TERM_ENVS: &[(&str, u8)] = &[
+    ("eterm", COLORS_16),
+    ("cons25", COLORS_16),
+    ("console", COLORS_16),
+    ("cygwin", COLORS_16),
+    ("dtterm", COLORS_16),
+    ("gnome", COLORS_16),
+    ("hurd", COLORS_16),
+    ("jfbterm", COLORS_16),
+    ("konsole", COLORS_16),
+    ("kterm", COLORS_16),
+    ("mlterm", COLORS_16),
+    ("mosh", COLORS_16M),
+    ("putty", COLORS_16),
+    ("st", COLORS_16),
+    ("rxvt-unicode-24bit", COLORS_16M),
+    ("terminator", COLORS_16M),
+    ("xterm-kitty", COLORS_16M),
+];
+
+/// The `TERM` of the terminals with 16 colors, recognized by a part of it.
+fn is_16_colors_term(term: &str) -> bool {
+    ["ansi", "color", "linux", "direct"]
+        .iter()
+        .any(|part| term.contains(part))
+        || ["rxvt", "screen", "xterm", "vt100", "vt220"]
+            .iter()
+            .any(|start| term.starts_with(start))
+        // `con132x25` and the like.
+        || term
+            .strip_prefix("con")
+            .map(|rest| rest.trim_start_matches(|c: char| c.is_ascii_digit()))
+            .and_then(|rest| rest.strip_prefix('x'))
+            .map_or(false, |rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Whether a version of TeamCity has colored logs: 9.1 and above.
+fn is_colored_teamcity(version: &str) -> bool {
+    let number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    let mut parts = version.splitn(3, '.');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("9"), Some(minor), Some(_)) => number(minor) && !minor.trim_matches('0').is_empty(),
+        (Some(major), Some(_), _) => number(major) && major.len() >= 2,
+        _ => false,
+    }
+}
+
+/// Number of bits per pixel of the terminal: 1 for 2 colors, 4 for 16, 8
+/// for 256 and 24 for 16 millions.
+pub fn color_depth() -> u8 {
+    let var = |name: &str| env::var(name).ok();
+    if let Some(force) = var("FORCE_COLOR") {
+        return match force.as_str() {
+            "" | "1" | "true" => COLORS_16,
+            "2" => COLORS_256,
+            "3" => COLORS_16M,
+            _ => COLORS_2,
+        };
+    }
+    if var("NODE_DISABLE_COLORS").is_some()
+        || var("NO_COLOR").is_some()
+        || var("TERM").as_deref() == Some("dumb")
+    {
+        return COLORS_2;
+    }
+    if var("TMUX").map_or(false, |tmux| !tmux.is_empty()) {
+        return COLORS_16M;
+    }
+    if var("CI").map_or(false, |ci| !ci.is_empty()) {
+        for (name, depth) in CI_ENVS {
+            if var(name).is_some() {
+                return *depth;
+            }
+        }
+        if var("CI_NAME").as_deref() == Some("codeship") {
+            return COLORS_256;
+        }
+        return COLORS_2;
+    }
+    if let Some(version) = var("TEAMCITY_VERSION") {
+        return if is_colored_teamcity(&version) {
+            COLORS_16
+        } else {
+            COLORS_2
+        };
+    }
+    match var("TERM_PROGRAM").as_deref() {
+        Some("iTerm.app") => {
+            let version = var("TERM_PROGRAM_VERSION").unwrap_or_default();
+            let old = version.is_empty()
+                || (version.starts_with(['0', '1', '2']) && version[1..].starts_with('.'));
+            return if old { COLORS_256 } else { COLORS_16M };
+        }
+        Some("HyperTerm" | "MacTerm") => return COLORS_16M,
+        Some("Apple_Terminal") => return COLORS_256,
+        _ => {}
+    }
+    let color_term = var("COLORTERM");
+    if matches!(color_term.as_deref(), Some("truecolor" | "24bit")) {
+        return COLORS_16M;
+    }
+    if let Some(term) = var("TERM").filter(|term| !term.is_empty()) {
+        if term.contains("truecolor") {
+            return COLORS_16M;
+        }
+        if term.starts_with("xterm-256") {
+            return COLORS_256;
+        }
+        let term = term.to_lowercase();
+        if let Some((_, depth)) = TERM_ENVS.iter().find(|(name, _)| *name == term) {
+            return *depth;
+        }
+        if is_16_colors_term(&term) {
+            return COLORS_16;
+        }
+    }
+    if color_term.map_or(false, |color_term| !color_term.is_empty()) {
+        return COLORS_16;
+    }
+    COLORS_2
+}
+
+/// Whether a file descriptor, like the standard output, is a terminal.
+pub fn is_tty(fd: i32) -> bool {
+    unsafe { libc::isatty(fd) == 1 }
+}
+
+/// Whether an output is colored: if it's a terminal supporting at least 16
+/// colors, or if `FORCE_COLOR` asks for them.
+pub fn should_colorize(is_tty: bool) -> bool {
+    if env::var_os("FORCE_COLOR").is_some() {
+        return color_depth() > 2;
+    }
+    is_tty && color_depth() > 2
+}
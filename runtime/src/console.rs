@@ -1,53 +1,1032 @@
-use maybe_static::maybe_static;
-use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGenericClass, JSValue};
-use rusty_jsc_macros::{callback, constructor};
+//! The `console` global: log, info, debug, error, warn, trace, assert, count,
+//! time, group, dir, dirxml, clear and table.
+//!
+//! A console keeps its state (counters, timers and the indentation of the
+//! groups) in the private data of the object. Its methods are bound to it,
+//! so `const { log } = console` works as in NodeJS. error, warn, trace and
+//! the failed assertions are printed on the standard error, everything else
+//! on the standard output. The global console writes on `process.stdout`
+//! and `process.stderr`, see the stdio module for when the writes are
+//! synchronous.
+//!
+//! `console.Console` makes consoles printing on any object with a `write`
+//! method, like a Writable of `node:stream` or a `fs.WriteStream`.
+//!
+//! The values are colored when the output is a terminal, see the colors
+//! module, or as asked by the `colorMode` option of `new Console()`.
+
+use std::{collections::HashMap, time::Instant};
+
+use maybe_static::{maybe_static, maybe_static_unsafe};
+use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGenericClass, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
 
 use crate::{
-    fs_write_stream::{get_fs_write_stream_class, FsWriteStream},
-    util::format_parser,
+    colors::should_colorize,
+    errors::{code_error, invalid_arg_type, invalid_arg_value, new_error},
+    inspect::{inspect, is_array_index, string_width, type_tag, InspectOptions},
+    js_utils::{array_values, bind, call_global, call_method, has_own_property, same_value},
+    options::get_option,
+    stdio::{stderr, stdout},
+    util::format_with_options,
 };
 
-enum Output {
-    FsWriteStream(FsWriteStream),
+/// An output of a console: a `Writable` of `node:stream`, like
+/// `process.stdout` or a `fs.WriteStream`, or any object with a `write`
+/// method.
+struct Output(JSObject<JSProtected>);
+
+impl Output {
+    /// Write on the output. With `ignore_errors`, the errors of the Writable
+    /// are swallowed as in NodeJS, the synchronous ones and the ones given
+    /// to the write callback.
+    fn write(&self, context: &JSContext, text: &str, ignore_errors: bool) -> Result<(), JSValue> {
+        let stream: JSObject = self.0.clone().into();
+        if ignore_errors {
+            let _ = write_ignoring_errors(context, &stream, text);
+        } else {
+            call_method(context, &stream, "write", &[JSValue::string(context, text)])?;
+        }
+        Ok(())
+    }
+
+    /// Whether the output is a terminal, the Writable tells it with its
+    /// `isTTY` property.
+    fn is_tty(&self, context: &JSContext) -> bool {
+        let stream: JSValue = self.0.clone().into();
+        get_option(context, Some(&stream), "isTTY").map_or(false, |is_tty| is_tty.to_bool(context))
+    }
+}
+
+/// Write on a Writable, a noop 'error' listener is added while writing if
+/// there is none, to catch the errors emitted synchronously. The thrown
+/// errors are returned.
+fn write_ignoring_errors(
+    context: &JSContext,
+    stream: &JSObject,
+    text: &str,
+) -> Result<(), JSValue> {
+    let error = JSValue::string(context, "error");
+    let noop = JSValue::callback(context, Some(ignore_error));
+    // An output that isn't an EventEmitter has no listener to add.
+    let listened = call_method(context, stream, "listenerCount", &[error.clone()])
+        .and_then(|count| count.to_number(context))
+        .map_or(true, |count| count > 0.0);
+    if !listened {
+        call_method(context, stream, "once", &[error.clone(), noop.clone()])?;
+    }
+    let callback = bind(
+        context,
+        JSValue::callback(context, Some(on_write)),
+        stream.clone().into(),
+    );
+    let result = call_method(
+        context,
+        stream,
+        "write",
+        &[JSValue::string(context, text), callback],
+    );
+    if !listened {
+        call_method(context, stream, "removeListener", &[error, noop])?;
+    }
+    result.map(|_| ())
+}
+
+#[callback]
+/// Listener of the errors ignored by a console.
+fn ignore_error(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// Write callback of a console ignoring the errors, bound to the stream. The
+/// 'error' event is emitted after the callback, so a noop listener is added
+/// if there is none.
+fn on_write(
+    context: JSContext,
+    _function: JSObject,
+    this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let failed = arguments.first().map_or(false, |error| {
+        !error.is_undefined(&context) && !error.is_null(&context)
+    });
+    let error = JSValue::string(&context, "error");
+    if failed
+        && call_method(&context, &this, "listenerCount", &[error.clone()])?.to_number(&context)?
+            == 0.0
+    {
+        let noop = JSValue::callback(&context, Some(ignore_error));
+        call_method(&context, &this, "once", &[error, noop])?;
+    }
+    Ok(JSValue::undefined(&context))
+}
+
+/// Which output of the console a method prints on.
+#[derive(Clone, Copy)]
+enum Stream {
+    Out,
+    Err,
 }
 
 struct Console {
     stdout: Output,
     stderr: Output,
+    /// Current indentation, increased by `group()`.
+    group_indent: String,
+    /// Number of spaces added by each `group()`.
+    group_indentation: usize,
+    /// Whether the errors of the outputs are ignored.
+    ignore_errors: bool,
+    /// Whether the values are colored, None to color them on the terminals
+    /// (`colorMode: 'auto'`).
+    colors: Option<bool>,
+    /// The options of the values inspected, `inspectOptions`.
+    inspect_options: InspectOptions,
+    counts: HashMap<String, u64>,
+    timers: HashMap<String, Instant>,
 }
 
 impl Console {
-    fn log(&mut self, str: String) {}
+    fn new(stdout: Output, stderr: Output) -> Self {
+        Console {
+            stdout,
+            stderr,
+            group_indent: String::new(),
+            group_indentation: 2,
+            ignore_errors: true,
+            colors: None,
+            inspect_options: InspectOptions::default(),
+            counts: HashMap::new(),
+            timers: HashMap::new(),
+        }
+    }
+
+    fn try_from_object<'a>(
+        context: &JSContext,
+        object: &mut JSObject,
+    ) -> Result<&'a mut Console, JSValue> {
+        let object = object.try_as_mut_object_class(context, get_console_class())?;
+        let console: &mut Console = unsafe { &mut *object.get_private_data().unwrap() };
+        Ok(console)
+    }
+
+    /// The options to inspect the values printed on an output, colored if
+    /// it is.
+    fn inspect_options(&self, context: &JSContext, stream: Stream) -> InspectOptions {
+        let colors = self.colors.unwrap_or_else(|| match stream {
+            Stream::Out => should_colorize(self.stdout.is_tty(context)),
+            Stream::Err => should_colorize(self.stderr.is_tty(context)),
+        });
+        InspectOptions {
+            colors,
+            ..self.inspect_options.clone()
+        }
+    }
+
+    /// Format the arguments printed on an output, as `util.format`.
+    fn format(
+        &self,
+        context: &JSContext,
+        stream: Stream,
+        arguments: &[JSValue],
+    ) -> Result<String, JSValue> {
+        format_with_options(context, &self.inspect_options(context, stream), arguments)
+    }
+
+    /// Print a line, indented by the current groups.
+    fn print(&mut self, context: &JSContext, stream: Stream, text: &str) -> Result<(), JSValue> {
+        let mut line = String::new();
+        if self.group_indent.is_empty() {
+            line.push_str(text);
+        } else {
+            line.push_str(&self.group_indent);
+            line.push_str(&text.replace('\n', &format!("\n{}", self.group_indent)));
+        }
+        line.push('\n');
+        match stream {
+            Stream::Out => self.stdout.write(context, &line, self.ignore_errors),
+            Stream::Err => self.stderr.write(context, &line, self.ignore_errors),
+        }
+    }
+
+    /// Print a process warning, as NodeJS does for a misused label.
+    fn warning(&mut self, context: &JSContext, message: &str) -> Result<(), JSValue> {
+        let text = format!("(needjs) Warning: {message}\n");
+        self.stderr.write(context, &text, self.ignore_errors)
+    }
+}
+
+/// Get ConsoleClass
+fn get_console_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create(
+        "Console",
+        None,
+        Some(destructor)
+    ))
+}
+
+unsafe extern "C" fn destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<Console>::from_raw(object.get_private_data().unwrap()));
 }
 
+/// The label argument of count and time, 'default' if not given.
+fn label(context: &JSContext, arguments: &[JSValue]) -> Result<String, JSValue> {
+    match arguments.first() {
+        Some(label) if !label.is_undefined(context) => Ok(label.to_js_string(context)?.to_string()),
+        _ => Ok(String::from("default")),
+    }
+}
+
+/// Format a duration of `console.timeEnd` as NodeJS: `1.234ms`, `1.234s`,
+/// `1:02.345 (m:ss.mmm)` or `1:01:02.345 (h:mm:ss.mmm)`.
+fn format_time(milliseconds: f64) -> String {
+    const SECOND: f64 = 1000.0;
+    const MINUTE: f64 = 60.0 * SECOND;
+    const HOUR: f64 = 60.0 * MINUTE;
+    if milliseconds >= MINUTE {
+        let hours = (milliseconds / HOUR).floor();
+        let minutes = ((milliseconds % HOUR) / MINUTE).floor();
+        let seconds = format!("{:06.3}", (milliseconds % MINUTE) / SECOND);
+        return if hours > 0.0 {
+            format!("{hours}:{minutes:02}:{seconds} (h:mm:ss.mmm)")
+        } else {
+            format!("{minutes}:{seconds} (m:ss.mmm)")
+        };
+    }
+    if milliseconds >= SECOND {
+        return format!("{:.3}s", milliseconds / SECOND);
+    }
+    let milliseconds = format!("{milliseconds:.3}");
+    format!(
+        "{}ms",
+        milliseconds.trim_end_matches('0').trim_end_matches('.')
+    )
+}
+
+/// A cell of `console.table`: the value inspected at depth 0, the objects
+/// with more than two properties are abbreviated.
+fn table_cell(context: &JSContext, value: &JSValue, colors: bool) -> Result<String, JSValue> {
+    let large_object = value.is_object(context)
+        && type_tag(context, value)? != "Array"
+        && object_keys(context, value)?.len() > 2;
+    let options = InspectOptions {
+        depth: if large_object { -1.0 } else { 0.0 },
+        max_array_length: 3,
+        break_length: f64::INFINITY,
+        colors,
+        ..InspectOptions::default()
+    };
+    inspect(context, value, &options)
+}
+
+/// Draw the table of `console.table`, the cells are left aligned. The wide
+/// characters take two columns.
+fn render_table(head: &[String], columns: &[Vec<Option<String>>]) -> String {
+    let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let mut widths: Vec<usize> = head.iter().map(|name| string_width(name)).collect();
+    for (width, column) in widths.iter_mut().zip(columns) {
+        for cell in column.iter().flatten() {
+            *width = (*width).max(string_width(cell));
+        }
+    }
+    let render_row = |cells: Vec<&str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - string_width(cell))))
+            .collect();
+        format!("│ {} │\n", cells.join(" │ "))
+    };
+    let divider: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+    let mut table = format!("┌{}┐\n", divider.join("┬"));
+    table.push_str(&render_row(head.iter().map(String::as_str).collect()));
+    table.push_str(&format!("├{}┤\n", divider.join("┼")));
+    for row in 0..rows {
+        table.push_str(&render_row(
+            columns
+                .iter()
+                .map(|column| column.get(row).and_then(Option::as_deref).unwrap_or(""))
+                .collect(),
+        ));
+    }
+    table.push_str(&format!("└{}┘", divider.join("┴")));
+    table
+}
+
+/// Get `Object.keys(value)`.
+fn object_keys(context: &JSContext, value: &JSValue) -> Result<Vec<String>, JSValue> {
+    let keys = call_global(context, "Object.keys", &[value.clone()])?.to_object(context)?;
+    let length = keys.get_property(context, "length")?.to_number(context)? as usize;
+    (0..length)
+        .map(|index| {
+            Ok(keys
+                .get_property(context, &index.to_string())?
+                .to_js_string(context)?
+                .to_string())
+        })
+        .collect()
+}
+
+/// Define the console methods that format their arguments and print them on
+/// one of the outputs.
+macro_rules! console_print {
+    ($( $name: ident => $stream: ident ),*) => {
+        $(
+        #[callback]
+        fn $name(
+            context: JSContext,
+            _function: JSObject,
+            mut this: JSObject,
+            arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            let console = Console::try_from_object(&context, &mut this)?;
+            let text = console.format(&context, Stream::$stream, arguments)?;
+            console.print(&context, Stream::$stream, &text)?;
+            Ok(JSValue::undefined(&context))
+        }
+        )*
+    };
+}
+
+console_print!(
+    log => Out,
+    info => Out,
+    debug => Out,
+    dirxml => Out,
+    error => Err,
+    warn => Err
+);
+
 #[callback]
-fn log(context: JSContext, _function: JSObject, _this: JSObject, arguments: &[JSValue]) {
-    println!("{}", format_parser(&context, arguments).unwrap().join(""));
+/// `console.dir(obj[, options])`.
+fn dir(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let value = match arguments.first() {
+        Some(value) => value.clone(),
+        None => JSValue::undefined(&context),
+    };
+    // The `[util.inspect.custom]` methods aren't used, unless asked.
+    let mut options = InspectOptions {
+        custom_inspect: false,
+        ..console.inspect_options(&context, Stream::Out)
+    };
+    if let Some(dir_options) = arguments.get(1).filter(|value| value.is_object(&context)) {
+        options.update(&context, dir_options)?;
+    }
+    let text = inspect(&context, &value, &options)?;
+    console.print(&context, Stream::Out, &text)?;
+    Ok(JSValue::undefined(&context))
 }
 
-fn make(context: &JSContext) -> JSObject<JSObjectGenericClass> {
-    let console_class = maybe_static!(JSClass, || JSClass::create(
-        "console",
-        Some(new_console),
-        None
-    ));
-    let mut console = console_class.make_object(context);
-    console
-        .set_property(context, "log", JSValue::callback(context, Some(log)))
-        .unwrap();
-    console
+#[callback]
+/// `console.trace([message][, ...args])`, prints 'Trace: message' and the
+/// stack on the standard error.
+fn trace(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let mut text = String::from("Trace");
+    if !arguments.is_empty() {
+        text.push_str(": ");
+        text.push_str(&console.format(&context, Stream::Err, arguments)?);
+    }
+    let stack = new_error(&context, "Error", "")
+        .get_property(&context, "stack")?
+        .to_js_string(&context)?
+        .to_string();
+    for frame in stack.lines().filter(|frame| !frame.is_empty()) {
+        text.push_str("\n    at ");
+        text.push_str(frame);
+    }
+    console.print(&context, Stream::Err, &text)?;
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// `console.assert(value[, ...message])`, prints 'Assertion failed' on the
+/// standard error if the value is falsy.
+fn assert(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    if arguments
+        .first()
+        .map_or(false, |value| value.to_bool(&context))
+    {
+        return Ok(JSValue::undefined(&context));
+    }
+    let message = arguments.get(1..).unwrap_or_default();
+    let text = match message.first() {
+        None => String::from("Assertion failed"),
+        // The message may be a format string.
+        Some(first) if first.is_string(&context) => {
+            let text = console.format(&context, Stream::Err, message)?;
+            format!("Assertion failed: {text}")
+        }
+        Some(_) => {
+            let text = console.format(&context, Stream::Err, message)?;
+            format!("Assertion failed {text}")
+        }
+    };
+    console.print(&context, Stream::Err, &text)?;
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// `console.count([label])`, prints how many times it was called with the
+/// label.
+fn count(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let label = label(&context, arguments)?;
+    let count = console.counts.entry(label.clone()).or_insert(0);
+    *count += 1;
+    let text = format!("{label}: {count}");
+    console.print(&context, Stream::Out, &text)?;
+    Ok(JSValue::undefined(&context))
 }
 
-#[constructor]
-fn new_console(context: JSContext, _constructor: JSObject, arguments: Vec<JSValue>) -> JSValue {
-    if arguments[0].is_object_of_class(&context, get_fs_write_stream_class()) {}
-    todo!()
+#[callback]
+/// `console.countReset([label])`.
+fn count_reset(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let label = label(&context, arguments)?;
+    if console.counts.remove(&label).is_none() {
+        console.warning(&context, &format!("Count for '{label}' does not exist"))?;
+    }
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// `console.time([label])`, starts a timer.
+fn time(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let label = label(&context, arguments)?;
+    if console.timers.contains_key(&label) {
+        let message = format!("Label '{label}' already exists for console.time()");
+        console.warning(&context, &message)?;
+    } else {
+        console.timers.insert(label, Instant::now());
+    }
+    Ok(JSValue::undefined(&context))
+}
+
+/// Print the time elapsed since `console.time(label)` followed by `data`.
+/// The timer is removed by `timeEnd`.
+fn print_time(
+    context: &JSContext,
+    this: &mut JSObject,
+    arguments: &[JSValue],
+    method: &str,
+    end: bool,
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(context, this)?;
+    let label = label(context, arguments)?;
+    let start = match console.timers.get(&label) {
+        Some(start) => *start,
+        None => {
+            let message = format!("No such label '{label}' for console.{method}()");
+            console.warning(context, &message)?;
+            return Ok(JSValue::undefined(context));
+        }
+    };
+    if end {
+        console.timers.remove(&label);
+    }
+    let elapsed = format_time(start.elapsed().as_secs_f64() * 1000.0);
+    let mut format_arguments = vec![
+        JSValue::string(context, "%s: %s"),
+        JSValue::string(context, label),
+        JSValue::string(context, elapsed),
+    ];
+    format_arguments.extend(arguments.iter().skip(1).cloned());
+    let text = console.format(context, Stream::Out, &format_arguments)?;
+    console.print(context, Stream::Out, &text)?;
+    Ok(JSValue::undefined(context))
+}
+
+#[callback]
+/// `console.timeEnd([label])`, prints the elapsed time and stops the timer.
+fn time_end(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    print_time(
+        &context,
+        &mut this,
+        &arguments[..arguments.len().min(1)],
+        "timeEnd",
+        true,
+    )
+}
+
+#[callback]
+/// `console.timeLog([label][, ...data])`, prints the elapsed time.
+fn time_log(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    print_time(&context, &mut this, arguments, "timeLog", false)
+}
+
+#[callback]
+/// `console.group([...label])` and `console.groupCollapsed()`, print the
+/// label and indent the next lines.
+fn group(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    if !arguments.is_empty() {
+        let text = console.format(&context, Stream::Out, arguments)?;
+        console.print(&context, Stream::Out, &text)?;
+    }
+    let indentation = " ".repeat(console.group_indentation);
+    console.group_indent.push_str(&indentation);
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// `console.groupEnd()`.
+fn group_end(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let length = console
+        .group_indent
+        .len()
+        .saturating_sub(console.group_indentation);
+    console.group_indent.truncate(length);
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// `console.clear()`, only when the standard output is a terminal.
+fn clear(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let dumb = std::env::var("TERM").map_or(false, |term| term == "dumb");
+    if console.stdout.is_tty(&context) && !dumb {
+        // Move the cursor home and clear the screen down.
+        console
+            .stdout
+            .write(&context, "\x1b[1;1H\x1b[0J", console.ignore_errors)?;
+    }
+    Ok(JSValue::undefined(&context))
+}
+
+/// The head of a table, from the names of its columns.
+fn table_head(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
 }
 
+/// The index column of the tables of Maps and Sets.
+fn iteration_indexes(length: usize) -> Vec<Option<String>> {
+    (0..length).map(|index| Some(index.to_string())).collect()
+}
+
+#[callback]
+/// `console.table(tabularData[, properties])`: a column for the index, one
+/// per property of the rows (or per name of `properties`) and 'Values' for
+/// the rows that aren't objects. Maps have a 'Key' and a 'Values' column,
+/// Sets a 'Values' column.
+fn table(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let console = Console::try_from_object(&context, &mut this)?;
+    let colors = console.inspect_options(&context, Stream::Out).colors;
+    let properties = match arguments.get(1) {
+        Some(properties) if !properties.is_undefined(&context) => {
+            if !call_global(&context, "Array.isArray", &[properties.clone()])?.to_bool(&context) {
+                return Err(invalid_arg_type(
+                    &context,
+                    "properties",
+                    "an instance of Array",
+                ));
+            }
+            let names = array_values(&context, properties)?
+                .iter()
+                .map(|name| Ok(name.to_js_string(&context)?.to_string()))
+                .collect::<Result<Vec<String>, JSValue>>()?;
+            Some(names)
+        }
+        _ => None,
+    };
+    let data = match arguments.first() {
+        Some(data) if data.is_object(&context) => data.clone(),
+        _ => {
+            let text =
+                console.format(&context, Stream::Out, &arguments[..arguments.len().min(1)])?;
+            console.print(&context, Stream::Out, &text)?;
+            return Ok(JSValue::undefined(&context));
+        }
+    };
+    let cells = |values: &[JSValue]| -> Result<Vec<Option<String>>, JSValue> {
+        values
+            .iter()
+            .map(|value| Ok(Some(table_cell(&context, value, colors)?)))
+            .collect()
+    };
+    let (head, columns) = match type_tag(&context, &data)?.as_str() {
+        "Map" => {
+            let mut keys = vec![];
+            let mut values = vec![];
+            for entry in array_values(&context, &data)? {
+                let entry = entry.to_object(&context)?;
+                keys.push(entry.get_property(&context, "0")?);
+                values.push(entry.get_property(&context, "1")?);
+            }
+            (
+                table_head(&["(iteration index)", "Key", "Values"]),
+                vec![
+                    iteration_indexes(keys.len()),
+                    cells(&keys)?,
+                    cells(&values)?,
+                ],
+            )
+        }
+        "Set" => {
+            let values = array_values(&context, &data)?;
+            (
+                table_head(&["(iteration index)", "Values"]),
+                vec![iteration_indexes(values.len()), cells(&values)?],
+            )
+        }
+        _ => {
+            let data_object = data.to_object(&context)?;
+            let indexes = object_keys(&context, &data)?;
+            let mut named: Vec<(String, Vec<Option<String>>)> = vec![];
+            let mut values: Vec<Option<String>> = vec![];
+            for (row, index) in indexes.iter().enumerate() {
+                let item = data_object.get_property(&context, index)?;
+                let primitive = !item.is_object(&context);
+                if primitive && properties.is_none() {
+                    values.resize(row, None);
+                    values.push(Some(table_cell(&context, &item, colors)?));
+                    continue;
+                }
+                let keys = match &properties {
+                    Some(properties) => properties.clone(),
+                    None => object_keys(&context, &item)?,
+                };
+                for key in keys {
+                    let column = match named.iter().position(|(name, _)| *name == key) {
+                        Some(column) => column,
+                        None => {
+                            named.push((key.clone(), vec![]));
+                            named.len() - 1
+                        }
+                    };
+                    let cell = if primitive || !has_own_property(&context, &item, &key)? {
+                        String::new()
+                    } else {
+                        let value = item.to_object(&context)?.get_property(&context, &key)?;
+                        table_cell(&context, &value, colors)?
+                    };
+                    let (_, column) = &mut named[column];
+                    column.resize(row, None);
+                    column.push(Some(cell));
+                }
+            }
+            // The columns are the keys of an object: the indexes come first.
+            named.sort_by_key(|(name, _)| {
+                if is_array_index(name) {
+                    (0, name.parse::<u32>().unwrap())
+                } else {
+                    (1, 0)
+                }
+            });
+            let mut head = table_head(&["(index)"]);
+            let mut columns = vec![indexes.iter().cloned().map(Some).collect()];
+            for (name, column) in &named {
+                head.push(name.clone());
+                columns.push(column.clone());
+            }
+            if !values.is_empty() {
+                head.push(String::from("Values"));
+                columns.push(values);
+            }
+            (head, columns)
+        }
+    };
+    console.print(&context, Stream::Out, &render_table(&head, &columns))?;
+    Ok(JSValue::undefined(&context))
+}
+
+/// Create a console object, with its methods bound to it.
+fn make(context: &JSContext, console: Console) -> JSObject<JSObjectGenericClass> {
+    let mut object = get_console_class().make_object(context);
+    if object.set_private_data(console).is_err() {
+        panic!("cannot set private data to console");
+    }
+    let this: JSValue = object.clone().into();
+    macro_rules! method {
+        ($( $name: literal => $callback: ident ),*) => {
+            $(
+            object
+                .set_property(
+                    context,
+                    $name,
+                    bind(context, JSValue::callback(context, Some($callback)), this.clone()),
+                )
+                .unwrap();
+            )*
+        };
+    }
+    method!(
+        "log" => log,
+        "info" => info,
+        "debug" => debug,
+        "error" => error,
+        "warn" => warn,
+        "trace" => trace,
+        "assert" => assert,
+        "count" => count,
+        "countReset" => count_reset,
+        "time" => time,
+        "timeEnd" => time_end,
+        "timeLog" => time_log,
+        "group" => group,
+        "groupCollapsed" => group,
+        "groupEnd" => group_end,
+        "dir" => dir,
+        "dirxml" => dirxml,
+        "clear" => clear,
+        "table" => table
+    );
+    object
+}
+
+/// Read a stream argument of `new Console()`: it must have a write method.
+fn writable_argument(
+    context: &JSContext,
+    stream: Option<JSValue>,
+    name: &str,
+) -> Result<Output, JSValue> {
+    match stream {
+        Some(stream)
+            if get_option(context, Some(&stream), "write")
+                .map_or(false, |write| write.is_object(context)) =>
+        {
+            Ok(Output(stream.into_protected_object(context)))
+        }
+        _ => Err(code_error(
+            context,
+            "TypeError",
+            "ERR_CONSOLE_WRITABLE_STREAM",
+            &format!("Console expects a writable stream instance for {name}"),
+        )),
+    }
+}
+
+/// Read the `colorMode` and `inspectOptions` options of `new Console()`
+/// into the console. The `colors` of the inspect options replace the color
+/// mode, they can't be given together.
+fn color_options(
+    context: &JSContext,
+    options: Option<&JSValue>,
+    console: &mut Console,
+) -> Result<(), JSValue> {
+    if let Some(mode) = get_option(context, options, "colorMode") {
+        let valid = if mode.is_string(context) {
+            mode.to_js_string(context)?.to_string() == "auto"
+        } else {
+            [true, false]
+                .into_iter()
+                .any(|value| same_value(context, mode.clone(), JSValue::boolean(context, value)))
+        };
+        if !valid {
+            let mut received = mode.to_js_string(context)?.to_string();
+            if mode.is_string(context) {
+                received = format!("'{received}'");
+            }
+            return Err(invalid_arg_value(
+                context,
+                "colorMode",
+                &format!("must be one of: 'auto', true, false. Received {received}"),
+            ));
+        }
+        if !mode.is_string(context) {
+            console.colors = Some(mode.to_bool(context));
+        }
+    }
+    if let Some(inspect_options) = get_option(context, options, "inspectOptions") {
+        if !inspect_options.is_object(context) {
+            return Err(invalid_arg_type(
+                context,
+                "options.inspectOptions",
+                "of type object",
+            ));
+        }
+        if let Some(colors) = get_option(context, Some(&inspect_options), "colors") {
+            if get_option(context, options, "colorMode").is_some() {
+                return Err(code_error(
+                    context,
+                    "TypeError",
+                    "ERR_INCOMPATIBLE_OPTION_PAIR",
+                    "Option \"options.inspectOptions.color\" cannot be used in combination with option \"colorMode\"",
+                ));
+            }
+            console.colors = Some(colors.to_bool(context));
+        }
+        console.inspect_options.update(context, &inspect_options)?;
+    }
+    Ok(())
+}
+
+/// Read the `groupIndentation` option of `new Console()`, 2 by default.
+fn group_indentation_option(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<usize, JSValue> {
+    let indentation = match get_option(context, options, "groupIndentation") {
+        Some(value) if value.is_number(context) => value.to_number(context)?,
+        Some(_) => {
+            return Err(invalid_arg_type(
+                context,
+                "groupIndentation",
+                "of type number",
+            ))
+        }
+        None => return Ok(2),
+    };
+    let range = if indentation.fract() != 0.0 || !indentation.is_finite() {
+        "an integer"
+    } else if !(0.0..=1000.0).contains(&indentation) {
+        ">= 0 && <= 1000"
+    } else {
+        return Ok(indentation as usize);
+    };
+    Err(code_error(
+        context,
+        "RangeError",
+        "ERR_OUT_OF_RANGE",
+        &format!(
+            "The value of \"groupIndentation\" is out of range. It must be {range}. Received {indentation}"
+        ),
+    ))
+}
+
+#[callback]
+/// `new Console(stdout[, stderr][, ignoreErrors])` or `new Console(options)`,
+/// called by the `Console` function of `js/console.js`.
+///
+/// The options are `stdout`, `stderr` (stdout by default), `ignoreErrors`
+/// (true by default), `colorMode`, `inspectOptions` and `groupIndentation`.
+/// The outputs are any object with a `write` method, like a Writable.
+fn create(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let first = arguments.first();
+    // Positional arguments if the first one is a stream.
+    let positional = first.map_or(true, |first| {
+        !first.to_bool(&context) || get_option(&context, Some(first), "write").is_some()
+    });
+    let (stdout, stderr, ignore_errors, options) = if positional {
+        (
+            first.cloned(),
+            arguments.get(1).cloned(),
+            arguments.get(2).cloned(),
+            None,
+        )
+    } else {
+        (
+            get_option(&context, first, "stdout"),
+            get_option(&context, first, "stderr"),
+            get_option(&context, first, "ignoreErrors"),
+            first,
+        )
+    };
+    let stderr = match stderr {
+        Some(stderr) if !stderr.is_undefined(&context) => Some(stderr),
+        _ => stdout.clone(),
+    };
+    let stdout = writable_argument(&context, stdout, "stdout")?;
+    let stderr = writable_argument(&context, stderr, "stderr")?;
+    let mut console = Console::new(stdout, stderr);
+    color_options(&context, options, &mut console)?;
+    console.group_indentation = group_indentation_option(&context, options)?;
+    console.ignore_errors = match ignore_errors {
+        Some(ignore_errors) if !ignore_errors.is_undefined(&context) => {
+            ignore_errors.to_bool(&context)
+        }
+        _ => true,
+    };
+    Ok(make(&context, console).into())
+}
+
+#[callback]
+/// `Console[Symbol.hasInstance]`: whether the value is a console.
+fn is_console(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let is_console = match arguments.first() {
+        Some(value) if value.is_object(&context) => {
+            let mut object = value.to_object(&context)?;
+            Console::try_from_object(&context, &mut object).is_ok()
+        }
+        _ => false,
+    };
+    Ok(JSValue::boolean(&context, is_console))
+}
+
+/// Get the `Console` function. The script is evaluated on the first call.
+fn console_constructor(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let constructor = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    constructor
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/console.js"), 1)
+                .expect("cannot evaluate the Console class")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(
+                    context,
+                    None,
+                    &[
+                        JSValue::callback(context, Some(create)),
+                        JSValue::callback(context, Some(is_console)),
+                    ],
+                )
+                .expect("cannot create the Console class")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Define the global `console`, printing on `process.stdout` and
+/// `process.stderr`, and `console.Console`.
 pub fn init(context: &mut JSContext) {
     let global = &mut context.get_global_object();
-    // Define classes
-    let console = make(context);
+    let stdout: JSValue = stdout(context).into();
+    let stderr: JSValue = stderr(context).into();
+    let mut console = make(
+        context,
+        Console::new(
+            Output(stdout.into_protected_object(context)),
+            Output(stderr.into_protected_object(context)),
+        ),
+    );
+    console
+        .set_property(context, "Console", console_constructor(context).into())
+        .unwrap();
     global
         .set_property(context, "console", console.into())
         .unwrap();
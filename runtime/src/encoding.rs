@@ -0,0 +1,318 @@
+//! Conversions between javascript strings and bytes, following the NodeJS
+//! encoding names (`'utf8'`, `'latin1'`, `'hex'`, `'base64'`...).
+//!
+//! The runtime doesn't have `Buffer` yet: raw bytes are given to javascript
+//! as an `Uint8Array`, and any `ArrayBufferView` is accepted as input.
+//! Their memory is shared with javascript through the typed array API of
+//! JavaScriptCore, without going through the elements one by one.
+
+use std::{ffi::c_void, ptr, slice};
+
+use rusty_jsc::{
+    private::{
+        JSObjectGetTypedArrayByteLength, JSObjectGetTypedArrayByteOffset,
+        JSObjectGetTypedArrayBytesPtr, JSObjectMakeTypedArrayWithBytesNoCopy,
+        JSTypedArrayType_kJSTypedArrayTypeUint8Array,
+    },
+    JSContext, JSObject, JSValue,
+};
+
+use crate::{
+    errors::{invalid_arg_type, invalid_arg_value},
+    js_utils::call_global,
+    options::get_encoding,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Latin1,
+    Ascii,
+    Hex,
+    Base64,
+    Base64Url,
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl Encoding {
+    /// Parse an encoding name, case insensitive as in NodeJS. Returns None
+    /// for `'buffer'`, meaning the raw bytes.
+    pub fn parse(context: &JSContext, name: &str) -> Result<Option<Encoding>, JSValue> {
+        Ok(Some(match name.to_ascii_lowercase().as_str() {
+            "utf8" | "utf-8" => Encoding::Utf8,
+            "utf16le" | "utf-16le" | "ucs2" | "ucs-2" => Encoding::Utf16Le,
+            "latin1" | "binary" => Encoding::Latin1,
+            "ascii" => Encoding::Ascii,
+            "hex" => Encoding::Hex,
+            "base64" => Encoding::Base64,
+            "base64url" => Encoding::Base64Url,
+            "buffer" => return Ok(None),
+            _ => {
+                return Err(invalid_arg_value(
+                    context,
+                    "encoding",
+                    &format!("is invalid encoding. Received '{name}'"),
+                ))
+            }
+        }))
+    }
+
+    /// The normalized name, as NodeJS reports it (`'UTF-8'` is `'utf8'`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf8",
+            Encoding::Utf16Le => "utf16le",
+            Encoding::Latin1 => "latin1",
+            Encoding::Ascii => "ascii",
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+        }
+    }
+
+    /// Decode bytes into a string.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Encoding::Utf16Le => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            Encoding::Latin1 => bytes.iter().map(|&byte| byte as char).collect(),
+            Encoding::Ascii => bytes.iter().map(|&byte| (byte & 0x7f) as char).collect(),
+            Encoding::Hex => bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+            Encoding::Base64 => base64_encode(bytes, BASE64, true),
+            Encoding::Base64Url => base64_encode(bytes, BASE64_URL, false),
+        }
+    }
+
+    /// Encode a string into bytes. As NodeJS, invalid input is skipped
+    /// rather than reported.
+    pub fn encode(self, string: &str) -> Vec<u8> {
+        match self {
+            Encoding::Utf8 => string.as_bytes().to_vec(),
+            Encoding::Utf16Le => string
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect(),
+            Encoding::Latin1 | Encoding::Ascii => {
+                string.encode_utf16().map(|unit| unit as u8).collect()
+            }
+            Encoding::Hex => {
+                let digits: Vec<u8> = string
+                    .bytes()
+                    .map_while(|digit| (digit as char).to_digit(16).map(|value| value as u8))
+                    .collect();
+                digits
+                    .chunks_exact(2)
+                    .map(|pair| pair[0] << 4 | pair[1])
+                    .collect()
+            }
+            // Both alphabets are accepted by the two decoders.
+            Encoding::Base64 | Encoding::Base64Url => base64_decode(string),
+        }
+    }
+
+    /// Length of the longest prefix of `bytes` that decodes without splitting
+    /// a character. Used to decode a stream chunk by chunk, the rest is kept
+    /// for the next chunk.
+    pub fn complete_len(self, bytes: &[u8]) -> usize {
+        match self {
+            Encoding::Utf8 => {
+                for back in 1..=bytes.len().min(3) {
+                    let byte = bytes[bytes.len() - back];
+                    if byte & 0xc0 == 0x80 {
+                        // A continuation byte, the sequence starts before.
+                        continue;
+                    }
+                    let needed = match byte {
+                        0xf0.. => 4,
+                        0xe0.. => 3,
+                        0xc0.. => 2,
+                        _ => 1,
+                    };
+                    return if needed > back {
+                        bytes.len() - back
+                    } else {
+                        bytes.len()
+                    };
+                }
+                bytes.len()
+            }
+            Encoding::Utf16Le => bytes.len() - bytes.len() % 2,
+            Encoding::Base64 | Encoding::Base64Url => bytes.len() - bytes.len() % 3,
+            _ => bytes.len(),
+        }
+    }
+}
+
+fn base64_encode(bytes: &[u8], alphabet: &[u8; 64], padding: bool) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(alphabet[(group >> (18 - index * 6) & 0x3f) as usize] as char);
+            } else if padding {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn base64_decode(string: &str) -> Vec<u8> {
+    let mut decoded = vec![];
+    let (mut group, mut bits) = (0u32, 0);
+    for byte in string.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            _ => continue,
+        };
+        group = group << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((group >> bits) as u8);
+        }
+    }
+    decoded
+}
+
+/// Read the encoding of an API accepting `options` as an object or as a
+/// string. Returns None if it's missing or `'buffer'`.
+pub fn encoding_option(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<Option<Encoding>, JSValue> {
+    match get_encoding(context, options)? {
+        Some(name) => Encoding::parse(context, &name),
+        None => Ok(None),
+    }
+}
+
+/// Give bytes to javascript, as a string if an encoding is set, as an
+/// `Uint8Array` otherwise, which takes over their memory.
+pub fn bytes_to_value(context: &JSContext, bytes: Vec<u8>, encoding: Option<Encoding>) -> JSValue {
+    match encoding {
+        Some(encoding) => JSValue::string(context, encoding.decode(&bytes)),
+        None => new_uint8_array(context, bytes.into_boxed_slice()),
+    }
+}
+
+/// Free the memory of an `Uint8Array` made by `new_uint8_array`, called by
+/// JavaScriptCore once the array is collected.
+unsafe extern "C" fn free_bytes(bytes: *mut c_void, length: *mut c_void) {
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+        bytes as *mut u8,
+        length as usize,
+    )));
+}
+
+/// Create an `Uint8Array` owning `bytes`, without copying them.
+fn new_uint8_array(context: &JSContext, bytes: Box<[u8]>) -> JSValue {
+    let length = bytes.len();
+    let bytes = Box::into_raw(bytes) as *mut u8;
+    let mut exception = ptr::null();
+    // The length is given back to `free_bytes` as the deallocator context.
+    let array = unsafe {
+        JSObjectMakeTypedArrayWithBytesNoCopy(
+            context.get_ref(),
+            JSTypedArrayType_kJSTypedArrayTypeUint8Array,
+            bytes as *mut c_void,
+            length,
+            Some(free_bytes),
+            length as *mut c_void,
+            &mut exception,
+        )
+    };
+    if array.is_null() {
+        panic!("cannot create an Uint8Array");
+    }
+    JSObject::from(array).into()
+}
+
+/// True if `value` is an `ArrayBufferView` (a typed array or a DataView).
+pub fn is_array_buffer_view(context: &JSContext, value: &JSValue) -> bool {
+    value.is_object(context)
+        && call_global(context, "ArrayBuffer.isView", &[value.clone()])
+            .map_or(false, |is_view| is_view.to_bool(context))
+}
+
+/// Run `action` on the memory of an `ArrayBufferView`, whatever its element
+/// type. JavaScriptCore gives the start of the whole buffer, the bytes of the
+/// view are at its `byteOffset`.
+fn with_view_memory<T>(
+    context: &JSContext,
+    view: &JSValue,
+    action: impl FnOnce(&mut [u8]) -> T,
+) -> T {
+    let object = view.to_object(context).unwrap();
+    let mut exception = ptr::null();
+    // The pointer is null for a detached buffer, which has no bytes. Its
+    // memory is pinned once the pointer is taken, so it stays valid meanwhile.
+    unsafe {
+        let buffer =
+            JSObjectGetTypedArrayBytesPtr(context.get_ref(), object.get_ref(), &mut exception);
+        if buffer.is_null() {
+            return action(&mut []);
+        }
+        let offset =
+            JSObjectGetTypedArrayByteOffset(context.get_ref(), object.get_ref(), &mut exception);
+        let length =
+            JSObjectGetTypedArrayByteLength(context.get_ref(), object.get_ref(), &mut exception);
+        action(slice::from_raw_parts_mut(
+            (buffer as *mut u8).add(offset),
+            length,
+        ))
+    }
+}
+
+/// Read the bytes of an `ArrayBufferView`, whatever its element type.
+pub fn view_bytes(context: &JSContext, view: &JSValue) -> Vec<u8> {
+    with_view_memory(context, view, |memory| memory.to_vec())
+}
+
+/// Read a `data` argument given as a string, encoded with `encoding`
+/// (UTF-8 by default), or as an `ArrayBufferView`.
+pub fn data_argument(
+    context: &JSContext,
+    value: Option<&JSValue>,
+    encoding: Option<Encoding>,
+) -> Result<Vec<u8>, JSValue> {
+    match value {
+        Some(value) if value.is_string(context) => {
+            let string = value.to_js_string(context).unwrap().to_string();
+            Ok(encoding.unwrap_or(Encoding::Utf8).encode(&string))
+        }
+        Some(value) if is_array_buffer_view(context, value) => Ok(view_bytes(context, value)),
+        _ => Err(invalid_arg_type(
+            context,
+            "data",
+            "of type string or an instance of Buffer, TypedArray, or DataView",
+        )),
+    }
+}
+
+/// Copy `bytes` into an `ArrayBufferView`, starting at the byte `offset`.
+/// The bytes past its end are dropped.
+pub fn write_view_bytes(context: &JSContext, view: &JSValue, offset: usize, bytes: &[u8]) {
+    with_view_memory(context, view, |memory| {
+        let start = offset.min(memory.len());
+        let length = bytes.len().min(memory.len() - start);
+        memory[start..start + length].copy_from_slice(&bytes[..length]);
+    })
+}
@@ -0,0 +1,165 @@
+//! Javascript errors shaped like the NodeJS ones.
+//!
+//! System errors (a failing syscall) carry the `errno`, `code`, `syscall`,
+//! `path` and `dest` properties. Internal errors, like a bad argument given to
+//! an API, only carry a `code` such as `ERR_INVALID_ARG_TYPE`.
+
+use std::io;
+
+use rusty_jsc::{JSContext, JSObject, JSValue};
+
+use crate::stdio;
+
+/// Returns the libuv name and description of an errno value.
+fn errno_description(errno: i32) -> (&'static str, &'static str) {
+    match errno {
+        libc::EPERM => ("EPERM", "operation not permitted"),
+        libc::ENOENT => ("ENOENT", "no such file or directory"),
+        libc::EIO => ("EIO", "i/o error"),
+        libc::EBADF => ("EBADF", "bad file descriptor"),
+        libc::EAGAIN => ("EAGAIN", "resource temporarily unavailable"),
+        libc::ENOMEM => ("ENOMEM", "not enough memory"),
+        libc::EACCES => ("EACCES", "permission denied"),
+        libc::EBUSY => ("EBUSY", "resource busy or locked"),
+        libc::EEXIST => ("EEXIST", "file already exists"),
+        libc::EXDEV => ("EXDEV", "cross-device link not permitted"),
+        libc::ENOTDIR => ("ENOTDIR", "not a directory"),
+        libc::EISDIR => ("EISDIR", "illegal operation on a directory"),
+        libc::EINVAL => ("EINVAL", "invalid argument"),
+        libc::ENFILE => ("ENFILE", "file table overflow"),
+        libc::EMFILE => ("EMFILE", "too many open files"),
+        libc::ETXTBSY => ("ETXTBSY", "text file is busy"),
+        libc::EFBIG => ("EFBIG", "file too large"),
+        libc::ENOSPC => ("ENOSPC", "no space left on device"),
+        libc::EROFS => ("EROFS", "read-only file system"),
+        libc::EMLINK => ("EMLINK", "too many links"),
+        libc::EPIPE => ("EPIPE", "broken pipe"),
+        libc::ENAMETOOLONG => ("ENAMETOOLONG", "name too long"),
+        libc::ENOSYS => ("ENOSYS", "function not implemented"),
+        libc::ENOTEMPTY => ("ENOTEMPTY", "directory not empty"),
+        libc::ELOOP => ("ELOOP", "too many symbolic links encountered"),
+        libc::EOPNOTSUPP => ("ENOTSUP", "operation not supported on socket"),
+        libc::ETIMEDOUT => ("ETIMEDOUT", "connection timed out"),
+        libc::ECANCELED => ("ECANCELED", "operation canceled"),
+        _ => ("UNKNOWN", "unknown error"),
+    }
+}
+
+/// Create a new javascript error object calling the global `constructor`
+/// (Error, TypeError, RangeError...) with the given message.
+pub fn new_error(context: &JSContext, constructor: &str, message: &str) -> JSObject {
+    context
+        .get_global_object()
+        .get_property(context, constructor)
+        .unwrap()
+        .to_object(context)
+        .unwrap()
+        .call_as_function(context, None, &[JSValue::string(context, message)])
+        .unwrap()
+        .to_object(context)
+        .unwrap()
+}
+
+/// Create an error with a NodeJS internal `code` property, like
+/// `ERR_INVALID_ARG_TYPE`. The message is formatted as Node does:
+/// `The "path" argument must be of type string`.
+pub fn code_error(context: &JSContext, constructor: &str, code: &str, message: &str) -> JSValue {
+    let mut error = new_error(context, constructor, message);
+    error
+        .set_property(context, "code", JSValue::string(context, code))
+        .unwrap();
+    error.into()
+}
+
+/// Shortcut for the very common `ERR_INVALID_ARG_TYPE` TypeError.
+pub fn invalid_arg_type(context: &JSContext, name: &str, expected: &str) -> JSValue {
+    code_error(
+        context,
+        "TypeError",
+        "ERR_INVALID_ARG_TYPE",
+        &format!("The \"{name}\" argument must be {expected}"),
+    )
+}
+
+/// Shortcut for the `ERR_INVALID_ARG_VALUE` TypeError.
+pub fn invalid_arg_value(context: &JSContext, name: &str, reason: &str) -> JSValue {
+    code_error(
+        context,
+        "TypeError",
+        "ERR_INVALID_ARG_VALUE",
+        &format!("The argument '{name}' {reason}"),
+    )
+}
+
+/// Create a system error from the result of a syscall.
+///
+/// ```text
+/// ENOENT: no such file or directory, open 'foo.txt'
+/// ```
+pub fn system_error(
+    context: &JSContext,
+    err: &io::Error,
+    syscall: &str,
+    path: Option<&str>,
+    dest: Option<&str>,
+) -> JSValue {
+    let errno = err.raw_os_error().unwrap_or(0);
+    let (code, description) = errno_description(errno);
+    let mut message = format!("{code}: {description}, {syscall}");
+    if let Some(path) = path {
+        message.push_str(&format!(" '{path}'"));
+    }
+    if let Some(dest) = dest {
+        message.push_str(&format!(" -> '{dest}'"));
+    }
+    let mut error = new_error(context, "Error", &message);
+    error
+        .set_property(context, "errno", JSValue::number(context, -errno as f64))
+        .unwrap();
+    error
+        .set_property(context, "code", JSValue::string(context, code))
+        .unwrap();
+    error
+        .set_property(context, "syscall", JSValue::string(context, syscall))
+        .unwrap();
+    if let Some(path) = path {
+        error
+            .set_property(context, "path", JSValue::string(context, path))
+            .unwrap();
+    }
+    if let Some(dest) = dest {
+        error
+            .set_property(context, "dest", JSValue::string(context, dest))
+            .unwrap();
+    }
+    error.into()
+}
+
+/// Build an `io::Error` from a raw errno, used when a check is done on the
+/// Rust side before reaching the syscall (EEXIST, EISDIR...).
+pub fn from_errno(errno: i32) -> io::Error {
+    io::Error::from_raw_os_error(errno)
+}
+
+/// Create the `AbortError` used when an operation is cancelled through an
+/// `AbortSignal`.
+pub fn abort_error(context: &JSContext) -> JSValue {
+    let mut error = new_error(context, "Error", "The operation was aborted");
+    error
+        .set_property(context, "name", JSValue::string(context, "AbortError"))
+        .unwrap();
+    error
+        .set_property(context, "code", JSValue::string(context, "ABORT_ERR"))
+        .unwrap();
+    error.into()
+}
+
+/// Print an error thrown by a javascript callback called from the event
+/// loop. It's uncaught, printed as the errors of the main script once the
+/// queued writes of the standard outputs are done.
+pub fn print_uncaught(context: &JSContext, error: &JSValue) {
+    stdio::flush();
+    if let Ok(text) = error.to_js_string(context) {
+        println!("{text}");
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::{
 };
 
 use maybe_static::maybe_static;
-use rusty_jsc::{JSContext, JSObject, JSPromise};
+use rusty_jsc::{JSObject, JSPromise};
 use tokio::sync::{
     mpsc::{self, UnboundedReceiver, UnboundedSender},
     oneshot::Sender,
@@ -12,10 +12,17 @@ use tokio::sync::{
 };
 
 use crate::{
+    fs_callback::{exec_fs_callback, FsCallbackAction},
+    fs_cp::{exec_cp, CpOptions},
+    fs_dir::{
+        exec_close_dir, exec_opendir, exec_read_dir_entry, DirOperation, DirRead, OpenDirOptions,
+    },
     fs_promise::*,
-    fs_write_stream::{
-        exec_close, exec_create_file, exec_write_str, FsWriteStreamCallbacks, WSFile,
+    fs_watch::{
+        exec_watch, exec_watch_file, exec_watch_next, StatWatchAction, WatchAction,
+        WatchIteratorState,
     },
+    stdio::{exec_stdio_write, StdioWriteAction},
     timeout_api::{exec_timeout, TimeoutAction},
 };
 
@@ -29,26 +36,56 @@ pub enum Action {
     ///
     /// Binded with fsPromise.access(path[,mode]) in javascript.
     AccessFileWithMode((String, JSObject<JSPromise>, u8)),
-    /// Commands the file creation in write only mode like Path::create does.
-    /// This action is currently used when JS calls a `fs.createWriteStream`.
+    /// Close an opened directory. Result of the javascript call of
+    /// `dir.close()`.
+    CloseDir(DirOperation, JSObject<JSPromise>),
+    /// Copy a tree of files (Source, Destination, Promise Object, Options).
+    ///
+    /// Binded with fsPromise.cp(src, dest[, options]) in javascript.
+    Copy((String, String, JSObject<JSPromise>, CpOptions)),
+    /// Run the blocking work of a callback call and invoke its callback.
+    ///
+    /// Binded with the callback API of fs, like fs.readFile(path, callback),
+    /// in javascript.
+    FsCallback(FsCallbackAction),
+    /// Run the blocking work of a promise call and settle its promise.
     ///
-    /// Note: Look at fs_write_stream file for further documentation.
-    CreateWSFile(String, Arc<Mutex<WSFile>>),
+    /// Binded with the calls of fsPromise sharing their implementation with
+    /// fs, like fsPromise.mkdir(path[, options]), in javascript.
+    FsPromise(FsPromiseAction),
+    /// Open a directory (Path, Promise Object, Options).
+    ///
+    /// Binded with fsPromise.opendir(path[, options]) in javascript.
+    OpenDir((String, JSObject<JSPromise>, OpenDirOptions)),
     /// Open a file (Filename/path, Promise Object)
     OpenFile((String, JSObject<JSPromise>)),
+    /// Read the next entry of an opened directory. Result of the javascript
+    /// call of `dir.read()` or of the `next()` of its async iterator.
+    ///
+    /// Note: Look at fs_dir file for further documentation.
+    ReadDirEntry(DirOperation, JSObject<JSPromise>, DirRead),
     /// Contains a setTimeout call callback. (Callback, Duration to sleep,
     /// Cancel trigger Receiver)
     SetTimeout(TimeoutAction),
-    /// Write a String in a WriteStream file
-    WriteInWSFile(Arc<Mutex<WSFile>>, String, Arc<AtomicU32>),
-    /// Close a WriteStream file. Result of the javascript call of
-    /// `writer.close()`
-    CloseWSFile(
-        Arc<Mutex<WSFile>>,
-        Arc<std::sync::Mutex<FsWriteStreamCallbacks>>,
-        JSContext,
-        Arc<AtomicU32>,
-    ),
+    /// Wait for a write queued on the standard output or error, and give its
+    /// error to the stream if it fails.
+    ///
+    /// Binded with the writes of process.stdout and process.stderr on a pipe
+    /// in javascript.
+    StdioWrite(StdioWriteAction),
+    /// Run a file system watcher until it's closed.
+    ///
+    /// Binded with fsPromise.watch(filename[, options]) and
+    /// fs.watch(filename[, options][, listener]) in javascript.
+    Watch(WatchAction),
+    /// Poll the stats of a file.
+    ///
+    /// Binded with fs.watchFile(filename[, options], listener) in javascript.
+    WatchFile(StatWatchAction),
+    /// Wait for the next event of a watcher (Iterator state, Promise Object).
+    ///
+    /// Binded with the `next()` of the iterator returned by fsPromise.watch.
+    WatchNext(Arc<Mutex<WatchIteratorState>>, JSObject<JSPromise>),
     /// Stop the loop
     Stop(Sender<()>),
 }
@@ -150,11 +187,15 @@ async fn running_loop(mut receiver: UnboundedReceiver<Action>) {
             match action {
                 Action::AccessFile(a) => deff!(exec_access(a)),
                 Action::AccessFileWithMode(a) => deff!(exec_access_with_mode(a)),
-                Action::CloseWSFile(file, callbacks, context, pending) => {
-                    deff!(exec_close(file, callbacks, context, pending))
-                }
-                Action::CreateWSFile(path, ws_file) => deff!(exec_create_file(path, ws_file)),
+                Action::CloseDir(operation, promise) => deff!(exec_close_dir(operation, promise)),
+                Action::Copy(a) => deff!(exec_cp(a)),
+                Action::FsCallback(a) => deff!(exec_fs_callback(a)),
+                Action::FsPromise(a) => deff!(exec_fs_promise(a)),
+                Action::OpenDir(a) => deff!(exec_opendir(a)),
                 Action::OpenFile(a) => deff!(exec_open(a)),
+                Action::ReadDirEntry(operation, promise, read) => {
+                    deff!(exec_read_dir_entry(operation, promise, read))
+                }
                 Action::SetTimeout(a) => {
                     deff!(
                         exec_timeout(a),
@@ -169,9 +210,10 @@ async fn running_loop(mut receiver: UnboundedReceiver<Action>) {
                         std::mem::forget(a.callback)
                     )
                 }
-                Action::WriteInWSFile(ws_file, value, pending) => {
-                    deff!(exec_write_str(ws_file, value, pending))
-                }
+                Action::StdioWrite(a) => deff!(exec_stdio_write(a)),
+                Action::Watch(a) => deff!(exec_watch(a)),
+                Action::WatchFile(a) => deff!(exec_watch_file(a)),
+                Action::WatchNext(state, promise) => deff!(exec_watch_next(state, promise)),
                 Action::Stop(sender) => exec_stop(sender),
             }
         });
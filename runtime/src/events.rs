@@ -0,0 +1,115 @@
+//! The `node:events` module: `EventEmitter`, `EventTarget`, `Event` and the
+//! `events.once` / `events.on` helpers.
+//!
+//! User code extends EventEmitter (`class Foo extends EventEmitter`), which
+//! a class of the C API can't be. The module is written in javascript
+//! (`js/events.js`) and evaluated once. The native objects emitting events
+//! (watchers) inherit from `EventEmitter.prototype` and emit their events
+//! through `emit`, so they get the whole listener API. The streams are
+//! EventEmitters through `node:stream`.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSProtected, JSValue};
+
+use crate::{
+    errors::print_uncaught,
+    event_loop::get_hold,
+    internals::internals,
+    js_utils::{call_global, call_method},
+};
+
+/// Get the `EventEmitter` constructor, which is also the module. The script
+/// is evaluated on the first call.
+pub fn events(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let module = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    module
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/events.js"), 1)
+                .expect("cannot evaluate the events module")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(context, None, &[internals(context).into()])
+                .expect("cannot initialize the events module")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Make a native object an EventEmitter: it inherits from
+/// `EventEmitter.prototype` and its listeners are initialized.
+pub fn make_emitter(context: &JSContext, object: JSValue) {
+    let event_emitter = events(context);
+    let prototype = event_emitter.get_property(context, "prototype").unwrap();
+    call_global(
+        context,
+        "Object.setPrototypeOf",
+        &[object.clone(), prototype],
+    )
+    .unwrap();
+    event_emitter
+        .get_property(context, "init")
+        .unwrap()
+        .to_object(context)
+        .unwrap()
+        .call_as_function(context, Some(&object.to_object(context).unwrap()), &[])
+        .unwrap();
+}
+
+/// Call `emitter.emit(event, ...arguments)` on the javascript thread. Fails
+/// with the error of an 'error' event without listener, as in NodeJS.
+pub fn emit_sync(
+    context: &JSContext,
+    emitter: &JSObject,
+    event: &str,
+    arguments: &[JSValue],
+) -> Result<bool, JSValue> {
+    let arguments = [vec![JSValue::string(context, event)], arguments.to_vec()].concat();
+    Ok(call_method(context, emitter, "emit", &arguments)?.to_bool(context))
+}
+
+/// Emit an event of a native object from the event loop. The arguments are
+/// built once the hold is taken. An error thrown by `emit` is uncaught, it's
+/// printed as the errors of the main script.
+pub(crate) async fn emit(
+    emitter: &JSObject<JSProtected>,
+    event: &str,
+    arguments: impl FnOnce(&JSContext) -> Vec<JSValue>,
+) {
+    let _hold = get_hold().lock().await;
+    let context = emitter.context();
+    let arguments = arguments(&context);
+    let emitter: JSObject = emitter.clone().into();
+    if let Err(err) = emit_sync(&context, &emitter, event, &arguments) {
+        print_uncaught(&context, &err);
+    }
+}
+
+/// Add a listener through `emitter.on(event, listener)`, so the objects
+/// overriding `on` see it.
+pub fn listen(
+    context: &JSContext,
+    emitter: &JSObject,
+    event: &str,
+    listener: JSValue,
+) -> Result<(), JSValue> {
+    call_method(
+        context,
+        emitter,
+        "on",
+        &[JSValue::string(context, event), listener],
+    )?;
+    Ok(())
+}
+
+/// Define the `EventTarget` and `Event` globals.
+pub fn init(context: &mut JSContext) {
+    let event_emitter = events(context);
+    let global = &mut context.get_global_object();
+    for name in ["EventTarget", "Event"] {
+        let class = event_emitter.get_property(context, name).unwrap();
+        global.set_property(context, name, class).unwrap();
+    }
+}
@@ -1,7 +1,15 @@
 use maybe_static::maybe_static;
 use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGeneric, JSValue};
 
-use crate::fs_write_stream::create_write_stream;
+use crate::{
+    fs_callback::*,
+    fs_cp::cp_sync,
+    fs_dir::opendir_sync,
+    fs_read_stream::create_read_stream,
+    fs_sync::*,
+    fs_watch::{unwatch_file, watch, watch_file},
+    fs_write_stream::create_write_stream,
+};
 
 #[allow(unused)]
 
@@ -65,6 +73,14 @@ pub mod constants {
     pub const COPYFILE_FICLONE_FORCE: u16 = 4;
 }
 
+/// Check that a file mode only contains permission bits: the `S_IRWX*`
+/// user/group/other permissions plus the setuid, setgid and sticky bits.
+pub fn is_valid_permission(mode: u32) -> bool {
+    let permissions = constants::S_IRWXU | constants::S_IRWXG | constants::S_IRWXO;
+    let special = (libc::S_ISUID | libc::S_ISGID | libc::S_ISVTX) as u32;
+    mode & !(permissions as u32 | special) == 0
+}
+
 pub fn constants_object(context: &JSContext) -> JSObject {
     /// Macro to set constant property
     macro_rules! cst_prop {
@@ -144,13 +160,100 @@ pub fn constants_object(context: &JSContext) -> JSObject {
 }
 
 pub fn fs(context: &JSContext) -> JSObject {
+    /// Macro to set the callback properties with their javascript name.
+    macro_rules! cb_prop {
+        ($obj: ident, $ctx: ident, $( $name: literal => $callback: ident ),*) => {
+            $(
+            $obj.set_property($ctx, $name, JSValue::callback($ctx, Some($callback)))
+                .unwrap();
+            )*
+        };
+    }
     let fs_class = maybe_static!(JSClass, || JSClass::create("FileSystem", None, None));
     let mut fp = fs_class.make_object(context);
 
+    cb_prop!(
+        fp, context,
+        "access" => access_callback,
+        "appendFile" => append_file_callback,
+        "chmod" => chmod_callback,
+        "chown" => chown_callback,
+        "close" => close_callback,
+        "copyFile" => copy_file_callback,
+        "cp" => cp_callback,
+        "exists" => exists_callback,
+        "fstat" => fstat_callback,
+        "fsync" => fsync_callback,
+        "lchown" => lchown_callback,
+        "link" => link_callback,
+        "lstat" => lstat_callback,
+        "lutimes" => lutimes_callback,
+        "mkdir" => mkdir_callback,
+        "mkdtemp" => mkdtemp_callback,
+        "open" => open_callback,
+        "opendir" => opendir_callback,
+        "read" => read_callback,
+        "readdir" => readdir_callback,
+        "readFile" => read_file_callback,
+        "readlink" => readlink_callback,
+        "realpath" => realpath_callback,
+        "rename" => rename_callback,
+        "rm" => rm_callback,
+        "rmdir" => rmdir_callback,
+        "stat" => stat_callback,
+        "symlink" => symlink_callback,
+        "truncate" => truncate_callback,
+        "unlink" => unlink_callback,
+        "utimes" => utimes_callback,
+        "write" => write_callback,
+        "writev" => writev_callback,
+        "writeFile" => write_file_callback,
+        "createReadStream" => create_read_stream,
+        "createWriteStream" => create_write_stream,
+        "watch" => watch,
+        "watchFile" => watch_file,
+        "unwatchFile" => unwatch_file,
+        "accessSync" => access_sync,
+        "appendFileSync" => append_file_sync,
+        "chmodSync" => chmod_sync,
+        "chownSync" => chown_sync,
+        "closeSync" => close_sync,
+        "copyFileSync" => copy_file_sync,
+        "cpSync" => cp_sync,
+        "existsSync" => exists_sync,
+        "fstatSync" => fstat_sync,
+        "fsyncSync" => fsync_sync,
+        "lchownSync" => lchown_sync,
+        "linkSync" => link_sync,
+        "lstatSync" => lstat_sync,
+        "lutimesSync" => lutimes_sync,
+        "mkdirSync" => mkdir_sync,
+        "mkdtempSync" => mkdtemp_sync,
+        "openSync" => open_sync,
+        "opendirSync" => opendir_sync,
+        "readdirSync" => readdir_sync,
+        "readFileSync" => read_file_sync,
+        "readlinkSync" => readlink_sync,
+        "readSync" => read_sync,
+        "realpathSync" => realpath_sync,
+        "renameSync" => rename_sync,
+        "rmdirSync" => rmdir_sync,
+        "rmSync" => rm_sync,
+        "statSync" => stat_sync,
+        "symlinkSync" => symlink_sync,
+        "truncateSync" => truncate_sync,
+        "unlinkSync" => unlink_sync,
+        "utimesSync" => utimes_sync,
+        "writeFileSync" => write_file_sync,
+        "writeSync" => write_sync,
+        "writevSync" => writev_sync
+    );
+    fp.set_property(context, "constants", constants_object(context).into())
+        .unwrap();
     fp.set_property(
         context,
-        "createWriteStream",
-        JSValue::callback(context, Some(create_write_stream)),
+        "promises",
+        crate::fs_promise::fs_promise(context).into(),
     )
     .unwrap();
     fp.into()
@@ -0,0 +1,222 @@
+//! Callback file system API: `fs.readFile(path, cb)`, `fs.stat(path, cb)`...
+//!
+//! The calls share their implementation with the synchronous API of
+//! `fs_sync`: the arguments are read right away, the blocking work runs on
+//! the event loop and the callback is invoked with `cb(err, ...results)` on
+//! the javascript thread.
+//!
+//! The callbacks of `cp` and `opendir` are plugged on the promises of
+//! `fsPromise.cp` and `fsPromise.opendir`: the filter of `cp` can return a
+//! promise and a `Dir` reads through the event loop.
+
+use rusty_jsc::{JSContext, JSObject, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{
+    errors::{invalid_arg_type, print_uncaught},
+    event_loop::{self, get_hold, Action},
+    fs_cp::cp,
+    fs_dir::opendir,
+    fs_sync::{self, Work},
+    js_utils::bind,
+};
+
+/// A callback call waiting for its blocking work, see `exec_fs_callback`.
+pub struct FsCallbackAction {
+    work: Work,
+    callback: JSObject<JSProtected>,
+    /// False for `exists`, whose callback doesn't receive an error first.
+    error_first: bool,
+}
+
+/// Run the blocking work of a call, then invoke its callback with the error
+/// or with null followed by the results. An error thrown by the callback is
+/// uncaught.
+pub async fn exec_fs_callback(action: FsCallbackAction) {
+    let settle = tokio::task::spawn_blocking(action.work).await.unwrap();
+    let _hold = get_hold().lock().await;
+    let context = action.callback.context();
+    let arguments = match settle(&context) {
+        Ok(values) if action.error_first => [vec![JSValue::null(&context)], values].concat(),
+        Ok(values) => values,
+        Err(error) => vec![error],
+    };
+    if let Err(error) = action.callback.call_as_function(&context, None, &arguments) {
+        print_uncaught(&context, &error);
+    }
+}
+
+/// Split the arguments of a call from its callback, the last argument.
+fn callback_argument<'a>(
+    context: &JSContext,
+    arguments: &'a [JSValue],
+) -> Result<(&'a [JSValue], JSObject<JSProtected>), JSValue> {
+    match arguments.split_last() {
+        Some((callback, arguments)) if callback.is_object(context) => {
+            Ok((arguments, callback.clone().into_protected_object(context)))
+        }
+        _ => Err(invalid_arg_type(context, "cb", "of type function")),
+    }
+}
+
+/// Define the callback calls, they give their `Work` to the event loop.
+macro_rules! callback_call {
+    ($( $name: ident => $call: ident ),*) => {
+        $(
+        #[callback]
+        pub fn $name(
+            context: JSContext,
+            _function: JSObject,
+            _this: JSObject,
+            arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            let (arguments, callback) = callback_argument(&context, arguments)?;
+            let work = fs_sync::$call(&context, arguments)?;
+            event_loop::append(Action::FsCallback(FsCallbackAction {
+                work,
+                callback,
+                error_first: true,
+            }));
+            Ok(JSValue::undefined(&context))
+        }
+        )*
+    };
+}
+
+callback_call!(
+    access_callback => access,
+    append_file_callback => append_file,
+    chmod_callback => chmod,
+    chown_callback => chown,
+    close_callback => close,
+    copy_file_callback => copy_file,
+    fstat_callback => fstat,
+    fsync_callback => fsync,
+    lchown_callback => lchown,
+    link_callback => link,
+    lstat_callback => lstat,
+    lutimes_callback => lutimes,
+    mkdir_callback => mkdir,
+    mkdtemp_callback => mkdtemp,
+    open_callback => open,
+    read_file_callback => read_file,
+    read_callback => read,
+    readdir_callback => readdir,
+    readlink_callback => readlink,
+    realpath_callback => realpath,
+    rename_callback => rename,
+    rm_callback => rm,
+    rmdir_callback => rmdir,
+    stat_callback => stat,
+    symlink_callback => symlink,
+    truncate_callback => truncate,
+    unlink_callback => unlink,
+    utimes_callback => utimes,
+    write_file_callback => write_file,
+    write_callback => write,
+    writev_callback => writev
+);
+
+/// Javascript call of the deprecated `fs.exists(path, callback)`. The
+/// callback only receives a boolean, without error.
+#[callback]
+pub fn exists_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let (arguments, callback) = callback_argument(&context, arguments)?;
+    let work = fs_sync::exists(&context, arguments)?;
+    event_loop::append(Action::FsCallback(FsCallbackAction {
+        work,
+        callback,
+        error_first: false,
+    }));
+    Ok(JSValue::undefined(&context))
+}
+
+/// Fulfillment handler of the promise based calls, bound to the callback.
+/// An error thrown by the callback is uncaught, not a rejection of the
+/// promise given by `then`, which nobody handles.
+#[callback]
+fn on_fulfilled(context: JSContext, _function: JSObject, this: JSObject, arguments: &[JSValue]) {
+    let value = arguments
+        .first()
+        .cloned()
+        .unwrap_or_else(|| JSValue::undefined(&context));
+    if let Err(error) = this.call_as_function(&context, None, &[JSValue::null(&context), value]) {
+        print_uncaught(&context, &error);
+    }
+}
+
+/// Rejection handler of the promise based calls, bound to the callback, its
+/// errors are uncaught as well.
+#[callback]
+fn on_rejected(context: JSContext, _function: JSObject, this: JSObject, arguments: &[JSValue]) {
+    let error = arguments
+        .first()
+        .cloned()
+        .unwrap_or_else(|| JSValue::undefined(&context));
+    if let Err(error) = this.call_as_function(&context, None, &[error]) {
+        print_uncaught(&context, &error);
+    }
+}
+
+/// Call a promise based function and give its result to the callback.
+fn through_promise(
+    context: &JSContext,
+    arguments: &[JSValue],
+    function: JSValue,
+) -> Result<JSValue, JSValue> {
+    let (arguments, callback) = callback_argument(context, arguments)?;
+    let callback: JSValue = callback.into();
+    let promise = function
+        .to_object(context)?
+        .call_as_function(context, None, arguments)?
+        .to_object(context)?;
+    let then = promise.get_property(context, "then")?.to_object(context)?;
+    then.call_as_function(
+        context,
+        Some(&promise),
+        &[
+            bind(
+                context,
+                JSValue::callback(context, Some(on_fulfilled)),
+                callback.clone(),
+            ),
+            bind(
+                context,
+                JSValue::callback(context, Some(on_rejected)),
+                callback,
+            ),
+        ],
+    )?;
+    Ok(JSValue::undefined(context))
+}
+
+/// Javascript call of `fs.cp(src, dest[, options], callback)`.
+#[callback]
+pub fn cp_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    through_promise(&context, arguments, JSValue::callback(&context, Some(cp)))
+}
+
+/// Javascript call of `fs.opendir(path[, options], callback)`.
+#[callback]
+pub fn opendir_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    through_promise(
+        &context,
+        arguments,
+        JSValue::callback(&context, Some(opendir)),
+    )
+}
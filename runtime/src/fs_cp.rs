@@ -0,0 +1,573 @@
+//! Implementation of `copyFile`, whose calls are built by `fs_sync`, and of
+//! the recursive `fsPromises.cp` and `fs.cpSync`.
+//!
+//! `copyFile` honours the `COPYFILE_*` flags of `fs.constants`: EXCL fails if
+//! the destination exists, FICLONE tries a copy-on-write reflink with the
+//! FICLONE ioctl and falls back to a plain copy, FICLONE_FORCE fails if the
+//! reflink isn't possible.
+//!
+//! `cp` and `cpSync` share the walk of the source tree, a blocking one: `cp`
+//! runs it on the blocking pool, `cpSync` on the javascript thread. The
+//! optional `filter` function is called on the javascript thread for each
+//! entry, it can return a boolean, or a promise of a boolean for `cp`.
+
+use std::{
+    fs::{self, File, Metadata, OpenOptions},
+    io,
+    os::unix::{
+        fs::{symlink, MetadataExt, OpenOptionsExt},
+        io::AsRawFd,
+    },
+    path::Path,
+    sync::Mutex,
+};
+
+use maybe_static::maybe_static;
+use rusty_jsc::{JSClass, JSContext, JSObject, JSPromise, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+use tokio::{runtime::Handle, sync::oneshot};
+
+use crate::{
+    errors::{code_error, invalid_arg_type, system_error},
+    event_loop::{self, get_hold, Action},
+    fs::constants,
+    fs_promise::{absolute, rejected, timespec, try_or_reject},
+    fs_sync::libc_call,
+    js_utils::bind,
+    options::{get_bool_option, get_option, path_argument},
+};
+
+/// `_IOW(0x94, 9, int)`, clone the content of a file into another one on the
+/// file systems supporting reflinks (btrfs, xfs...).
+const FICLONE: libc::c_ulong = 0x40049409;
+
+/// Read the `mode` argument of `copyFile`, a combination of the
+/// `COPYFILE_*` constants.
+pub(crate) fn copy_mode(context: &JSContext, value: Option<&JSValue>) -> Result<u16, JSValue> {
+    let all =
+        constants::COPYFILE_EXCL | constants::COPYFILE_FICLONE | constants::COPYFILE_FICLONE_FORCE;
+    match value {
+        None => Ok(0),
+        Some(value) if value.is_undefined(context) || value.is_null(context) => Ok(0),
+        Some(value) if value.is_number(context) => {
+            let mode = value.to_number(context).unwrap();
+            if mode.fract() != 0.0 || mode < 0.0 || mode > all as f64 {
+                return Err(code_error(
+                    context,
+                    "RangeError",
+                    "ERR_OUT_OF_RANGE",
+                    &format!(
+                        "The value of \"mode\" is out of range. It must be an integer >= 0 && <= {all}. Received {mode}"
+                    ),
+                ));
+            }
+            Ok(mode as u16)
+        }
+        Some(_) => Err(invalid_arg_type(context, "mode", "of type number")),
+    }
+}
+
+/// Copy the content and the permissions of `src` into `dest`, blocking.
+///
+/// As libuv, copying a file onto itself, or onto one of its hard links, does
+/// nothing, and the destination is removed if the copy fails once it's
+/// created or truncated.
+pub(crate) fn copy_file(src: &Path, dest: &Path, mode: u16) -> io::Result<()> {
+    let mut source = File::open(src)?;
+    let metadata = source.metadata()?;
+    if metadata.is_dir() {
+        return Err(io::Error::from_raw_os_error(libc::EISDIR));
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).mode(metadata.mode());
+    if mode & constants::COPYFILE_EXCL != 0 {
+        options.create_new(true);
+    } else {
+        if let Ok(target) = fs::metadata(dest) {
+            if target.dev() == metadata.dev() && target.ino() == metadata.ino() {
+                return Ok(());
+            }
+        }
+        options.create(true).truncate(true);
+    }
+    let mut destination = options.open(dest)?;
+    let result = copy_content(&mut source, &mut destination, &metadata, mode);
+    if result.is_err() && matches!(destination.metadata(), Ok(target) if target.is_file()) {
+        drop(destination);
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+/// Copy the content and the permissions of an opened file into the opened
+/// destination, see `copy_file`.
+fn copy_content(
+    source: &mut File,
+    destination: &mut File,
+    metadata: &Metadata,
+    mode: u16,
+) -> io::Result<()> {
+    let mut cloned = false;
+    if mode & (constants::COPYFILE_FICLONE | constants::COPYFILE_FICLONE_FORCE) != 0 {
+        cloned = unsafe { libc::ioctl(destination.as_raw_fd(), FICLONE, source.as_raw_fd()) } == 0;
+        if !cloned && mode & constants::COPYFILE_FICLONE_FORCE != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    if !cloned {
+        // std::io::copy uses copy_file_range when it's available.
+        io::copy(source, destination)?;
+    }
+    // A reflink only shares the content, the permissions are applied in both
+    // cases.
+    destination.set_permissions(metadata.permissions())
+}
+
+/// Options of `fsPromises.cp(src, dest[, options])` and `fs.cpSync`.
+pub struct CpOptions {
+    recursive: bool,
+    force: bool,
+    error_on_exist: bool,
+    preserve_timestamps: bool,
+    dereference: bool,
+    verbatim_symlinks: bool,
+    /// The `COPYFILE_*` flags used for each file.
+    mode: u16,
+    /// The mutex makes the options shareable between the recursive copies.
+    filter: Option<Mutex<JSObject<JSProtected>>>,
+}
+
+/// Reasons of a `cp` failure.
+enum CpError {
+    /// A failing syscall (Error, Syscall, Path, Dest).
+    System(io::Error, &'static str, String, Option<String>),
+    /// An `ERR_FS_CP_*` error (Code, Message).
+    Internal(&'static str, String),
+    /// The filter has thrown or its promise was rejected.
+    Filter(JSObject<JSProtected>),
+}
+
+type CpResult = Result<(), CpError>;
+
+/// Map an io result into a cp result, keeping the syscall and the path.
+fn sys<T>(result: io::Result<T>, syscall: &'static str, path: &Path) -> Result<T, CpError> {
+    result.map_err(|err| CpError::System(err, syscall, path.to_string_lossy().into_owned(), None))
+}
+
+/// Read the options of `cp` and `cpSync`.
+fn cp_options(context: &JSContext, options: Option<&JSValue>) -> Result<CpOptions, JSValue> {
+    let filter = match get_option(context, options, "filter") {
+        Some(filter) if filter.is_object(context) => {
+            Some(Mutex::new(filter.into_protected_object(context)))
+        }
+        Some(_) => {
+            return Err(invalid_arg_type(
+                context,
+                "options.filter",
+                "of type function",
+            ))
+        }
+        None => None,
+    };
+    let mode = copy_mode(context, get_option(context, options, "mode").as_ref())?;
+    Ok(CpOptions {
+        recursive: get_bool_option(context, options, "recursive").unwrap_or(false),
+        force: get_bool_option(context, options, "force").unwrap_or(true),
+        error_on_exist: get_bool_option(context, options, "errorOnExist").unwrap_or(false),
+        preserve_timestamps: get_bool_option(context, options, "preserveTimestamps")
+            .unwrap_or(false),
+        dereference: get_bool_option(context, options, "dereference").unwrap_or(false),
+        verbatim_symlinks: get_bool_option(context, options, "verbatimSymlinks").unwrap_or(false),
+        mode,
+        filter,
+    })
+}
+
+/// The javascript error of a failed copy.
+fn cp_error(context: &JSContext, error: CpError) -> JSValue {
+    match error {
+        CpError::System(err, syscall, path, dest) => {
+            system_error(context, &err, syscall, Some(&path), dest.as_deref())
+        }
+        CpError::Internal(code, message) => code_error(context, "Error", code, &message),
+        CpError::Filter(reason) => reason.into(),
+    }
+}
+
+#[callback]
+pub fn cp(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let src = try_or_reject!(context, path_argument(&context, arguments, 0, "src"));
+    let dest = try_or_reject!(context, path_argument(&context, arguments, 1, "dest"));
+    let options = try_or_reject!(context, cp_options(&context, arguments.get(2)));
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::Copy((src, dest, promise.clone(), options)));
+    Ok(promise.into())
+}
+
+/// Handle `fsPromise.cp`. The tree is walked on the blocking pool, the
+/// filter is awaited from there.
+pub async fn exec_cp(
+    (src, dest, promise, options): (String, String, JSObject<JSPromise>, CpOptions),
+) {
+    let result = tokio::task::spawn_blocking(move || {
+        let runtime = Handle::current();
+        let mut filter = |src: &Path, dest: &Path| match &options.filter {
+            Some(filter) => runtime.block_on(run_filter(filter, src, dest)),
+            None => Ok(true),
+        };
+        cp_root(Path::new(&src), Path::new(&dest), &options, &mut filter)
+    })
+    .await
+    .unwrap();
+    let _hold = get_hold().lock().await;
+    let context = promise.context();
+    match result {
+        Ok(()) => promise.resolve(&[]),
+        Err(error) => promise.reject(&[cp_error(&context, error)]),
+    }
+}
+
+#[callback]
+/// Javascript call of `fs.cpSync(src, dest[, options])`. The tree is walked
+/// on the javascript thread, the filter must return a boolean.
+pub fn cp_sync(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let src = path_argument(&context, arguments, 0, "src")?;
+    let dest = path_argument(&context, arguments, 1, "dest")?;
+    let options = cp_options(&context, arguments.get(2))?;
+    let mut filter = |src: &Path, dest: &Path| match &options.filter {
+        Some(filter) => {
+            let value = call_filter(&context, &filter.lock().unwrap(), src, dest)?;
+            match get_option(&context, Some(&value), "then") {
+                Some(then) if then.is_object(&context) => {
+                    let error = code_error(
+                        &context,
+                        "TypeError",
+                        "ERR_INVALID_RETURN_VALUE",
+                        "Expected boolean to be returned from the \"filter\" function but got an instance of Promise.",
+                    );
+                    Err(CpError::Filter(error.into_protected_object(&context)))
+                }
+                _ => Ok(value.to_bool(&context)),
+            }
+        }
+        None => Ok(true),
+    };
+    cp_root(Path::new(&src), Path::new(&dest), &options, &mut filter)
+        .map_err(|error| cp_error(&context, error))?;
+    Ok(JSValue::undefined(&context))
+}
+
+/// Decide if an entry is copied, from its source and its destination.
+type Filter<'a> = dyn FnMut(&Path, &Path) -> Result<bool, CpError> + 'a;
+
+/// Copy a tree, blocking. Shared by `cp` and `cpSync`, which give the way
+/// their filter is called.
+fn cp_root(src: &Path, dest: &Path, options: &CpOptions, filter: &mut Filter) -> CpResult {
+    let src_absolute = absolute(src);
+    let dest_absolute = absolute(dest);
+    if src_absolute == dest_absolute {
+        return Err(CpError::Internal(
+            "ERR_FS_CP_EINVAL",
+            format!(
+                "Invalid src or dest: cp returned EINVAL (src and dest cannot be the same) {}",
+                dest.display()
+            ),
+        ));
+    }
+    let metadata = stat(src, options)?;
+    if metadata.is_dir() && dest_absolute.starts_with(&src_absolute) {
+        return Err(CpError::Internal(
+            "ERR_FS_CP_EINVAL",
+            format!(
+                "Invalid src or dest: cp returned EINVAL (cannot copy {} to a subdirectory of self {})",
+                src.display(),
+                dest.display()
+            ),
+        ));
+    }
+    copy_entry(src, dest, options, filter)
+}
+
+/// Stat `src`, following the links only with the dereference option.
+fn stat(src: &Path, options: &CpOptions) -> Result<Metadata, CpError> {
+    if options.dereference {
+        sys(fs::metadata(src), "stat", src)
+    } else {
+        sys(fs::symlink_metadata(src), "lstat", src)
+    }
+}
+
+/// Copy one entry of the tree.
+fn copy_entry(src: &Path, dest: &Path, options: &CpOptions, filter: &mut Filter) -> CpResult {
+    if !filter(src, dest)? {
+        return Ok(());
+    }
+    let metadata = stat(src, options)?;
+    let dest_metadata = match fs::symlink_metadata(dest) {
+        Ok(metadata) => Some(metadata),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            return Err(CpError::System(
+                err,
+                "lstat",
+                dest.to_string_lossy().into_owned(),
+                None,
+            ))
+        }
+    };
+
+    if metadata.is_dir() {
+        if !options.recursive {
+            return Err(CpError::Internal(
+                "ERR_FS_EISDIR",
+                format!(
+                    "Recursive option is required to copy a directory: {}",
+                    src.display()
+                ),
+            ));
+        }
+        match dest_metadata {
+            Some(dest_metadata) if !dest_metadata.is_dir() => {
+                return Err(CpError::Internal(
+                    "ERR_FS_CP_DIR_TO_NON_DIR",
+                    format!(
+                        "Cannot overwrite non-directory {} with directory {}",
+                        dest.display(),
+                        src.display()
+                    ),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                sys(fs::create_dir(dest), "mkdir", dest)?;
+                sys(
+                    fs::set_permissions(dest, metadata.permissions()),
+                    "chmod",
+                    dest,
+                )?;
+            }
+        }
+        for entry in sys(fs::read_dir(src), "opendir", src)? {
+            let entry = sys(entry, "readdir", src)?;
+            copy_entry(
+                &entry.path(),
+                &dest.join(entry.file_name()),
+                options,
+                filter,
+            )?;
+        }
+    } else {
+        if let Some(dest_metadata) = &dest_metadata {
+            if dest_metadata.is_dir() {
+                return Err(CpError::Internal(
+                    "ERR_FS_CP_NON_DIR_TO_DIR",
+                    format!(
+                        "Cannot overwrite directory {} with non-directory {}",
+                        dest.display(),
+                        src.display()
+                    ),
+                ));
+            }
+            if !options.force {
+                if options.error_on_exist {
+                    return Err(CpError::Internal(
+                        "ERR_FS_CP_EEXIST",
+                        format!(
+                            "Target already exists: cp returned EEXIST ({} already exists)",
+                            dest.display()
+                        ),
+                    ));
+                }
+                return Ok(());
+            }
+        }
+        if metadata.file_type().is_symlink() {
+            return copy_link(src, dest, dest_metadata.is_some(), options);
+        }
+        copy_file(src, dest, options.mode).map_err(|err| {
+            CpError::System(
+                err,
+                "copyfile",
+                src.to_string_lossy().into_owned(),
+                Some(dest.to_string_lossy().into_owned()),
+            )
+        })?;
+    }
+
+    if options.preserve_timestamps {
+        let times = [
+            timespec(metadata.atime() as f64 + metadata.atime_nsec() as f64 / 1e9),
+            timespec(metadata.mtime() as f64 + metadata.mtime_nsec() as f64 / 1e9),
+        ];
+        let result = libc_call(&dest.to_string_lossy(), |path| unsafe {
+            libc::utimensat(libc::AT_FDCWD, path, times.as_ptr(), 0)
+        });
+        sys(result, "utime", dest)?;
+    }
+    Ok(())
+}
+
+/// Copy a symbolic link as a link. Relative targets are resolved from the
+/// source directory, unless `verbatimSymlinks` is set.
+fn copy_link(src: &Path, dest: &Path, dest_exists: bool, options: &CpOptions) -> CpResult {
+    let mut target = sys(fs::read_link(src), "readlink", src)?;
+    if !options.verbatim_symlinks && target.is_relative() {
+        target = absolute(&src.parent().unwrap_or(Path::new(".")).join(target));
+    }
+    if dest_exists {
+        sys(fs::remove_file(dest), "unlink", dest)?;
+    }
+    symlink(&target, dest).map_err(|err| {
+        CpError::System(
+            err,
+            "symlink",
+            target.to_string_lossy().into_owned(),
+            Some(dest.to_string_lossy().into_owned()),
+        )
+    })
+}
+
+/// Sender of the filter result, kept in the private data of the objects
+/// bound to `on_filter_fulfilled` and `on_filter_rejected`.
+type FilterSender = Mutex<Option<oneshot::Sender<Result<bool, JSObject<JSProtected>>>>>;
+
+/// Get FilterWaiterClass
+fn get_filter_waiter_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create(
+        "FilterWaiter",
+        None,
+        Some(filter_waiter_destructor)
+    ))
+}
+
+unsafe extern "C" fn filter_waiter_destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<FilterSender>::from_raw(
+        object.get_private_data().unwrap(),
+    ));
+}
+
+/// Send the result of the filter through the sender stored in `this`.
+fn send_filter_result(
+    context: &JSContext,
+    this: &mut JSObject,
+    result: Result<bool, JSObject<JSProtected>>,
+) {
+    if let Ok(object) = this.try_as_mut_object_class(context, get_filter_waiter_class()) {
+        let sender: &mut FilterSender = unsafe { &mut *object.get_private_data().unwrap() };
+        if let Some(sender) = sender.lock().unwrap().take() {
+            let _ = sender.send(result);
+        }
+    }
+}
+
+#[callback]
+fn on_filter_fulfilled(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) {
+    let keep = arguments
+        .first()
+        .map_or(false, |value| value.to_bool(&context));
+    send_filter_result(&context, &mut this, Ok(keep));
+}
+
+#[callback]
+fn on_filter_rejected(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    arguments: &[JSValue],
+) {
+    let reason = arguments
+        .first()
+        .cloned()
+        .unwrap_or_else(|| JSValue::undefined(&context))
+        .into_protected_object(&context);
+    send_filter_result(&context, &mut this, Err(reason));
+}
+
+/// Call the filter with the source and the destination of an entry.
+fn call_filter(
+    context: &JSContext,
+    filter: &JSObject<JSProtected>,
+    src: &Path,
+    dest: &Path,
+) -> Result<JSValue, CpError> {
+    filter
+        .call_as_function(
+            context,
+            None,
+            &[
+                JSValue::string(context, src.to_string_lossy()),
+                JSValue::string(context, dest.to_string_lossy()),
+            ],
+        )
+        .map_err(|err| CpError::Filter(err.into_protected_object(context)))
+}
+
+/// Call the filter of `cp` on the javascript thread. If it returns a promise
+/// (any thenable), wait for its resolution without holding the javascript
+/// thread.
+async fn run_filter(
+    filter: &Mutex<JSObject<JSProtected>>,
+    src: &Path,
+    dest: &Path,
+) -> Result<bool, CpError> {
+    let receiver = {
+        let _hold = get_hold().lock().await;
+        let filter = filter.lock().unwrap();
+        let context = filter.context();
+        let value = call_filter(&context, &filter, src, dest)?;
+        let then = match get_option(&context, Some(&value), "then") {
+            Some(then) if then.is_object(&context) => then,
+            _ => return Ok(value.to_bool(&context)),
+        };
+        let (sender, receiver) = oneshot::channel();
+        let mut waiter = get_filter_waiter_class().make_object(&context);
+        let sender: FilterSender = Mutex::new(Some(sender));
+        if waiter.set_private_data(sender).is_err() {
+            panic!("cannot set private data to filter waiter");
+        }
+        let waiter: JSValue = waiter.into();
+        let on_fulfilled = bind(
+            &context,
+            JSValue::callback(&context, Some(on_filter_fulfilled)),
+            waiter.clone(),
+        );
+        let on_rejected = bind(
+            &context,
+            JSValue::callback(&context, Some(on_filter_rejected)),
+            waiter,
+        );
+        let promise = value.to_object(&context).unwrap();
+        if let Err(err) = then.to_object(&context).unwrap().call_as_function(
+            &context,
+            Some(&promise),
+            &[on_fulfilled, on_rejected],
+        ) {
+            return Err(CpError::Filter(err.into_protected_object(&context)));
+        }
+        receiver
+    };
+    match receiver.await {
+        Ok(Ok(keep)) => Ok(keep),
+        Ok(Err(reason)) => Err(CpError::Filter(reason)),
+        // The promise was never settled and has been collected.
+        Err(_) => Ok(false),
+    }
+}
@@ -0,0 +1,587 @@
+//! Implementation of the `fs.Dir` class returned by `fsPromises.opendir` and
+//! `fs.opendirSync`.
+//!
+//! A `Dir` keeps an opened `ReadDir` in its private data and reads the
+//! entries by chunks of `bufferSize`, on the blocking pool for `dir.read()`
+//! and on the javascript thread for `dir.readSync()`. The listing is never
+//! fully materialised, so iterating over huge directories with
+//! `for await (const entry of dir)` stays cheap.
+//!
+//! The `ReadDir` is the blocking one of `std::fs`, not the `ReadDir` of
+//! tokio: `readSync` reads the same state from the javascript thread, out of
+//! any runtime task, and a tokio `ReadDir` can only be polled from an async
+//! task. Running the blocking `ReadDir` on `spawn_blocking` is also what the
+//! tokio `ReadDir` does internally.
+//!
+//! As the `kDirOperationQueue` of NodeJS, the reads and the close of a
+//! directory are done one after the other, in the order of the calls, even
+//! when they are not awaited: each operation waits for the end of the
+//! previous one, see `DirOperation`. The synchronous calls throw while
+//! asynchronous operations are pending.
+
+use std::{
+    collections::VecDeque,
+    fs::{self, ReadDir},
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use maybe_static::maybe_static;
+use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGenericClass, JSPromise, JSValue};
+use rusty_jsc_macros::callback;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+
+use crate::{
+    errors::{code_error, system_error},
+    event_loop::{self, get_hold, Action},
+    fs::constants,
+    fs_dirent::{dirent_kind, Dirent},
+    fs_promise::{rejected, settle, try_or_reject},
+    js_utils::{iterator_result, set_symbol_property},
+    options::{get_bool_option, get_number_option, path_argument},
+};
+
+/// State of an opened directory.
+pub struct DirState {
+    /// Directory currently read, with its path.
+    current: Option<(ReadDir, PathBuf)>,
+    /// Subdirectories waiting to be read in recursive mode.
+    pending: VecDeque<PathBuf>,
+    /// Entries read in advance: (name, parent path, UV_DIRENT_* kind).
+    buffer: VecDeque<(String, String, u16)>,
+    buffer_size: usize,
+    recursive: bool,
+    closed: bool,
+}
+
+impl DirState {
+    fn new(read_dir: ReadDir, path: &str, options: OpenDirOptions) -> DirState {
+        DirState {
+            current: Some((read_dir, PathBuf::from(path))),
+            pending: VecDeque::new(),
+            buffer: VecDeque::new(),
+            buffer_size: options.buffer_size,
+            recursive: options.recursive,
+            closed: false,
+        }
+    }
+
+    /// Read the next entry, filling the buffer with up to `buffer_size`
+    /// entries when it's empty. Blocking.
+    fn next(&mut self) -> io::Result<Option<(String, String, u16)>> {
+        if self.buffer.is_empty() {
+            self.fill()?;
+        }
+        Ok(self.buffer.pop_front())
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        while self.buffer.len() < self.buffer_size {
+            let (read_dir, path) = match self.current.as_mut() {
+                Some(current) => current,
+                None => match self.pending.pop_front() {
+                    Some(path) => {
+                        self.current = Some((fs::read_dir(&path)?, path));
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+            match read_dir.next().transpose()? {
+                Some(entry) => {
+                    let kind = dirent_kind(&entry.file_type()?);
+                    if self.recursive && kind == constants::UV_DIRENT_DIR {
+                        self.pending.push_back(entry.path());
+                    }
+                    self.buffer.push_back((
+                        entry.file_name().to_string_lossy().into_owned(),
+                        path.to_string_lossy().into_owned(),
+                        kind,
+                    ));
+                }
+                None => self.current = None,
+            }
+        }
+        Ok(())
+    }
+
+    /// Release the directory handles. Returns false if already closed.
+    fn close(&mut self) -> bool {
+        if self.closed {
+            return false;
+        }
+        self.closed = true;
+        self.current = None;
+        self.pending.clear();
+        self.buffer.clear();
+        true
+    }
+}
+
+/// How the result of a read is given back to javascript.
+#[derive(Clone, Copy)]
+pub enum DirRead {
+    /// `dir.read()`, resolved with a Dirent or null.
+    Read,
+    /// `iterator.next()`, resolved with `{ value, done }`. The directory is
+    /// closed automatically at the end of the iteration.
+    Next,
+    /// `iterator.return()`, close the directory and resolve with
+    /// `{ value: undefined, done: true }`.
+    Return,
+}
+
+/// An opened directory, shared between the `Dir` object, its async
+/// iterators and the event loop actions.
+pub struct DirHandle {
+    state: Mutex<DirState>,
+    /// End of the last operation queued, notified when it's dropped.
+    last: Mutex<Option<oneshot::Receiver<()>>>,
+}
+
+impl DirHandle {
+    fn new(state: DirState) -> Arc<DirHandle> {
+        Arc::new(DirHandle {
+            state: Mutex::new(state),
+            last: Mutex::new(None),
+        })
+    }
+
+    /// Get the state for a synchronous call, which throws while asynchronous
+    /// operations are pending.
+    fn state_sync(&self, context: &JSContext) -> Result<MutexGuard<'_, DirState>, JSValue> {
+        let mut last = self.last.lock().unwrap();
+        if let Some(end) = last.as_mut() {
+            if let Err(TryRecvError::Empty) = end.try_recv() {
+                return Err(code_error(
+                    context,
+                    "Error",
+                    "ERR_DIR_CONCURRENT_OPERATION",
+                    "Cannot do synchronous work on directory handle with concurrent asynchronous operations",
+                ));
+            }
+            // Ended, the next operation doesn't wait for it.
+            *last = None;
+        }
+        Ok(self.state.lock().unwrap())
+    }
+
+    /// Queue an operation, called in the order of the javascript calls.
+    fn queue(self: &Arc<DirHandle>) -> DirOperation {
+        let (done, end) = oneshot::channel();
+        let previous = self.last.lock().unwrap().replace(end);
+        DirOperation {
+            handle: self.clone(),
+            previous,
+            _done: done,
+        }
+    }
+}
+
+/// An operation queued on a directory. The next one starts when it's
+/// dropped, at the end of its action.
+pub struct DirOperation {
+    handle: Arc<DirHandle>,
+    previous: Option<oneshot::Receiver<()>>,
+    _done: oneshot::Sender<()>,
+}
+
+impl DirOperation {
+    /// Wait for the end of the previous operation, then run `call` on the
+    /// state on the blocking pool.
+    async fn run<T, F>(&mut self, call: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut DirState) -> T + Send + 'static,
+    {
+        if let Some(previous) = self.previous.take() {
+            // An error only means the previous operation is dropped.
+            let _ = previous.await;
+        }
+        let handle = self.handle.clone();
+        tokio::task::spawn_blocking(move || call(&mut handle.state.lock().unwrap()))
+            .await
+            .unwrap()
+    }
+}
+
+pub struct FsDir {
+    handle: Arc<DirHandle>,
+}
+
+/// Get DirClass
+pub fn get_fs_dir_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create("Dir", None, Some(destructor)))
+}
+
+/// Get DirIteratorClass, the class of the object returned by
+/// `dir[Symbol.asyncIterator]()`. It shares the state of its `Dir`.
+fn get_fs_dir_iterator_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create(
+        "DirIterator",
+        None,
+        Some(destructor)
+    ))
+}
+
+// Asynchronous functions called by the event loop.
+// * OpenDir => exec_opendir
+// * ReadDirEntry => exec_read_dir_entry
+// * CloseDir => exec_close_dir
+
+/// Options of `fsPromises.opendir(path[, options])` and `fs.opendirSync`.
+pub struct OpenDirOptions {
+    buffer_size: usize,
+    recursive: bool,
+}
+
+pub async fn exec_opendir((path, promise, options): (String, JSObject<JSPromise>, OpenDirOptions)) {
+    let result = tokio::task::spawn_blocking({
+        let path = path.clone();
+        move || fs::read_dir(path)
+    })
+    .await
+    .unwrap();
+    settle(
+        promise,
+        result,
+        "opendir",
+        &path.clone(),
+        |context, read_dir| {
+            let state = DirState::new(read_dir, &path, options);
+            FsDir::make(context, path, DirHandle::new(state)).into()
+        },
+    )
+    .await;
+}
+
+pub async fn exec_read_dir_entry(
+    mut operation: DirOperation,
+    promise: JSObject<JSPromise>,
+    read: DirRead,
+) {
+    // The entry read, or None if the directory was already closed.
+    let result = operation
+        .run(move |state| {
+            if let DirRead::Return = read {
+                state.close();
+                return Some(Ok(None));
+            }
+            if state.closed {
+                return None;
+            }
+            let result = state.next();
+            if matches!(read, DirRead::Next) && !matches!(result, Ok(Some(_))) {
+                state.close();
+            }
+            Some(result)
+        })
+        .await;
+    let _hold = get_hold().lock().await;
+    let context = promise.context();
+    let Some(result) = result else {
+        match read {
+            // An iterator that was closed by a `break` is simply done.
+            DirRead::Read => promise.reject(&[dir_closed_error(&context)]),
+            _ => promise.resolve(&[iterator_result(&context, None)]),
+        }
+        return;
+    };
+    let entry = match result {
+        Ok(entry) => entry.map(|(name, parent_path, kind)| {
+            JSValue::from(Dirent::make(
+                &context,
+                JSValue::string(&context, name),
+                parent_path,
+                kind,
+            ))
+        }),
+        Err(err) => {
+            promise.reject(&[system_error(&context, &err, "scandir", None, None)]);
+            return;
+        }
+    };
+    match read {
+        DirRead::Read => promise.resolve(&[entry.unwrap_or_else(|| JSValue::null(&context))]),
+        _ => promise.resolve(&[iterator_result(&context, entry)]),
+    }
+}
+
+pub async fn exec_close_dir(mut operation: DirOperation, promise: JSObject<JSPromise>) {
+    let closed = operation.run(|state| state.close()).await;
+    let _hold = get_hold().lock().await;
+    if closed {
+        promise.resolve(&[]);
+    } else {
+        promise.reject(&[dir_closed_error(&promise.context())]);
+    }
+}
+
+fn dir_closed_error(context: &JSContext) -> JSValue {
+    code_error(
+        context,
+        "Error",
+        "ERR_DIR_CLOSED",
+        "Directory handle was closed",
+    )
+}
+
+impl FsDir {
+    /// Create a new `Dir` JS object around an opened directory.
+    pub fn make(
+        context: &JSContext,
+        path: String,
+        handle: Arc<DirHandle>,
+    ) -> JSObject<JSObjectGenericClass> {
+        let mut object = get_fs_dir_class().make_object(context);
+        object
+            .set_property(context, "path", JSValue::string(context, path))
+            .unwrap();
+        object
+            .set_property(context, "read", JSValue::callback(context, Some(read)))
+            .unwrap();
+        object
+            .set_property(context, "close", JSValue::callback(context, Some(close)))
+            .unwrap();
+        object
+            .set_property(
+                context,
+                "readSync",
+                JSValue::callback(context, Some(read_sync)),
+            )
+            .unwrap();
+        object
+            .set_property(
+                context,
+                "closeSync",
+                JSValue::callback(context, Some(close_sync)),
+            )
+            .unwrap();
+        set_symbol_property(
+            context,
+            object.clone().into(),
+            "asyncIterator",
+            JSValue::callback(context, Some(async_iterator)),
+        )
+        .unwrap();
+        if object.set_private_data(FsDir { handle }).is_err() {
+            panic!("cannot set private data to dir");
+        }
+        object
+    }
+
+    pub fn try_from_object<'a>(
+        context: &JSContext,
+        object: &mut JSObject,
+        class: &JSClass,
+    ) -> Result<&'a mut FsDir, JSValue> {
+        let object = object.try_as_mut_object_class(context, class)?;
+        let dir: &mut FsDir = unsafe { &mut *object.get_private_data().unwrap() };
+        Ok(dir)
+    }
+}
+
+pub unsafe extern "C" fn destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<FsDir>::from_raw(object.get_private_data().unwrap()));
+}
+
+#[callback]
+pub fn opendir(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let path = try_or_reject!(context, path_argument(&context, arguments, 0, "path"));
+    let options = try_or_reject!(context, opendir_options(&context, arguments.get(1)));
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::OpenDir((path, promise.clone(), options)));
+    Ok(promise.into())
+}
+
+#[callback]
+/// Javascript call of `fs.opendirSync(path[, options])`.
+pub fn opendir_sync(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let path = path_argument(&context, arguments, 0, "path")?;
+    let options = opendir_options(&context, arguments.get(1))?;
+    let read_dir = fs::read_dir(&path)
+        .map_err(|err| system_error(&context, &err, "opendir", Some(&path), None))?;
+    let state = DirState::new(read_dir, &path, options);
+    Ok(FsDir::make(&context, path, DirHandle::new(state)).into())
+}
+
+/// Read the options of `opendir` and `opendirSync`.
+fn opendir_options(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<OpenDirOptions, JSValue> {
+    let buffer_size = get_number_option(context, options, "bufferSize")?.unwrap_or(32.0);
+    if !(1.0..=4294967295.0).contains(&buffer_size) || buffer_size.fract() != 0.0 {
+        return Err(code_error(
+            context,
+            "RangeError",
+            "ERR_OUT_OF_RANGE",
+            &format!(
+                "The value of \"options.bufferSize\" is out of range. It must be >= 1 && <= 4294967295. Received {buffer_size}"
+            ),
+        ));
+    }
+    Ok(OpenDirOptions {
+        buffer_size: buffer_size as usize,
+        recursive: get_bool_option(context, options, "recursive").unwrap_or(false),
+    })
+}
+
+#[callback]
+/// Javascript call of `dir.read()`. Returns a promise resolved with the next
+/// `Dirent`, or null when there is no more entries to read.
+fn read(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_class())?;
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::ReadDirEntry(
+        dir.handle.queue(),
+        promise.clone(),
+        DirRead::Read,
+    ));
+    Ok(promise.into())
+}
+
+#[callback]
+/// Javascript call of `dir.close()`. Pending reads are finished before the
+/// directory is closed.
+fn close(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_class())?;
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::CloseDir(dir.handle.queue(), promise.clone()));
+    Ok(promise.into())
+}
+
+#[callback]
+/// Javascript call of `dir.readSync()`. Returns the next `Dirent`, or null
+/// when there is no more entries to read.
+fn read_sync(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_class())?;
+    let mut state = dir.handle.state_sync(&context)?;
+    if state.closed {
+        return Err(dir_closed_error(&context));
+    }
+    let entry = state
+        .next()
+        .map_err(|err| system_error(&context, &err, "scandir", None, None))?;
+    Ok(match entry {
+        Some((name, parent_path, kind)) => {
+            Dirent::make(&context, JSValue::string(&context, name), parent_path, kind).into()
+        }
+        None => JSValue::null(&context),
+    })
+}
+
+#[callback]
+/// Javascript call of `dir.closeSync()`.
+fn close_sync(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_class())?;
+    if !dir.handle.state_sync(&context)?.close() {
+        return Err(dir_closed_error(&context));
+    }
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// Javascript call of `dir[Symbol.asyncIterator]()`.
+fn async_iterator(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_class())?;
+    let mut iterator = get_fs_dir_iterator_class().make_object(&context);
+    iterator
+        .set_property(&context, "next", JSValue::callback(&context, Some(next)))
+        .unwrap();
+    iterator
+        .set_property(
+            &context,
+            "return",
+            JSValue::callback(&context, Some(iterator_return)),
+        )
+        .unwrap();
+    if iterator
+        .set_private_data(FsDir {
+            handle: dir.handle.clone(),
+        })
+        .is_err()
+    {
+        panic!("cannot set private data to dir iterator");
+    }
+    Ok(iterator.into())
+}
+
+#[callback]
+fn next(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_iterator_class())?;
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::ReadDirEntry(
+        dir.handle.queue(),
+        promise.clone(),
+        DirRead::Next,
+    ));
+    Ok(promise.into())
+}
+
+#[callback]
+/// Called when a `for await` loop is left early (break, return, throw). The
+/// directory is closed as NodeJS does.
+fn iterator_return(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let dir = FsDir::try_from_object(&context, &mut this, get_fs_dir_iterator_class())?;
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::ReadDirEntry(
+        dir.handle.queue(),
+        promise.clone(),
+        DirRead::Return,
+    ));
+    Ok(promise.into())
+}
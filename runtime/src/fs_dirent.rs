@@ -0,0 +1,140 @@
+//! Implementation of the `fs.Dirent` class, a representation of a directory
+//! entry returned by `readdir(path, { withFileTypes: true })` or by a `Dir`.
+//!
+//! The type of the entry is kept in the private data of the object as one of
+//! the `UV_DIRENT_*` constants.
+
+use std::fs::FileType;
+use std::os::unix::fs::FileTypeExt;
+
+use maybe_static::maybe_static;
+use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGenericClass, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::fs::constants;
+
+pub struct Dirent {
+    /// One of the `UV_DIRENT_*` constants.
+    kind: u16,
+}
+
+/// Get DirentClass
+pub fn get_dirent_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create(
+        "Dirent",
+        None,
+        Some(destructor)
+    ))
+}
+
+/// Convert a Rust file type into its `UV_DIRENT_*` value.
+pub fn dirent_kind(file_type: &FileType) -> u16 {
+    if file_type.is_symlink() {
+        constants::UV_DIRENT_LINK
+    } else if file_type.is_file() {
+        constants::UV_DIRENT_FILE
+    } else if file_type.is_dir() {
+        constants::UV_DIRENT_DIR
+    } else if file_type.is_fifo() {
+        constants::UV_DIRENT_FIFO
+    } else if file_type.is_socket() {
+        constants::UV_DIRENT_SOCKET
+    } else if file_type.is_char_device() {
+        constants::UV_DIRENT_CHAR
+    } else if file_type.is_block_device() {
+        constants::UV_DIRENT_BLOCK
+    } else {
+        constants::UV_DIRENT_UNKNOWN
+    }
+}
+
+impl Dirent {
+    /// Create a new `Dirent` JS object. `parent_path` is the directory that
+    /// contains the entry, exposed as `parentPath` and as the deprecated
+    /// `path` property.
+    pub fn make(
+        context: &JSContext,
+        name: JSValue,
+        parent_path: String,
+        kind: u16,
+    ) -> JSObject<JSObjectGenericClass> {
+        let mut object = get_dirent_class().make_object(context);
+        object.set_property(context, "name", name).unwrap();
+        object
+            .set_property(
+                context,
+                "parentPath",
+                JSValue::string(context, parent_path.clone()),
+            )
+            .unwrap();
+        object
+            .set_property(context, "path", JSValue::string(context, parent_path))
+            .unwrap();
+        macro_rules! method {
+            ($( $name: literal => $callback: ident ),*) => {
+                $(
+                object
+                    .set_property(context, $name, JSValue::callback(context, Some($callback)))
+                    .unwrap();
+                )*
+            };
+        }
+        method!(
+            "isFile" => is_file,
+            "isDirectory" => is_directory,
+            "isSymbolicLink" => is_symbolic_link,
+            "isFIFO" => is_fifo,
+            "isSocket" => is_socket,
+            "isCharacterDevice" => is_character_device,
+            "isBlockDevice" => is_block_device
+        );
+        if object.set_private_data(Dirent { kind }).is_err() {
+            panic!("cannot set private data to dirent");
+        }
+        object
+    }
+
+    pub fn try_from_object<'a>(
+        context: &JSContext,
+        object: &mut JSObject,
+    ) -> Result<&'a mut Dirent, JSValue> {
+        let object = object.try_as_mut_object_class(context, get_dirent_class())?;
+        let dirent: &mut Dirent = unsafe { &mut *object.get_private_data().unwrap() };
+        Ok(dirent)
+    }
+}
+
+pub unsafe extern "C" fn destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<Dirent>::from_raw(object.get_private_data().unwrap()));
+}
+
+/// Define the `Dirent.is*()` methods, true if the private kind of `this` is
+/// the given constant.
+macro_rules! dirent_is {
+    ($( $name: ident => $kind: ident ),*) => {
+        $(
+        #[callback]
+        fn $name(
+            context: JSContext,
+            _function: JSObject,
+            mut this: JSObject,
+            _arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            let dirent = Dirent::try_from_object(&context, &mut this)?;
+            Ok(JSValue::boolean(&context, dirent.kind == constants::$kind))
+        }
+        )*
+    };
+}
+
+dirent_is!(
+    is_file => UV_DIRENT_FILE,
+    is_directory => UV_DIRENT_DIR,
+    is_symbolic_link => UV_DIRENT_LINK,
+    is_fifo => UV_DIRENT_FIFO,
+    is_socket => UV_DIRENT_SOCKET,
+    is_character_device => UV_DIRENT_CHAR,
+    is_block_device => UV_DIRENT_BLOCK
+);
@@ -1,14 +1,147 @@
-use std::os::unix::prelude::MetadataExt;
+use std::{
+    collections::VecDeque,
+    ffi::{CString, OsStr, OsString},
+    io,
+    os::unix::{
+        ffi::{OsStrExt, OsStringExt},
+        fs::DirBuilderExt,
+        prelude::MetadataExt,
+    },
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use maybe_static::maybe_static;
 use rusty_jsc::{JSClass, JSContext, JSObject, JSPromise, JSValue};
 use rusty_jsc_macros::callback;
 
 use crate::{
+    encoding::{bytes_to_value, Encoding},
+    errors::{code_error, from_errno, invalid_arg_type, invalid_arg_value, system_error},
     event_loop::{self, get_hold, Action},
-    fs::constants_object,
+    fs::{constants_object, is_valid_permission},
+    fs_cp::cp,
+    fs_dir::opendir,
+    fs_dirent::{dirent_kind, Dirent},
+    fs_sync::{self, Work},
+    fs_watch::watch_promise,
+    options::{
+        get_bool_option, get_encoding, get_number_option, get_option, parse_mode, path_argument,
+    },
 };
 
+/// Returns a promise already rejected with `error`. The promise API of NodeJS
+/// never throws on invalid arguments, it rejects.
+pub(crate) fn rejected(context: &mut JSContext, error: JSValue) -> JSValue {
+    let promise = JSObject::<JSPromise>::promise(context);
+    promise.reject(&[error]);
+    promise.into()
+}
+
+/// Unwrap an argument parsing result, or return a rejected promise from the
+/// current callback.
+macro_rules! try_or_reject {
+    ($context: ident, $e: expr) => {
+        match $e {
+            Ok(value) => value,
+            Err(error) => return Ok(rejected(&mut $context, error)),
+        }
+    };
+}
+pub(crate) use try_or_reject;
+
+/// Settle a promise with the result of an asynchronous file system call. On
+/// success, the resolved value is built by `to_value` once the javascript
+/// thread is held. On failure the promise is rejected with a system error.
+pub(crate) async fn settle<T>(
+    promise: JSObject<JSPromise>,
+    result: io::Result<T>,
+    syscall: &str,
+    path: &str,
+    to_value: impl FnOnce(&JSContext, T) -> JSValue,
+) {
+    let _hold = get_hold().lock().await;
+    let context = promise.context();
+    match result {
+        Ok(value) => promise.resolve(&[to_value(&context, value)]),
+        Err(err) => promise.reject(&[system_error(&context, &err, syscall, Some(path), None)]),
+    }
+}
+
+/// A promise call waiting for its blocking work, see `exec_fs_promise`.
+pub struct FsPromiseAction {
+    work: Work,
+    promise: JSObject<JSPromise>,
+}
+
+/// Run the blocking work of a call, then resolve its promise with the first
+/// result or reject it with the error.
+pub async fn exec_fs_promise(action: FsPromiseAction) {
+    let settle = tokio::task::spawn_blocking(action.work).await.unwrap();
+    let _hold = get_hold().lock().await;
+    let context = action.promise.context();
+    match settle(&context) {
+        Ok(values) => action.promise.resolve(&values[..values.len().min(1)]),
+        Err(error) => action.promise.reject(&[error]),
+    }
+}
+
+/// Define the promise calls. They share their implementation with the
+/// synchronous and the callback APIs: the `Work` of `fs_sync` is given to
+/// the event loop, the invalid arguments reject the promise.
+macro_rules! promise_call {
+    ($( $name: ident => $call: ident ),*) => {
+        $(
+        #[callback]
+        fn $name(
+            context: JSContext,
+            _function: JSObject,
+            _this: JSObject,
+            arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            let mut context = context;
+            let work = try_or_reject!(context, fs_sync::$call(&context, arguments));
+            let promise = JSObject::<JSPromise>::promise(&mut context);
+            event_loop::append(Action::FsPromise(FsPromiseAction {
+                work,
+                promise: promise.clone(),
+            }));
+            Ok(promise.into())
+        }
+        )*
+    };
+}
+
+promise_call!(
+    chmod => chmod,
+    chown => chown,
+    copy_file => copy_file,
+    lchown => lchown,
+    link => link,
+    lutimes => lutimes,
+    mkdir => mkdir,
+    mkdtemp => mkdtemp,
+    readdir => readdir,
+    readlink => readlink,
+    realpath => realpath,
+    rename => rename,
+    rm => rm,
+    rmdir => rmdir,
+    symlink => symlink,
+    truncate => truncate,
+    unlink => unlink,
+    utimes => utimes
+);
+
+/// Make `path` absolute, relatively to the current working directory.
+pub(crate) fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    }
+}
+
 #[callback]
 fn open(
     context: JSContext,
@@ -131,14 +264,488 @@ pub async fn exec_access_with_mode((filename, promise, mode): (String, JSObject<
     }
 }
 
+/// Options of `fsPromises.mkdir(path[, options])`.
+pub struct MkdirOptions {
+    recursive: bool,
+    mode: u32,
+}
+
+/// Read the options of `mkdir`, they can be the mode itself:
+/// `mkdir(path, 0o755)`.
+pub(crate) fn mkdir_options(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<MkdirOptions, JSValue> {
+    let mode = match options {
+        Some(value) if value.is_number(context) || value.is_string(context) => {
+            parse_mode(context, Some(value), 0o777)?
+        }
+        _ => parse_mode(
+            context,
+            get_option(context, options, "mode").as_ref(),
+            0o777,
+        )?,
+    };
+    Ok(MkdirOptions {
+        recursive: get_bool_option(context, options, "recursive").unwrap_or(false),
+        mode,
+    })
+}
+
+/// Create a directory, blocking. In recursive mode, returns the first
+/// directory created, or None if nothing was created.
+pub(crate) fn create_dir(path: &str, options: &MkdirOptions) -> io::Result<Option<PathBuf>> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.mode(options.mode);
+    if !options.recursive {
+        return builder.create(path).map(|_| None);
+    }
+    // Look for the topmost ancestor that doesn't exist yet, this is the first
+    // directory that will be created.
+    let absolute_path = absolute(Path::new(path));
+    let mut first_created = None;
+    for ancestor in absolute_path.ancestors() {
+        if std::fs::symlink_metadata(ancestor).is_ok() {
+            break;
+        }
+        first_created = Some(ancestor.to_path_buf());
+    }
+    builder.recursive(true);
+    builder.create(path).map(|_| first_created)
+}
+
+/// Options of `fsPromises.readdir(path[, options])`.
+pub struct ReadDirOptions {
+    with_file_types: bool,
+    pub(crate) recursive: bool,
+    /// The encoding of the names, None for `'buffer'`.
+    encoding: Option<Encoding>,
+}
+
+pub(crate) fn readdir_options(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<ReadDirOptions, JSValue> {
+    let encoding = match get_encoding(context, options)? {
+        Some(name) => Encoding::parse(context, &name)?,
+        None => Some(Encoding::Utf8),
+    };
+    Ok(ReadDirOptions {
+        with_file_types: get_bool_option(context, options, "withFileTypes").unwrap_or(false),
+        recursive: get_bool_option(context, options, "recursive").unwrap_or(false),
+        encoding,
+    })
+}
+
+/// A directory entry read from the file system. The name is relative to the
+/// directory given to `readdir`.
+pub(crate) struct Entry {
+    name: PathBuf,
+    parent_path: String,
+    kind: u16,
+}
+
+/// List the entries of `path`, walking the subdirectories in breadth first
+/// order if `recursive`. Symbolic links are never followed. Blocking.
+pub(crate) fn read_entries(path: &str, recursive: bool) -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+    let mut directories = VecDeque::from([(PathBuf::from(path), PathBuf::new())]);
+    while let Some((directory, relative)) = directories.pop_front() {
+        for entry in std::fs::read_dir(&directory)? {
+            let entry = entry?;
+            let kind = dirent_kind(&entry.file_type()?);
+            let name = relative.join(entry.file_name());
+            if recursive && kind == crate::fs::constants::UV_DIRENT_DIR {
+                directories.push_back((entry.path(), name.clone()));
+            }
+            entries.push(Entry {
+                name,
+                parent_path: directory.to_string_lossy().into_owned(),
+                kind,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Convert the entries into an array of names, or of `Dirent` objects if
+/// `withFileTypes` is set. The names are decoded with the encoding option,
+/// they are `Uint8Array` with `'buffer'`.
+pub(crate) fn entries_array(
+    context: &JSContext,
+    entries: Vec<Entry>,
+    options: &ReadDirOptions,
+) -> JSValue {
+    let values: Vec<JSValue> = entries
+        .into_iter()
+        .map(|entry| {
+            let name =
+                |name: &OsStr| bytes_to_value(context, name.as_bytes().to_vec(), options.encoding);
+            if options.with_file_types {
+                // Dirent names are the base name of the entry.
+                let base_name = entry.name.file_name().unwrap_or(entry.name.as_os_str());
+                Dirent::make(context, name(base_name), entry.parent_path, entry.kind).into()
+            } else {
+                name(entry.name.as_os_str())
+            }
+        })
+        .collect();
+    JSObject::new_array(context, &values).unwrap().into()
+}
+
+/// Options of `fsPromises.rm(path[, options])` and of the deprecated
+/// recursive `fsPromises.rmdir(path[, options])`.
+pub struct RmOptions {
+    recursive: bool,
+    force: bool,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+pub(crate) fn rm_options(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<RmOptions, JSValue> {
+    let max_retries = get_number_option(context, options, "maxRetries")?.unwrap_or(0.0);
+    let retry_delay = get_number_option(context, options, "retryDelay")?.unwrap_or(100.0);
+    Ok(RmOptions {
+        recursive: get_bool_option(context, options, "recursive").unwrap_or(false),
+        force: get_bool_option(context, options, "force").unwrap_or(false),
+        max_retries: max_retries.max(0.0) as u32,
+        retry_delay: Duration::from_millis(retry_delay.max(0.0) as u64),
+    })
+}
+
+/// Retry `operation` on the errors NodeJS considers as temporary, with a
+/// linear backoff of `retry_delay` between each attempt. Blocking.
+fn with_retries<F>(options: &RmOptions, operation: F) -> io::Result<()>
+where
+    F: Fn() -> io::Result<()>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation() {
+            Err(err)
+                if attempt < options.max_retries
+                    && matches!(
+                        err.raw_os_error(),
+                        Some(
+                            libc::EBUSY
+                                | libc::EMFILE
+                                | libc::ENFILE
+                                | libc::ENOTEMPTY
+                                | libc::EPERM
+                        )
+                    ) =>
+            {
+                attempt += 1;
+                std::thread::sleep(options.retry_delay * attempt);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Remove a directory, blocking. The recursive option is deprecated in
+/// NodeJS but still honoured.
+pub(crate) fn remove_dir(path: &str, options: &RmOptions) -> io::Result<()> {
+    with_retries(options, || {
+        if options.recursive {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_dir(path)
+        }
+    })
+}
+
+/// Reasons of a `rm` failure.
+pub(crate) enum RmError {
+    /// A failing syscall (Error, Syscall).
+    System(io::Error, &'static str),
+    /// The path is a directory and the recursive option isn't set.
+    IsDirectory,
+}
+
+/// Remove a file or a tree, blocking. Removing a directory requires the
+/// recursive option, a missing path is ignored with the force option.
+pub(crate) fn remove(path: &str, options: &RmOptions) -> Result<(), RmError> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if options.force && err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(RmError::System(err, "lstat")),
+    };
+    if metadata.is_dir() && !options.recursive {
+        return Err(RmError::IsDirectory);
+    }
+    with_retries(options, || {
+        let result = if metadata.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_file(path)
+        };
+        match result {
+            Err(err) if options.force && err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    })
+    .map_err(|err| RmError::System(err, "rm"))
+}
+
+/// Create the javascript error of a `rm` failure.
+pub(crate) fn rm_error(context: &JSContext, error: &RmError, path: &str) -> JSValue {
+    match error {
+        RmError::System(err, syscall) => system_error(context, err, syscall, Some(path), None),
+        RmError::IsDirectory => {
+            let error = code_error(
+                context,
+                "Error",
+                "ERR_FS_EISDIR",
+                &format!("Path is a directory: rm returned EISDIR (is a directory) {path}"),
+            );
+            let mut object = error.to_object(context).unwrap();
+            object
+                .set_property(
+                    context,
+                    "errno",
+                    JSValue::number(context, libc::EISDIR as f64),
+                )
+                .unwrap();
+            object
+                .set_property(context, "syscall", JSValue::string(context, "rm"))
+                .unwrap();
+            object
+                .set_property(context, "path", JSValue::string(context, path))
+                .unwrap();
+            error
+        }
+    }
+}
+
+/// Create a unique temporary directory from a `prefixXXXXXX` template, six
+/// random characters replace the X by the libc. Blocking.
+pub(crate) fn make_temp_dir(template: &str) -> io::Result<PathBuf> {
+    let template = CString::new(template).map_err(|_| from_errno(libc::EINVAL))?;
+    let mut bytes = template.into_bytes_with_nul();
+    let created = unsafe { libc::mkdtemp(bytes.as_mut_ptr() as *mut libc::c_char) };
+    if created.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    // Remove the trailing nul byte.
+    bytes.pop();
+    Ok(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+/// Validate the type of a symbolic link. It's only meaningful on Windows, but
+/// it's validated everywhere.
+pub(crate) fn symlink_type(context: &JSContext, kind: Option<&JSValue>) -> Result<(), JSValue> {
+    match kind {
+        Some(kind) if !kind.is_undefined(context) && !kind.is_null(context) => {
+            let valid = kind.is_string(context)
+                && matches!(
+                    kind.to_js_string(context).unwrap().to_string().as_str(),
+                    "dir" | "file" | "junction"
+                );
+            if !valid {
+                return Err(invalid_arg_value(
+                    context,
+                    "type",
+                    "must be 'dir', 'file' or 'junction'",
+                ));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Read a mandatory mode argument, only permission bits are accepted.
+pub(crate) fn permission_argument(
+    context: &JSContext,
+    value: Option<&JSValue>,
+) -> Result<u32, JSValue> {
+    if value.map_or(true, |value| value.is_undefined(context)) {
+        return Err(invalid_arg_type(context, "mode", "of type number"));
+    }
+    let mode = parse_mode(context, value, 0)?;
+    if !is_valid_permission(mode) {
+        return Err(code_error(
+            context,
+            "RangeError",
+            "ERR_OUT_OF_RANGE",
+            &format!(
+                "The value of \"mode\" is out of range. It must be >= 0 && <= 4095. Received {mode}"
+            ),
+        ));
+    }
+    Ok(mode)
+}
+
+#[callback]
+/// `lchmod` only exists on macOS, NodeJS doesn't implement it elsewhere.
+fn lchmod(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let path = try_or_reject!(context, path_argument(&context, arguments, 0, "path"));
+    try_or_reject!(context, permission_argument(&context, arguments.get(1)));
+    let error = system_error(
+        &context,
+        &from_errno(libc::ENOSYS),
+        "lchmod",
+        Some(&path),
+        None,
+    );
+    Ok(rejected(&mut context, error))
+}
+
+/// Read an uid or gid argument, -1 means that the id is not changed.
+pub(crate) fn id_argument(
+    context: &JSContext,
+    arguments: &[JSValue],
+    index: usize,
+    name: &str,
+) -> Result<u32, JSValue> {
+    match arguments.get(index) {
+        Some(value) if value.is_number(context) => {
+            let id = value.to_number(context).unwrap();
+            if id.fract() != 0.0 || !(-1.0..=u32::MAX as f64).contains(&id) {
+                return Err(code_error(
+                    context,
+                    "RangeError",
+                    "ERR_OUT_OF_RANGE",
+                    &format!(
+                        "The value of \"{name}\" is out of range. It must be >= -1 && <= 4294967295. Received {id}"
+                    ),
+                ));
+            }
+            // -1 is (uid_t)-1 for the libc.
+            Ok(if id < 0.0 { u32::MAX } else { id as u32 })
+        }
+        _ => Err(invalid_arg_type(context, name, "of type number")),
+    }
+}
+
+/// Convert a javascript time into seconds since the epoch. As NodeJS, it
+/// accepts a Date, a number of seconds or a numeric string. Invalid numbers
+/// (NaN, Infinity, negative) are replaced by the current time.
+pub(crate) fn time_argument(
+    context: &JSContext,
+    arguments: &[JSValue],
+    index: usize,
+    name: &str,
+) -> Result<f64, JSValue> {
+    let value = match arguments.get(index) {
+        Some(value) => value,
+        None => {
+            return Err(invalid_arg_type(
+                context,
+                name,
+                "of type number or an instance of Date",
+            ))
+        }
+    };
+    let seconds = if value.is_number(context) {
+        value.to_number(context).unwrap()
+    } else if value.is_string(context) {
+        match value
+            .to_js_string(context)
+            .unwrap()
+            .to_string()
+            .trim()
+            .parse::<f64>()
+        {
+            Ok(seconds) => seconds,
+            Err(_) => {
+                return Err(invalid_arg_type(
+                    context,
+                    name,
+                    "of type number or an instance of Date",
+                ))
+            }
+        }
+    } else {
+        // A Date is recognized by its getTime method.
+        let get_time = get_option(context, Some(value), "getTime");
+        match get_time {
+            Some(get_time) if get_time.is_object(context) => {
+                let object = value.to_object(context).unwrap();
+                get_time
+                    .to_object(context)
+                    .unwrap()
+                    .call_as_function(context, Some(&object), &[])?
+                    .to_number(context)?
+                    / 1000.0
+            }
+            _ => {
+                return Err(invalid_arg_type(
+                    context,
+                    name,
+                    "of type number or an instance of Date",
+                ))
+            }
+        }
+    };
+    if seconds.is_finite() && seconds >= 0.0 {
+        Ok(seconds)
+    } else {
+        Ok(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64())
+    }
+}
+
+pub(crate) fn timespec(seconds: f64) -> libc::timespec {
+    libc::timespec {
+        tv_sec: seconds.trunc() as libc::time_t,
+        tv_nsec: (seconds.fract() * 1e9) as libc::c_long,
+    }
+}
+
+/// Read the optional `len` argument of `truncate`, negative lengths are
+/// considered as 0.
+pub(crate) fn len_argument(context: &JSContext, len: Option<&JSValue>) -> Result<u64, JSValue> {
+    match len {
+        None => Ok(0),
+        Some(len) if len.is_undefined(context) => Ok(0),
+        Some(len) if len.is_number(context) => Ok(len.to_number(context).unwrap().max(0.0) as u64),
+        Some(_) => Err(invalid_arg_type(context, "len", "of type number")),
+    }
+}
+
 pub fn fs_promise(context: &JSContext) -> JSObject {
+    /// Macro to set the callback properties, named as their javascript
+    /// counterpart.
+    macro_rules! cb_prop {
+        ($obj: ident, $ctx: ident, $( $name: ident ),*) => {
+            $(
+            $obj.set_property($ctx, stringify!($name), JSValue::callback($ctx, Some($name)))
+                .unwrap();
+            )*
+        };
+    }
     let fs_promise_class = maybe_static!(JSClass, || JSClass::create("FsPromise", None, None));
     let mut fp = fs_promise_class.make_object(context);
 
-    fp.set_property(context, "open", JSValue::callback(context, Some(open)))
-        .unwrap();
-    fp.set_property(context, "access", JSValue::callback(context, Some(access)))
-        .unwrap();
+    cb_prop!(
+        fp, context, open, access, chmod, chown, cp, lchmod, lchown, link, lutimes, mkdir, mkdtemp,
+        opendir, readdir, readlink, realpath, rename, rm, rmdir, symlink, truncate, unlink, utimes
+    );
+    fp.set_property(
+        context,
+        "watch",
+        JSValue::callback(context, Some(watch_promise)),
+    )
+    .unwrap();
+    fp.set_property(
+        context,
+        "copyFile",
+        JSValue::callback(context, Some(copy_file)),
+    )
+    .unwrap();
     fp.set_property(context, "constants", constants_object(context).into())
         .unwrap();
     fp.into()
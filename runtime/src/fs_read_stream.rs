@@ -0,0 +1,176 @@
+//! `fs.createReadStream`: a `Readable` of `node:stream` over a file, read
+//! chunk by chunk so a file never has to fit in memory.
+//!
+//! The class is written in javascript (`js/fs_read_stream.js`) on top of
+//! the callback fs API: the file is opened by `fs.open` when the stream is
+//! constructed, each `_read` is a `fs.read` of at most `highWaterMark` bytes
+//! and the file is closed by `fs.close` once the stream is destroyed. The
+//! flowing and paused modes, `pipe`, the async iteration and the decoding of
+//! the `encoding` option are the ones of `Readable`.
+//!
+//! The options are checked here, before the stream is constructed.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{
+    encoding::encoding_option,
+    errors::{code_error, invalid_arg_type},
+    internals::internals,
+    js_utils::construct_with,
+    options::{get_bool_option, get_number_option, get_option, parse_flags, parse_mode},
+    stream::stream,
+};
+
+/// Default size of the chunks, the same as NodeJS.
+const DEFAULT_HIGH_WATER_MARK: f64 = 64.0 * 1024.0;
+
+/// Get the `ReadStream` class. The script is evaluated on the first call.
+pub fn read_stream_class(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let class = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    class
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/fs_read_stream.js"), 1)
+                .expect("cannot evaluate the ReadStream class")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(
+                    context,
+                    None,
+                    &[
+                        stream(context).into(),
+                        crate::fs::fs(context).into(),
+                        internals(context).into(),
+                    ],
+                )
+                .expect("cannot create the ReadStream class")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Read a positive integer option of `createReadStream`.
+fn position_option(
+    context: &JSContext,
+    options: Option<&JSValue>,
+    name: &str,
+) -> Result<Option<u64>, JSValue> {
+    match get_number_option(context, options, name)? {
+        Some(value) if value.fract() != 0.0 || value < 0.0 => Err(code_error(
+            context,
+            "RangeError",
+            "ERR_OUT_OF_RANGE",
+            &format!(
+                "The value of \"{name}\" is out of range. It must be >= 0 && <= 9007199254740991. Received {value}"
+            ),
+        )),
+        value => Ok(value.map(|value| value as u64)),
+    }
+}
+
+#[callback]
+/// Javascript call of `fs.createReadStream(path[, options])`. Returns a
+/// `ReadStream` emitting 'open', 'ready', 'data', 'end', 'error' and 'close'.
+///
+/// `end` is inclusive: `{ start: 0, end: 9 }` reads the first 10 bytes.
+pub fn create_read_stream(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let options = arguments.get(1);
+    let fd = match get_option(&context, options, "fd") {
+        Some(fd) if fd.is_number(&context) => Some(fd.to_number(&context)?),
+        Some(_) => return Err(invalid_arg_type(&context, "options.fd", "of type number")),
+        None => None,
+    };
+    let path = match arguments.first() {
+        Some(path) if path.is_string(&context) => path.clone(),
+        _ if fd.is_some() => JSValue::undefined(&context),
+        _ => return Err(invalid_arg_type(&context, "path", "of type string")),
+    };
+    let start = position_option(&context, options, "start")?;
+    let end = position_option(&context, options, "end")?;
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err(code_error(
+                &context,
+                "RangeError",
+                "ERR_OUT_OF_RANGE",
+                &format!(
+                    "The value of \"start\" is out of range. It must be <= \"end\" (here: {end}). Received {start}"
+                ),
+            ));
+        }
+    }
+    let high_water_mark = match get_number_option(&context, options, "highWaterMark")? {
+        Some(size) if size >= 1.0 => size,
+        Some(size) => {
+            return Err(code_error(
+                &context,
+                "RangeError",
+                "ERR_OUT_OF_RANGE",
+                &format!(
+                    "The value of \"options.highWaterMark\" is out of range. It must be >= 1. Received {size}"
+                ),
+            ))
+        }
+        None => DEFAULT_HIGH_WATER_MARK,
+    };
+    // Checked here, the stream keeps them as given for `fs.open`.
+    let flags =
+        get_option(&context, options, "flags").unwrap_or_else(|| JSValue::string(&context, "r"));
+    parse_flags(&context, Some(&flags), "r")?;
+    let mode = get_option(&context, options, "mode")
+        .unwrap_or_else(|| JSValue::number(&context, 0o666 as f64));
+    parse_mode(&context, Some(&mode), 0o666)?;
+    let encoding = encoding_option(&context, options)?;
+
+    let mut normalized = JSObject::<JSObjectGeneric>::new(&context);
+    macro_rules! property {
+        ($( $name: literal => $value: expr ),*) => {
+            $(
+            normalized.set_property(&context, $name, $value).unwrap();
+            )*
+        };
+    }
+    let number = |value: f64| JSValue::number(&context, value);
+    let flag = |name: &str, default: bool| {
+        JSValue::boolean(
+            &context,
+            get_bool_option(&context, options, name).unwrap_or(default),
+        )
+    };
+    property!(
+        "flags" => flags,
+        "mode" => mode,
+        "highWaterMark" => number(high_water_mark),
+        "autoClose" => flag("autoClose", true),
+        "emitClose" => flag("emitClose", true)
+    );
+    if let Some(encoding) = encoding {
+        property!("encoding" => JSValue::string(&context, encoding.name()));
+    }
+    if let Some(fd) = fd {
+        property!("fd" => number(fd));
+    }
+    if let Some(start) = start {
+        property!("start" => number(start as f64));
+    }
+    if let Some(end) = end {
+        property!("end" => number(end as f64));
+    }
+    if let Some(signal) = get_option(&context, options, "signal") {
+        property!("signal" => signal);
+    }
+    construct_with(
+        &context,
+        read_stream_class(&context).into(),
+        &[path, normalized.into()],
+    )
+}
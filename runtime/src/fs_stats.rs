@@ -0,0 +1,158 @@
+//! Implementation of the `fs.Stats` class, the information about a file
+//! returned by the stat family and given to the `fs.watchFile` listeners.
+//!
+//! The file mode is kept in the private data of the object to answer the
+//! `is*()` methods.
+
+use std::{fs::Metadata, os::unix::fs::MetadataExt};
+
+use maybe_static::maybe_static;
+use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGenericClass, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{fs::constants, js_utils::new_date};
+
+pub struct Stats {
+    mode: u32,
+}
+
+/// Get StatsClass
+pub fn get_stats_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create("Stats", None, Some(destructor)))
+}
+
+/// Milliseconds since the epoch from a (seconds, nanoseconds) timestamp.
+fn milliseconds(seconds: i64, nanoseconds: i64) -> f64 {
+    seconds as f64 * 1000.0 + nanoseconds as f64 / 1e6
+}
+
+impl Stats {
+    /// Create a new `Stats` JS object. A missing file is represented by a
+    /// zeroed object, as NodeJS does for `fs.watchFile`.
+    pub fn make(
+        context: &JSContext,
+        metadata: Option<&Metadata>,
+    ) -> JSObject<JSObjectGenericClass> {
+        let mut object = get_stats_class().make_object(context);
+        macro_rules! num_prop {
+            ($( $name: literal => $getter: ident ),*) => {
+                $(
+                let value = metadata.map_or(0.0, |metadata| metadata.$getter() as f64);
+                object
+                    .set_property(context, $name, JSValue::number(context, value))
+                    .unwrap();
+                )*
+            };
+        }
+        num_prop!(
+            "dev" => dev,
+            "ino" => ino,
+            "mode" => mode,
+            "nlink" => nlink,
+            "uid" => uid,
+            "gid" => gid,
+            "rdev" => rdev,
+            "size" => size,
+            "blksize" => blksize,
+            "blocks" => blocks
+        );
+        let times = metadata.map_or([0.0; 4], |metadata| {
+            // The statx birth time isn't exposed by the unix metadata, use
+            // the creation time when the platform gives it.
+            let birthtime = metadata
+                .created()
+                .ok()
+                .and_then(|created| created.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(
+                    milliseconds(metadata.ctime(), metadata.ctime_nsec()),
+                    |created| created.as_secs_f64() * 1000.0,
+                );
+            [
+                milliseconds(metadata.atime(), metadata.atime_nsec()),
+                milliseconds(metadata.mtime(), metadata.mtime_nsec()),
+                milliseconds(metadata.ctime(), metadata.ctime_nsec()),
+                birthtime,
+            ]
+        });
+        for (name, time) in ["atime", "mtime", "ctime", "birthtime"].iter().zip(times) {
+            object
+                .set_property(
+                    context,
+                    &format!("{name}Ms"),
+                    JSValue::number(context, time),
+                )
+                .unwrap();
+            object
+                .set_property(context, name, new_date(context, time).unwrap())
+                .unwrap();
+        }
+        macro_rules! method {
+            ($( $name: literal => $callback: ident ),*) => {
+                $(
+                object
+                    .set_property(context, $name, JSValue::callback(context, Some($callback)))
+                    .unwrap();
+                )*
+            };
+        }
+        method!(
+            "isFile" => is_file,
+            "isDirectory" => is_directory,
+            "isSymbolicLink" => is_symbolic_link,
+            "isFIFO" => is_fifo,
+            "isSocket" => is_socket,
+            "isCharacterDevice" => is_character_device,
+            "isBlockDevice" => is_block_device
+        );
+        let mode = metadata.map_or(0, |metadata| metadata.mode());
+        if object.set_private_data(Stats { mode }).is_err() {
+            panic!("cannot set private data to stats");
+        }
+        object
+    }
+
+    pub fn try_from_object<'a>(
+        context: &JSContext,
+        object: &mut JSObject,
+    ) -> Result<&'a mut Stats, JSValue> {
+        let object = object.try_as_mut_object_class(context, get_stats_class())?;
+        let stats: &mut Stats = unsafe { &mut *object.get_private_data().unwrap() };
+        Ok(stats)
+    }
+}
+
+pub unsafe extern "C" fn destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<Stats>::from_raw(object.get_private_data().unwrap()));
+}
+
+/// Define the `Stats.is*()` methods, true if the file type bits of the mode
+/// are the given `S_IF*` constant.
+macro_rules! stats_is {
+    ($( $name: ident => $kind: ident ),*) => {
+        $(
+        #[callback]
+        fn $name(
+            context: JSContext,
+            _function: JSObject,
+            mut this: JSObject,
+            _arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            let stats = Stats::try_from_object(&context, &mut this)?;
+            let kind = stats.mode & constants::S_IFMT as u32;
+            Ok(JSValue::boolean(&context, kind == constants::$kind as u32))
+        }
+        )*
+    };
+}
+
+stats_is!(
+    is_file => S_IFREG,
+    is_directory => S_IFDIR,
+    is_symbolic_link => S_IFLNK,
+    is_fifo => S_IFIFO,
+    is_socket => S_IFSOCK,
+    is_character_device => S_IFCHR,
+    is_block_device => S_IFBLK
+);
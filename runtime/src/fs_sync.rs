@@ -0,0 +1,949 @@
+//! Synchronous file system API: `fs.readFileSync`, `fs.statSync`...
+//!
+//! Each call is split in two. The arguments are read on the javascript
+//! thread and give a `Work`, the blocking part, which gives back how to build
+//! the result once on the javascript thread again. The synchronous API runs
+//! the work right away and throws the error, the callback API of `fs_callback`
+//! and the promise API of `fs_promise` run it on the blocking pool.
+//!
+//! `openSync` gives raw file descriptors to javascript, they are only closed
+//! by `closeSync`.
+
+use std::{
+    ffi::CString,
+    fs::{File, Metadata, Permissions},
+    io::{self, Read, Write},
+    mem::ManuallyDrop,
+    os::unix::{fs::PermissionsExt, io::FromRawFd},
+    path::Path,
+};
+
+use rusty_jsc::{JSContext, JSObject, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{
+    encoding::{
+        bytes_to_value, data_argument, encoding_option, is_array_buffer_view, view_bytes,
+        write_view_bytes, Encoding,
+    },
+    errors::{code_error, from_errno, invalid_arg_type, system_error},
+    fs_cp::{self, copy_mode},
+    fs_promise::{
+        create_dir, entries_array, id_argument, len_argument, make_temp_dir, mkdir_options,
+        permission_argument, read_entries, readdir_options, remove, remove_dir, rm_error,
+        rm_options, symlink_type, time_argument, timespec,
+    },
+    fs_stats::Stats,
+    js_utils::call_global,
+    options::{
+        get_bool_option, get_encoding, get_number_option, get_option, parse_flags, parse_mode,
+        path_argument,
+    },
+};
+
+/// Build the result of a call on the javascript thread: the values given to
+/// the callback after the error, the first one is returned by the
+/// synchronous API.
+pub type Settle = Box<dyn FnOnce(&JSContext) -> Result<Vec<JSValue>, JSValue> + Send>;
+
+/// The blocking part of a call.
+pub type Work = Box<dyn FnOnce() -> Settle + Send>;
+
+/// An error built on the javascript thread.
+type JsError = Box<dyn FnOnce(&JSContext) -> JSValue + Send>;
+
+/// Create a `Work` from a blocking `call` and the conversion of its result.
+fn work<T, C, V>(call: C, to_values: V) -> Work
+where
+    T: Send + 'static,
+    C: FnOnce() -> Result<T, JsError> + Send + 'static,
+    V: FnOnce(&JSContext, T) -> Vec<JSValue> + Send + 'static,
+{
+    Box::new(move || {
+        let result = call();
+        Box::new(move |context: &JSContext| match result {
+            Ok(value) => Ok(to_values(context, value)),
+            Err(error) => Err(error(context)),
+        })
+    })
+}
+
+/// The conversion of the calls without result.
+fn nothing(_context: &JSContext, _: ()) -> Vec<JSValue> {
+    vec![]
+}
+
+/// Run a call synchronously and return its first value.
+fn run_sync(context: &JSContext, work: Work) -> Result<JSValue, JSValue> {
+    let values = work()(context)?;
+    Ok(values
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| JSValue::undefined(context)))
+}
+
+/// Keep the error of a syscall, it becomes a system error.
+fn sys<T>(result: io::Result<T>, syscall: &'static str, path: &str) -> Result<T, JsError> {
+    result.map_err(|err| {
+        let path = path.to_string();
+        Box::new(move |context: &JSContext| system_error(context, &err, syscall, Some(&path), None))
+            as JsError
+    })
+}
+
+/// Same as `sys` for the calls on a file descriptor, without path.
+fn sys_fd<T>(result: io::Result<T>, syscall: &'static str) -> Result<T, JsError> {
+    result.map_err(|err| {
+        Box::new(move |context: &JSContext| system_error(context, &err, syscall, None, None))
+            as JsError
+    })
+}
+
+/// Same as `sys` for the calls involving two paths.
+fn sys_with_dest<T>(
+    result: io::Result<T>,
+    syscall: &'static str,
+    path: &str,
+    dest: &str,
+) -> Result<T, JsError> {
+    result.map_err(|err| {
+        let (path, dest) = (path.to_string(), dest.to_string());
+        Box::new(move |context: &JSContext| {
+            system_error(context, &err, syscall, Some(&path), Some(&dest))
+        }) as JsError
+    })
+}
+
+/// Run a libc call taking a path, expected to return -1 and to set errno on
+/// failure.
+pub(crate) fn libc_call<F>(path: &str, call: F) -> io::Result<libc::c_int>
+where
+    F: FnOnce(*const libc::c_char) -> libc::c_int,
+{
+    let path = CString::new(path).map_err(|_| from_errno(libc::EINVAL))?;
+    match call(path.as_ptr()) {
+        -1 => Err(io::Error::last_os_error()),
+        result => Ok(result),
+    }
+}
+
+/// Read a file descriptor argument, a positive 32-bit integer.
+fn fd_argument(context: &JSContext, arguments: &[JSValue], index: usize) -> Result<i32, JSValue> {
+    match arguments.get(index) {
+        Some(value) if value.is_number(context) => {
+            let fd = value.to_number(context).unwrap();
+            if fd.fract() != 0.0 || !(0.0..=i32::MAX as f64).contains(&fd) {
+                return Err(code_error(
+                    context,
+                    "RangeError",
+                    "ERR_OUT_OF_RANGE",
+                    &format!(
+                        "The value of \"fd\" is out of range. It must be >= 0 && <= 2147483647. Received {fd}"
+                    ),
+                ));
+            }
+            Ok(fd as i32)
+        }
+        _ => Err(invalid_arg_type(context, "fd", "of type number")),
+    }
+}
+
+/// Borrow a file descriptor owned by javascript as a `File`, without closing
+/// it on drop.
+fn borrow_fd(fd: i32) -> ManuallyDrop<File> {
+    ManuallyDrop::new(unsafe { File::from_raw_fd(fd) })
+}
+
+/// Open a file with `open(2)` flags and mode.
+pub(crate) fn open_file(path: &str, flags: libc::c_int, mode: u32) -> io::Result<File> {
+    let fd = libc_call(path, |path| unsafe {
+        libc::open(path, flags | libc::O_CLOEXEC, mode as libc::c_uint)
+    })?;
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+/// The file given to `readFile` and `writeFile`: a path or a file
+/// descriptor.
+enum FileArgument {
+    Path(String),
+    Fd(i32),
+}
+
+fn file_argument(context: &JSContext, arguments: &[JSValue]) -> Result<FileArgument, JSValue> {
+    match arguments.first() {
+        Some(value) if value.is_number(context) => {
+            Ok(FileArgument::Fd(fd_argument(context, arguments, 0)?))
+        }
+        _ => Ok(FileArgument::Path(path_argument(
+            context, arguments, 0, "path",
+        )?)),
+    }
+}
+
+/// `access(path[, mode])`.
+pub fn access(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let mode = match arguments.get(1) {
+        Some(mode) if mode.is_number(context) => {
+            let mode = mode.to_number(context).unwrap();
+            if mode.fract() != 0.0 || !(0.0..=7.0).contains(&mode) {
+                return Err(code_error(
+                    context,
+                    "RangeError",
+                    "ERR_OUT_OF_RANGE",
+                    &format!(
+                        "The value of \"mode\" is out of range. It must be an integer >= 0 && <= 7. Received {mode}"
+                    ),
+                ));
+            }
+            mode as libc::c_int
+        }
+        Some(mode) if !mode.is_undefined(context) => {
+            return Err(invalid_arg_type(context, "mode", "of type number"))
+        }
+        _ => libc::F_OK,
+    };
+    Ok(work(
+        move || {
+            let result = libc_call(&path, |path| unsafe { libc::access(path, mode) });
+            sys(result.map(|_| ()), "access", &path)
+        },
+        nothing,
+    ))
+}
+
+/// `exists(path)`, never fails.
+pub fn exists(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path").ok();
+    Ok(work(
+        move || Ok(path.map_or(false, |path| std::fs::metadata(path).is_ok())),
+        |context, exists| vec![JSValue::boolean(context, exists)],
+    ))
+}
+
+/// `readFile(path[, options])`, gives a string if an encoding is set, an
+/// `Uint8Array` otherwise.
+pub fn read_file(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let file = file_argument(context, arguments)?;
+    let options = arguments.get(1);
+    let encoding = encoding_option(context, options)?;
+    let flags = parse_flags(context, get_option(context, options, "flag").as_ref(), "r")?;
+    Ok(work(
+        move || {
+            let mut bytes = vec![];
+            match file {
+                FileArgument::Fd(fd) => {
+                    sys_fd(borrow_fd(fd).read_to_end(&mut bytes), "read")?;
+                }
+                FileArgument::Path(path) => {
+                    let mut file = sys(open_file(&path, flags, 0o666), "open", &path)?;
+                    sys(file.read_to_end(&mut bytes), "read", &path)?;
+                }
+            }
+            Ok(bytes)
+        },
+        move |context, bytes| vec![bytes_to_value(context, bytes, encoding)],
+    ))
+}
+
+/// Shared by `writeFile` and `appendFile`, they only differ by the default
+/// flag.
+fn write_to_file(
+    context: &JSContext,
+    arguments: &[JSValue],
+    default_flag: &str,
+) -> Result<Work, JSValue> {
+    let file = file_argument(context, arguments)?;
+    let options = arguments.get(2);
+    let encoding = encoding_option(context, options)?;
+    let data = data_argument(context, arguments.get(1), encoding)?;
+    let flags = parse_flags(
+        context,
+        get_option(context, options, "flag").as_ref(),
+        default_flag,
+    )?;
+    let mode = parse_mode(
+        context,
+        get_option(context, options, "mode").as_ref(),
+        0o666,
+    )?;
+    Ok(work(
+        move || match file {
+            FileArgument::Fd(fd) => sys_fd(borrow_fd(fd).write_all(&data), "write"),
+            FileArgument::Path(path) => {
+                let mut file = sys(open_file(&path, flags, mode), "open", &path)?;
+                sys(file.write_all(&data), "write", &path)
+            }
+        },
+        nothing,
+    ))
+}
+
+/// `writeFile(file, data[, options])`.
+pub fn write_file(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    write_to_file(context, arguments, "w")
+}
+
+/// `appendFile(path, data[, options])`.
+pub fn append_file(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    write_to_file(context, arguments, "a")
+}
+
+/// Shared by `stat` and `lstat`. With `throwIfNoEntry: false`, a missing
+/// file gives undefined instead of an error.
+fn stat_with(
+    context: &JSContext,
+    arguments: &[JSValue],
+    metadata: fn(&str) -> io::Result<Metadata>,
+    syscall: &'static str,
+) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let throw_if_no_entry =
+        get_bool_option(context, arguments.get(1), "throwIfNoEntry").unwrap_or(true);
+    Ok(work(
+        move || match metadata(&path) {
+            Ok(metadata) => Ok(Some(metadata)),
+            Err(err) if !throw_if_no_entry && err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => sys(Err(err), syscall, &path),
+        },
+        |context, metadata| match metadata {
+            Some(metadata) => vec![Stats::make(context, Some(&metadata)).into()],
+            None => vec![JSValue::undefined(context)],
+        },
+    ))
+}
+
+/// `stat(path[, options])`, follows the symbolic links.
+pub fn stat(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    stat_with(context, arguments, |path| std::fs::metadata(path), "stat")
+}
+
+/// `lstat(path[, options])`, stats the symbolic links themselves.
+pub fn lstat(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    stat_with(
+        context,
+        arguments,
+        |path| std::fs::symlink_metadata(path),
+        "lstat",
+    )
+}
+
+/// `fstat(fd[, options])`.
+pub fn fstat(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let fd = fd_argument(context, arguments, 0)?;
+    Ok(work(
+        move || sys_fd(borrow_fd(fd).metadata(), "fstat"),
+        |context, metadata| vec![Stats::make(context, Some(&metadata)).into()],
+    ))
+}
+
+/// `readdir(path[, options])`.
+pub fn readdir(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let options = readdir_options(context, arguments.get(1))?;
+    let recursive = options.recursive;
+    Ok(work(
+        move || sys(read_entries(&path, recursive), "scandir", &path),
+        move |context, entries| vec![entries_array(context, entries, &options)],
+    ))
+}
+
+/// `mkdir(path[, options])`, gives the first directory created in recursive
+/// mode.
+pub fn mkdir(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let options = mkdir_options(context, arguments.get(1))?;
+    Ok(work(
+        move || sys(create_dir(&path, &options), "mkdir", &path),
+        |context, first_created| {
+            vec![match first_created {
+                Some(first) => JSValue::string(context, first.to_string_lossy()),
+                None => JSValue::undefined(context),
+            }]
+        },
+    ))
+}
+
+/// `mkdtemp(prefix[, options])`.
+pub fn mkdtemp(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let prefix = path_argument(context, arguments, 0, "prefix")?;
+    get_encoding(context, arguments.get(1))?;
+    let template = format!("{prefix}XXXXXX");
+    Ok(work(
+        move || sys(make_temp_dir(&template), "mkdtemp", &template),
+        |context, path| vec![JSValue::string(context, path.to_string_lossy())],
+    ))
+}
+
+/// `rmdir(path[, options])`.
+pub fn rmdir(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let options = rm_options(context, arguments.get(1))?;
+    Ok(work(
+        move || sys(remove_dir(&path, &options), "rmdir", &path),
+        nothing,
+    ))
+}
+
+/// `rm(path[, options])`.
+pub fn rm(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let options = rm_options(context, arguments.get(1))?;
+    Ok(work(
+        move || {
+            remove(&path, &options).map_err(|error| {
+                Box::new(move |context: &JSContext| rm_error(context, &error, &path)) as JsError
+            })
+        },
+        nothing,
+    ))
+}
+
+/// `rename(oldPath, newPath)`.
+pub fn rename(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let old_path = path_argument(context, arguments, 0, "oldPath")?;
+    let new_path = path_argument(context, arguments, 1, "newPath")?;
+    Ok(work(
+        move || {
+            let result = std::fs::rename(&old_path, &new_path);
+            sys_with_dest(result, "rename", &old_path, &new_path)
+        },
+        nothing,
+    ))
+}
+
+/// `link(existingPath, newPath)`.
+pub fn link(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let existing_path = path_argument(context, arguments, 0, "existingPath")?;
+    let new_path = path_argument(context, arguments, 1, "newPath")?;
+    Ok(work(
+        move || {
+            let result = std::fs::hard_link(&existing_path, &new_path);
+            sys_with_dest(result, "link", &existing_path, &new_path)
+        },
+        nothing,
+    ))
+}
+
+/// `symlink(target, path[, type])`.
+pub fn symlink(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let target = path_argument(context, arguments, 0, "target")?;
+    let path = path_argument(context, arguments, 1, "path")?;
+    symlink_type(context, arguments.get(2))?;
+    Ok(work(
+        move || {
+            let result = std::os::unix::fs::symlink(&target, &path);
+            sys_with_dest(result, "symlink", &target, &path)
+        },
+        nothing,
+    ))
+}
+
+/// `copyFile(src, dest[, mode])`.
+pub fn copy_file(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let src = path_argument(context, arguments, 0, "src")?;
+    let dest = path_argument(context, arguments, 1, "dest")?;
+    let mode = copy_mode(context, arguments.get(2))?;
+    Ok(work(
+        move || {
+            let result = fs_cp::copy_file(Path::new(&src), Path::new(&dest), mode);
+            sys_with_dest(result, "copyfile", &src, &dest)
+        },
+        nothing,
+    ))
+}
+
+/// `readlink(path[, options])`.
+pub fn readlink(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    get_encoding(context, arguments.get(1))?;
+    Ok(work(
+        move || sys(std::fs::read_link(&path), "readlink", &path),
+        |context, target| vec![JSValue::string(context, target.to_string_lossy())],
+    ))
+}
+
+/// `realpath(path[, options])`.
+pub fn realpath(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    get_encoding(context, arguments.get(1))?;
+    Ok(work(
+        move || sys(std::fs::canonicalize(&path), "realpath", &path),
+        |context, real_path| vec![JSValue::string(context, real_path.to_string_lossy())],
+    ))
+}
+
+/// `unlink(path)`.
+pub fn unlink(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    Ok(work(
+        move || sys(std::fs::remove_file(&path), "unlink", &path),
+        nothing,
+    ))
+}
+
+/// `chmod(path, mode)`.
+pub fn chmod(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let mode = permission_argument(context, arguments.get(1))?;
+    Ok(work(
+        move || {
+            let result = std::fs::set_permissions(&path, Permissions::from_mode(mode));
+            sys(result, "chmod", &path)
+        },
+        nothing,
+    ))
+}
+
+/// Shared by `chown` and `lchown`, they only differ by the libc call.
+fn chown_with(
+    context: &JSContext,
+    arguments: &[JSValue],
+    call: unsafe extern "C" fn(*const libc::c_char, libc::uid_t, libc::gid_t) -> libc::c_int,
+    syscall: &'static str,
+) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let uid = id_argument(context, arguments, 1, "uid")?;
+    let gid = id_argument(context, arguments, 2, "gid")?;
+    Ok(work(
+        move || {
+            let result = libc_call(&path, |path| unsafe { call(path, uid, gid) });
+            sys(result.map(|_| ()), syscall, &path)
+        },
+        nothing,
+    ))
+}
+
+/// `chown(path, uid, gid)`, follows the symbolic links.
+pub fn chown(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    chown_with(context, arguments, libc::chown, "chown")
+}
+
+/// `lchown(path, uid, gid)`, changes the owner of the link itself.
+pub fn lchown(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    chown_with(context, arguments, libc::lchown, "lchown")
+}
+
+/// Shared by `utimes` and `lutimes`, they only differ by the `utimensat`
+/// flags.
+fn utimes_with(
+    context: &JSContext,
+    arguments: &[JSValue],
+    flags: libc::c_int,
+    syscall: &'static str,
+) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let atime = time_argument(context, arguments, 1, "atime")?;
+    let mtime = time_argument(context, arguments, 2, "mtime")?;
+    Ok(work(
+        move || {
+            let times = [timespec(atime), timespec(mtime)];
+            let result = libc_call(&path, |path| unsafe {
+                libc::utimensat(libc::AT_FDCWD, path, times.as_ptr(), flags)
+            });
+            sys(result.map(|_| ()), syscall, &path)
+        },
+        nothing,
+    ))
+}
+
+/// `utimes(path, atime, mtime)`.
+pub fn utimes(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    utimes_with(context, arguments, 0, "utime")
+}
+
+/// `lutimes(path, atime, mtime)`.
+pub fn lutimes(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    utimes_with(context, arguments, libc::AT_SYMLINK_NOFOLLOW, "lutime")
+}
+
+/// `truncate(path[, len])`.
+pub fn truncate(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let len = len_argument(context, arguments.get(1))?;
+    Ok(work(
+        move || {
+            let file = std::fs::OpenOptions::new().write(true).open(&path);
+            let file = sys(file, "open", &path)?;
+            sys(file.set_len(len), "ftruncate", &path)
+        },
+        nothing,
+    ))
+}
+
+/// `open(path[, flags[, mode]])`, gives a file descriptor.
+pub fn open(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let path = path_argument(context, arguments, 0, "path")?;
+    let flags = parse_flags(context, arguments.get(1), "r")?;
+    let mode = parse_mode(context, arguments.get(2), 0o666)?;
+    Ok(work(
+        move || {
+            let result = libc_call(&path, |path| unsafe {
+                libc::open(path, flags | libc::O_CLOEXEC, mode as libc::c_uint)
+            });
+            sys(result, "open", &path)
+        },
+        |context, fd| vec![JSValue::number(context, fd as f64)],
+    ))
+}
+
+/// `close(fd)`.
+pub fn close(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let fd = fd_argument(context, arguments, 0)?;
+    Ok(work(
+        move || {
+            if unsafe { libc::close(fd) } == -1 {
+                return sys_fd(Err(io::Error::last_os_error()), "close");
+            }
+            Ok(())
+        },
+        nothing,
+    ))
+}
+
+/// Read an optional integer argument of `read` and `write`.
+fn integer_argument(
+    context: &JSContext,
+    value: Option<&JSValue>,
+    name: &str,
+) -> Result<Option<f64>, JSValue> {
+    match value {
+        Some(value) if value.is_number(context) => {
+            let number = value.to_number(context).unwrap();
+            if number.fract() != 0.0 {
+                return Err(code_error(
+                    context,
+                    "RangeError",
+                    "ERR_OUT_OF_RANGE",
+                    &format!(
+                        "The value of \"{name}\" is out of range. It must be an integer. Received {number}"
+                    ),
+                ));
+            }
+            Ok(Some(number))
+        }
+        Some(value) if !value.is_undefined(context) && !value.is_null(context) => {
+            Err(invalid_arg_type(context, name, "of type number"))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The `offset`, `length` and `position` given to `read` and `write`.
+type RangeArguments = (Option<f64>, Option<f64>, Option<f64>);
+
+/// Read the `offset`, `length` and `position` of `read` and `write`, given
+/// as arguments from `index` or in an options object.
+fn range_arguments(
+    context: &JSContext,
+    arguments: &[JSValue],
+    index: usize,
+) -> Result<RangeArguments, JSValue> {
+    match arguments.get(index) {
+        Some(options) if options.is_object(context) => Ok((
+            get_number_option(context, Some(options), "offset")?,
+            get_number_option(context, Some(options), "length")?,
+            get_number_option(context, Some(options), "position")?,
+        )),
+        offset => Ok((
+            integer_argument(context, offset, "offset")?,
+            integer_argument(context, arguments.get(index + 1), "length")?,
+            integer_argument(context, arguments.get(index + 2), "position")?,
+        )),
+    }
+}
+
+/// Check that `offset` and `length` fit in a buffer of `size` bytes. A
+/// missing length means up to the end of the buffer.
+fn check_range(
+    context: &JSContext,
+    offset: Option<f64>,
+    length: Option<f64>,
+    size: usize,
+) -> Result<(usize, usize), JSValue> {
+    let offset = offset.unwrap_or(0.0);
+    if offset < 0.0 || offset > size as f64 {
+        return Err(code_error(
+            context,
+            "RangeError",
+            "ERR_OUT_OF_RANGE",
+            &format!(
+                "The value of \"offset\" is out of range. It must be >= 0 && <= {size}. Received {offset}"
+            ),
+        ));
+    }
+    let length = length.unwrap_or(size as f64 - offset);
+    if length < 0.0 || offset + length > size as f64 {
+        return Err(code_error(
+            context,
+            "RangeError",
+            "ERR_OUT_OF_RANGE",
+            &format!(
+                "The value of \"length\" is out of range. It must be >= 0 && <= {}. Received {length}",
+                size as f64 - offset
+            ),
+        ));
+    }
+    Ok((offset as usize, length as usize))
+}
+
+/// Read or write at `position` with pread/pwrite, or at the current position
+/// of the file if it's missing or negative.
+fn positioned<F, G>(position: Option<f64>, at: F, current: G) -> io::Result<usize>
+where
+    F: FnOnce(libc::off_t) -> libc::ssize_t,
+    G: FnOnce() -> libc::ssize_t,
+{
+    let result = match position {
+        Some(position) if position >= 0.0 => at(position as libc::off_t),
+        _ => current(),
+    };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+/// `read(fd, buffer, offset, length, position)` or `read(fd, buffer[,
+/// options])`. Gives the number of bytes read and the buffer.
+pub fn read(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let fd = fd_argument(context, arguments, 0)?;
+    let buffer = match arguments.get(1) {
+        Some(buffer) if is_array_buffer_view(context, buffer) => buffer,
+        _ => {
+            return Err(invalid_arg_type(
+                context,
+                "buffer",
+                "an instance of Buffer, TypedArray, or DataView",
+            ))
+        }
+    };
+    let size = buffer
+        .to_object(context)?
+        .get_property(context, "byteLength")?
+        .to_number(context)? as usize;
+    let (offset, length, position) = range_arguments(context, arguments, 2)?;
+    let (offset, length) = check_range(context, offset, length, size)?;
+    let buffer: JSObject<JSProtected> = buffer.clone().into_protected_object(context);
+    Ok(work(
+        move || {
+            let mut bytes = vec![0u8; length];
+            let pointer = bytes.as_mut_ptr() as *mut libc::c_void;
+            let read = sys_fd(
+                positioned(
+                    position,
+                    |position| unsafe { libc::pread(fd, pointer, length, position) },
+                    || unsafe { libc::read(fd, pointer, length) },
+                ),
+                "read",
+            )?;
+            bytes.truncate(read);
+            Ok(bytes)
+        },
+        move |context, bytes| {
+            let buffer: JSValue = buffer.into();
+            write_view_bytes(context, &buffer, offset, &bytes);
+            vec![JSValue::number(context, bytes.len() as f64), buffer]
+        },
+    ))
+}
+
+/// The data given to `write`, given back to the callback as it was received.
+enum WrittenData {
+    View(JSObject<JSProtected>),
+    String(String),
+}
+
+/// `write(fd, buffer, offset[, length[, position]])` or `write(fd,
+/// string[, position[, encoding]])`. Gives the number of bytes written and
+/// the data.
+pub fn write(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let fd = fd_argument(context, arguments, 0)?;
+    let (bytes, position, data) = match arguments.get(1) {
+        Some(data) if data.is_string(context) => {
+            let encoding = match arguments.get(3) {
+                Some(encoding) if encoding.is_string(context) => {
+                    let name = encoding.to_js_string(context).unwrap().to_string();
+                    Encoding::parse(context, &name)?
+                }
+                _ => None,
+            };
+            let string = data.to_js_string(context).unwrap().to_string();
+            (
+                encoding.unwrap_or(Encoding::Utf8).encode(&string),
+                integer_argument(context, arguments.get(2), "position")?,
+                WrittenData::String(string),
+            )
+        }
+        Some(data) if is_array_buffer_view(context, data) => {
+            let bytes = view_bytes(context, data);
+            let (offset, length, position) = range_arguments(context, arguments, 2)?;
+            let (offset, length) = check_range(context, offset, length, bytes.len())?;
+            (
+                bytes[offset..offset + length].to_vec(),
+                position,
+                WrittenData::View(data.clone().into_protected_object(context)),
+            )
+        }
+        _ => {
+            return Err(invalid_arg_type(
+                context,
+                "buffer",
+                "of type string or an instance of Buffer, TypedArray, or DataView",
+            ))
+        }
+    };
+    Ok(work(
+        move || {
+            let pointer = bytes.as_ptr() as *const libc::c_void;
+            sys_fd(
+                positioned(
+                    position,
+                    |position| unsafe { libc::pwrite(fd, pointer, bytes.len(), position) },
+                    || unsafe { libc::write(fd, pointer, bytes.len()) },
+                ),
+                "write",
+            )
+        },
+        move |context, written| {
+            let data = match data {
+                WrittenData::View(view) => view.into(),
+                WrittenData::String(string) => JSValue::string(context, string),
+            };
+            vec![JSValue::number(context, written as f64), data]
+        },
+    ))
+}
+
+/// Maximum number of buffers of a vectored write (IOV_MAX on Linux).
+const IOV_MAX: usize = 1024;
+
+/// `writev(fd, buffers[, position])`, writes an array of `ArrayBufferView`
+/// with vectored writes. Gives the number of bytes written and the buffers.
+pub fn writev(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let fd = fd_argument(context, arguments, 0)?;
+    let buffers = match arguments.get(1) {
+        Some(buffers)
+            if call_global(context, "Array.isArray", &[buffers.clone()])?.to_bool(context) =>
+        {
+            buffers.to_object(context)?
+        }
+        _ => {
+            return Err(invalid_arg_type(
+                context,
+                "buffers",
+                "an instance of ArrayBufferView[]",
+            ))
+        }
+    };
+    let length = buffers
+        .get_property(context, "length")?
+        .to_number(context)? as usize;
+    let mut chunks = Vec::with_capacity(length);
+    for index in 0..length {
+        let buffer = buffers.get_property(context, &index.to_string())?;
+        if !is_array_buffer_view(context, &buffer) {
+            return Err(invalid_arg_type(
+                context,
+                "buffers",
+                "an instance of ArrayBufferView[]",
+            ));
+        }
+        chunks.push(view_bytes(context, &buffer));
+    }
+    let mut position = integer_argument(context, arguments.get(2), "position")?;
+    let buffers = buffers.into_protected_object(context);
+    Ok(work(
+        move || {
+            let mut written = 0;
+            for batch in chunks.chunks(IOV_MAX) {
+                let iovecs: Vec<libc::iovec> = batch
+                    .iter()
+                    .map(|chunk| libc::iovec {
+                        iov_base: chunk.as_ptr() as *mut libc::c_void,
+                        iov_len: chunk.len(),
+                    })
+                    .collect();
+                let count = iovecs.len() as libc::c_int;
+                let batch_written = sys_fd(
+                    positioned(
+                        position,
+                        |position| unsafe { libc::pwritev(fd, iovecs.as_ptr(), count, position) },
+                        || unsafe { libc::writev(fd, iovecs.as_ptr(), count) },
+                    ),
+                    "write",
+                )?;
+                written += batch_written;
+                position = position.map(|position| position + batch_written as f64);
+                // A partial write ends the call, as a single writev(2).
+                if batch_written < batch.iter().map(Vec::len).sum() {
+                    break;
+                }
+            }
+            Ok(written)
+        },
+        move |context, written| vec![JSValue::number(context, written as f64), buffers.into()],
+    ))
+}
+
+/// `fsync(fd)`, flushes the data and the metadata of the file to the disk.
+pub fn fsync(context: &JSContext, arguments: &[JSValue]) -> Result<Work, JSValue> {
+    let fd = fd_argument(context, arguments, 0)?;
+    Ok(work(
+        move || sys_fd(borrow_fd(fd).sync_all(), "fsync"),
+        nothing,
+    ))
+}
+
+/// Define the synchronous calls, they run their `Work` right away.
+macro_rules! sync_call {
+    ($( $name: ident => $call: ident ),*) => {
+        $(
+        #[callback]
+        pub fn $name(
+            context: JSContext,
+            _function: JSObject,
+            _this: JSObject,
+            arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            run_sync(&context, $call(&context, arguments)?)
+        }
+        )*
+    };
+}
+
+sync_call!(
+    access_sync => access,
+    append_file_sync => append_file,
+    chmod_sync => chmod,
+    chown_sync => chown,
+    close_sync => close,
+    copy_file_sync => copy_file,
+    exists_sync => exists,
+    fstat_sync => fstat,
+    fsync_sync => fsync,
+    lchown_sync => lchown,
+    link_sync => link,
+    lstat_sync => lstat,
+    lutimes_sync => lutimes,
+    mkdir_sync => mkdir,
+    mkdtemp_sync => mkdtemp,
+    open_sync => open,
+    read_file_sync => read_file,
+    read_sync => read,
+    readdir_sync => readdir,
+    readlink_sync => readlink,
+    realpath_sync => realpath,
+    rename_sync => rename,
+    rm_sync => rm,
+    rmdir_sync => rmdir,
+    stat_sync => stat,
+    symlink_sync => symlink,
+    truncate_sync => truncate,
+    unlink_sync => unlink,
+    utimes_sync => utimes,
+    write_file_sync => write_file,
+    write_sync => write,
+    writev_sync => writev
+);
@@ -0,0 +1,843 @@
+//! File system watchers: `fsPromises.watch`, `fs.watch` and the polling
+//! `fs.watchFile` / `fs.unwatchFile`.
+//!
+//! `watch` is backed by inotify. The inotify file descriptor is registered in
+//! the tokio reactor, and a watcher runs as an event loop action until it's
+//! closed, so the process stays alive while something is watched. A
+//! watcher created with `persistent: false`, or `unref()`, runs out of the
+//! event loop instead, it moves from one to the other on `ref()` and
+//! `unref()`. Inotify
+//! isn't recursive, in recursive mode a watch is added on every subdirectory,
+//! including the ones created after the start.
+//!
+//! `watchFile` polls the stats of a file at a given interval and calls the
+//! listeners with the current and the previous `Stats` when they differ.
+
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    fs::Metadata,
+    io,
+    os::unix::{
+        ffi::OsStrExt,
+        fs::MetadataExt,
+        io::{AsRawFd, FromRawFd, OwnedFd},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use maybe_static::{maybe_static, maybe_static_unsafe};
+use rusty_jsc::{JSClass, JSContext, JSObject, JSObjectGeneric, JSPromise, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+use tokio::{
+    io::unix::AsyncFd,
+    sync::{mpsc, oneshot, watch},
+};
+
+use crate::{
+    errors::{abort_error, invalid_arg_type, print_uncaught, system_error},
+    event_loop::{self, get_hold, Action},
+    events::{emit, listen, make_emitter},
+    fs_promise::absolute,
+    fs_stats::Stats,
+    js_utils::{bind, iterator_result, same_value, set_symbol_property},
+    options::{get_bool_option, get_encoding, get_number_option, get_option, path_argument},
+};
+
+/// Events watched, the same as libuv.
+const WATCH_MASK: u32 = libc::IN_ATTRIB
+    | libc::IN_CREATE
+    | libc::IN_MODIFY
+    | libc::IN_DELETE
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO;
+
+/// Size of the fixed part of an inotify event, the name follows.
+const EVENT_HEADER_SIZE: usize = std::mem::size_of::<libc::inotify_event>();
+
+/// An event as NodeJS gives it: `eventType` is 'rename' or 'change'.
+pub struct WatchEvent {
+    event_type: &'static str,
+    filename: String,
+}
+
+/// An inotify instance watching a file, a directory or a tree.
+pub struct Inotify {
+    fd: AsyncFd<OwnedFd>,
+    /// Watch descriptors and the watched directories, relative to the root.
+    watches: HashMap<i32, PathBuf>,
+    root: PathBuf,
+    /// Name reported in the events if the root is a file.
+    file_name: Option<String>,
+    recursive: bool,
+}
+
+impl Inotify {
+    pub fn new(path: &str, recursive: bool) -> io::Result<Self> {
+        let root = PathBuf::from(path);
+        let metadata = std::fs::metadata(&root)?;
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let file_name = if metadata.is_dir() {
+            None
+        } else {
+            root.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        };
+        let mut inotify = Inotify {
+            fd: AsyncFd::new(fd)?,
+            watches: HashMap::new(),
+            root,
+            file_name,
+            recursive,
+        };
+        inotify.add_watch(PathBuf::new())?;
+        Ok(inotify)
+    }
+
+    /// Watch `relative`, and all its subdirectories in recursive mode.
+    fn add_watch(&mut self, relative: PathBuf) -> io::Result<()> {
+        let path = self.root.join(&relative);
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::from_raw_os_error(libc::EINVAL))?;
+        let wd =
+            unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+        if wd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        self.watches.insert(wd, relative.clone());
+        if self.recursive && self.file_name.is_none() {
+            for entry in std::fs::read_dir(&path)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    self.add_watch(relative.join(entry.file_name()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait for the next batch of events.
+    pub async fn read_events(&mut self) -> io::Result<Vec<WatchEvent>> {
+        let mut buffer = [0u8; 4096];
+        let read = loop {
+            let mut guard = self.fd.readable().await?;
+            let result = guard.try_io(|fd| {
+                let read = unsafe {
+                    libc::read(
+                        fd.as_raw_fd(),
+                        buffer.as_mut_ptr() as *mut libc::c_void,
+                        buffer.len(),
+                    )
+                };
+                if read == -1 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(read as usize)
+                }
+            });
+            match result {
+                Ok(read) => break read?,
+                // The readiness was a false positive, wait again.
+                Err(_would_block) => continue,
+            }
+        };
+
+        let mut events = vec![];
+        let mut offset = 0;
+        while offset + EVENT_HEADER_SIZE <= read {
+            let field = |index: usize| {
+                let start = offset + index * 4;
+                u32::from_ne_bytes(buffer[start..start + 4].try_into().unwrap())
+            };
+            let (wd, mask, len) = (field(0) as i32, field(1), field(3) as usize);
+            let name_bytes = &buffer[offset + EVENT_HEADER_SIZE..offset + EVENT_HEADER_SIZE + len];
+            // The name is padded with null bytes.
+            let name = String::from_utf8_lossy(name_bytes)
+                .trim_end_matches('\0')
+                .to_string();
+            offset += EVENT_HEADER_SIZE + len;
+
+            if mask & libc::IN_IGNORED != 0 {
+                self.watches.remove(&wd);
+                continue;
+            }
+            let relative = match self.watches.get(&wd) {
+                Some(relative) => relative.clone(),
+                None => continue,
+            };
+            let filename = match &self.file_name {
+                Some(file_name) => file_name.clone(),
+                None if name.is_empty() => self
+                    .root
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                None => relative.join(&name).to_string_lossy().into_owned(),
+            };
+            if self.recursive
+                && mask & libc::IN_ISDIR != 0
+                && mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0
+            {
+                // The directory can already be gone, ignore the failure.
+                let _ = self.add_watch(relative.join(&name));
+            }
+            events.push(WatchEvent {
+                event_type: if mask & (libc::IN_ATTRIB | libc::IN_MODIFY) != 0 {
+                    "change"
+                } else {
+                    "rename"
+                },
+                filename,
+            });
+        }
+        Ok(events)
+    }
+}
+
+/// Where the events of a watcher go.
+pub enum WatchSink {
+    /// Consumed by the async iterator of `fsPromises.watch`, the error of
+    /// the watcher rejects its pending `next()`.
+    Iterator(mpsc::UnboundedSender<io::Result<WatchEvent>>),
+    /// Emitted by a `FSWatcher`.
+    Watcher(JSObject<JSProtected>),
+}
+
+/// A running watcher, see `exec_watch`.
+pub struct WatchAction {
+    inotify: Inotify,
+    sink: WatchSink,
+    path: String,
+    cancel: oneshot::Receiver<()>,
+    /// True if the watcher keeps the process alive, changed by `ref()` and
+    /// `unref()`.
+    persistent: watch::Receiver<bool>,
+}
+
+/// Run a watcher until it's closed or until an error happens.
+pub async fn exec_watch(mut action: WatchAction) {
+    let persistent = *action.persistent.borrow();
+    loop {
+        let events = tokio::select! {
+            events = action.inotify.read_events() => events,
+            _ = &mut action.cancel => break,
+            // Disabled once the `FSWatcher` is collected, and for the
+            // iterators which can't be unreferenced.
+            Ok(()) = action.persistent.changed() => {
+                if *action.persistent.borrow_and_update() != persistent {
+                    // Run again in or out of the event loop.
+                    start_watch(action);
+                    return;
+                }
+                continue;
+            }
+        };
+        match (events, &action.sink) {
+            (Ok(events), WatchSink::Iterator(sender)) => {
+                for event in events {
+                    if sender.send(Ok(event)).is_err() {
+                        // The iterator has been collected.
+                        return;
+                    }
+                }
+            }
+            (Ok(events), WatchSink::Watcher(this)) => {
+                for event in events {
+                    emit(this, "change", |context| {
+                        vec![
+                            JSValue::string(context, event.event_type),
+                            JSValue::string(context, event.filename),
+                        ]
+                    })
+                    .await;
+                }
+            }
+            (Err(err), WatchSink::Watcher(this)) => {
+                let path = action.path.clone();
+                emit(this, "error", |context| {
+                    vec![system_error(context, &err, "watch", Some(&path), None)]
+                })
+                .await;
+                break;
+            }
+            (Err(err), WatchSink::Iterator(sender)) => {
+                let _ = sender.send(Err(err));
+                break;
+            }
+        }
+    }
+    if let WatchSink::Watcher(this) = &action.sink {
+        emit(this, "close", |_| vec![]).await;
+    }
+}
+
+/// Start a watcher. Persistent watchers are run by the event loop and keep
+/// the process alive, the others are simply spawned.
+fn start_watch(action: WatchAction) {
+    let persistent = *action.persistent.borrow();
+    if persistent {
+        event_loop::append(Action::Watch(action));
+    } else {
+        tokio::spawn(exec_watch(action));
+    }
+}
+
+/// Common options of `fs.watch` and `fsPromises.watch`.
+fn watch_options(context: &JSContext, options: Option<&JSValue>) -> Result<(bool, bool), JSValue> {
+    get_encoding(context, options)?;
+    Ok((
+        get_bool_option(context, options, "persistent").unwrap_or(true),
+        get_bool_option(context, options, "recursive").unwrap_or(false),
+    ))
+}
+
+/// Private data of a `FSWatcher`.
+pub struct FsWatcher {
+    cancel: Option<oneshot::Sender<()>>,
+    persistent: watch::Sender<bool>,
+}
+
+/// Get FSWatcherClass
+fn get_fs_watcher_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create(
+        "FSWatcher",
+        None,
+        Some(fs_watcher_destructor)
+    ))
+}
+
+unsafe extern "C" fn fs_watcher_destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<FsWatcher>::from_raw(
+        object.get_private_data().unwrap(),
+    ));
+}
+
+impl FsWatcher {
+    pub fn try_from_object<'a>(
+        context: &JSContext,
+        object: &mut JSObject,
+    ) -> Result<&'a mut FsWatcher, JSValue> {
+        let object = object.try_as_mut_object_class(context, get_fs_watcher_class())?;
+        let watcher: &mut FsWatcher = unsafe { &mut *object.get_private_data().unwrap() };
+        Ok(watcher)
+    }
+}
+
+#[callback]
+/// Javascript call of `fs.watch(filename[, options][, listener])`. Returns a
+/// `FSWatcher` emitting 'change', 'error' and 'close'.
+pub fn watch(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let path = path_argument(&context, arguments, 0, "filename")?;
+    // The options are optional: watch(filename, listener). A function is
+    // recognized by its inherited `call` method.
+    let (options, listener) = match arguments.get(1) {
+        Some(value)
+            if value.is_object(&context) && get_option(&context, Some(value), "call").is_some() =>
+        {
+            (None, Some(value))
+        }
+        options => (options, arguments.get(2)),
+    };
+    let (persistent, recursive) = watch_options(&context, options)?;
+    let inotify = Inotify::new(&path, recursive)
+        .map_err(|err| system_error(&context, &err, "watch", Some(&path), None))?;
+
+    let (cancel_sender, cancel) = oneshot::channel();
+    let (persistent_sender, persistent) = watch::channel(persistent);
+    let mut watcher = get_fs_watcher_class().make_object(&context);
+    macro_rules! method {
+        ($( $name: literal => $callback: ident ),*) => {
+            $(
+            watcher
+                .set_property(&context, $name, JSValue::callback(&context, Some($callback)))
+                .unwrap();
+            )*
+        };
+    }
+    method!(
+        "close" => watcher_close,
+        "ref" => watcher_ref,
+        "unref" => watcher_unref
+    );
+    if watcher
+        .set_private_data(FsWatcher {
+            cancel: Some(cancel_sender),
+            persistent: persistent_sender,
+        })
+        .is_err()
+    {
+        panic!("cannot set private data to fs watcher");
+    }
+    let this: JSValue = watcher.into();
+    make_emitter(&context, this.clone());
+    if let Some(listener) = listener.filter(|listener| listener.is_object(&context)) {
+        listen(
+            &context,
+            &this.to_object(&context)?,
+            "change",
+            listener.clone(),
+        )?;
+    }
+    start_watch(WatchAction {
+        inotify,
+        sink: WatchSink::Watcher(this.clone().into_protected_object(&context)),
+        path,
+        cancel,
+        persistent,
+    });
+    Ok(this)
+}
+
+#[callback]
+/// Javascript call of `watcher.close()`, the 'close' event is emitted once
+/// the watcher is stopped.
+fn watcher_close(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) {
+    if let Ok(watcher) = FsWatcher::try_from_object(&context, &mut this) {
+        if let Some(cancel) = watcher.cancel.take() {
+            let _ = cancel.send(());
+        }
+    }
+}
+
+/// Shared by `ref()` and `unref()`: the running watcher moves in or out of
+/// the event loop, see `exec_watch`.
+fn set_persistent(
+    context: &JSContext,
+    mut this: JSObject,
+    persistent: bool,
+) -> Result<JSValue, JSValue> {
+    let watcher = FsWatcher::try_from_object(context, &mut this)?;
+    // Kept even without receiver, once the watcher is stopped.
+    watcher.persistent.send_replace(persistent);
+    Ok(this.into())
+}
+
+#[callback]
+/// Javascript call of `watcher.ref()`, the watcher keeps the process alive.
+fn watcher_ref(
+    context: JSContext,
+    _function: JSObject,
+    this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    set_persistent(&context, this, true)
+}
+
+#[callback]
+/// Javascript call of `watcher.unref()`, the process can exit while the
+/// watcher is running.
+fn watcher_unref(
+    context: JSContext,
+    _function: JSObject,
+    this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    set_persistent(&context, this, false)
+}
+
+/// State of the async iterator returned by `fsPromises.watch`.
+pub struct WatchIteratorState {
+    /// The events, or the error of the watcher given to the next `next()`.
+    receiver: mpsc::UnboundedReceiver<io::Result<WatchEvent>>,
+    path: String,
+    aborted: Arc<AtomicBool>,
+}
+
+/// Private data of the async iterator returned by `fsPromises.watch`.
+struct FsWatchIterator {
+    state: Arc<tokio::sync::Mutex<WatchIteratorState>>,
+    cancel: Option<oneshot::Sender<()>>,
+    aborted: Arc<AtomicBool>,
+}
+
+/// Get WatchIteratorClass
+fn get_watch_iterator_class() -> &'static JSClass {
+    maybe_static!(JSClass, || JSClass::create(
+        "WatchIterator",
+        None,
+        Some(watch_iterator_destructor)
+    ))
+}
+
+unsafe extern "C" fn watch_iterator_destructor(this: rusty_jsc::private::JSObjectRef) {
+    let mut object = JSObject::from(this);
+    let object = object.as_mut_object_class_unchecked();
+    drop(Box::<FsWatchIterator>::from_raw(
+        object.get_private_data().unwrap(),
+    ));
+}
+
+fn watch_iterator_from_object<'a>(
+    context: &JSContext,
+    object: &mut JSObject,
+) -> Result<&'a mut FsWatchIterator, JSValue> {
+    let object = object.try_as_mut_object_class(context, get_watch_iterator_class())?;
+    let iterator: &mut FsWatchIterator = unsafe { &mut *object.get_private_data().unwrap() };
+    Ok(iterator)
+}
+
+#[callback]
+/// Javascript call of `fsPromises.watch(filename[, options])`. Returns an
+/// async iterator of `{ eventType, filename }` objects.
+pub fn watch_promise(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let path = path_argument(&context, arguments, 0, "filename")?;
+    let options = arguments.get(1);
+    let (persistent, recursive) = watch_options(&context, options)?;
+    let signal = get_option(&context, options, "signal");
+    let aborted = Arc::new(AtomicBool::new(
+        signal
+            .as_ref()
+            .and_then(|signal| get_bool_option(&context, Some(signal), "aborted"))
+            .unwrap_or(false),
+    ));
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let (cancel_sender, cancel) = oneshot::channel();
+    if !aborted.load(Ordering::SeqCst) {
+        match Inotify::new(&path, recursive) {
+            Ok(inotify) => start_watch(WatchAction {
+                inotify,
+                sink: WatchSink::Iterator(sender),
+                path: path.clone(),
+                cancel,
+                // The sender is dropped, the iterator can't be unreferenced.
+                persistent: watch::channel(persistent).1,
+            }),
+            // Given to the first `next()`.
+            Err(err) => {
+                let _ = sender.send(Err(err));
+            }
+        }
+    }
+
+    let mut iterator = get_watch_iterator_class().make_object(&context);
+    iterator
+        .set_property(
+            &context,
+            "next",
+            JSValue::callback(&context, Some(iterator_next)),
+        )
+        .unwrap();
+    iterator
+        .set_property(
+            &context,
+            "return",
+            JSValue::callback(&context, Some(iterator_return)),
+        )
+        .unwrap();
+    let state = WatchIteratorState {
+        receiver,
+        path,
+        aborted: aborted.clone(),
+    };
+    if iterator
+        .set_private_data(FsWatchIterator {
+            state: Arc::new(tokio::sync::Mutex::new(state)),
+            cancel: Some(cancel_sender),
+            aborted,
+        })
+        .is_err()
+    {
+        panic!("cannot set private data to watch iterator");
+    }
+    let iterator: JSValue = iterator.into();
+    set_symbol_property(
+        &context,
+        iterator.clone(),
+        "asyncIterator",
+        JSValue::callback(&context, Some(iterator_self)),
+    )?;
+    if let Some(signal) = signal {
+        if let Some(add_event_listener) = get_option(&context, Some(&signal), "addEventListener") {
+            let on_abort = bind(
+                &context,
+                JSValue::callback(&context, Some(iterator_abort)),
+                iterator.clone(),
+            );
+            add_event_listener.to_object(&context)?.call_as_function(
+                &context,
+                Some(&signal.to_object(&context)?),
+                &[JSValue::string(&context, "abort"), on_abort],
+            )?;
+        }
+    }
+    Ok(iterator)
+}
+
+#[callback]
+fn iterator_self(
+    _context: JSContext,
+    _function: JSObject,
+    this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    Ok(this.into())
+}
+
+#[callback]
+fn iterator_next(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let iterator = watch_iterator_from_object(&context, &mut this)?;
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    event_loop::append(Action::WatchNext(iterator.state.clone(), promise.clone()));
+    Ok(promise.into())
+}
+
+#[callback]
+/// Called when a `for await` loop is left early, stop the watcher.
+fn iterator_return(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let mut context = context;
+    let iterator = watch_iterator_from_object(&context, &mut this)?;
+    if let Some(cancel) = iterator.cancel.take() {
+        let _ = cancel.send(());
+    }
+    let promise = JSObject::<JSPromise>::promise(&mut context);
+    promise.resolve(&[iterator_result(&context, None)]);
+    Ok(promise.into())
+}
+
+#[callback]
+/// Bound to the iterator and called by the 'abort' event of the signal.
+fn iterator_abort(
+    context: JSContext,
+    _function: JSObject,
+    mut this: JSObject,
+    _arguments: &[JSValue],
+) {
+    if let Ok(iterator) = watch_iterator_from_object(&context, &mut this) {
+        iterator.aborted.store(true, Ordering::SeqCst);
+        if let Some(cancel) = iterator.cancel.take() {
+            let _ = cancel.send(());
+        }
+    }
+}
+
+/// Wait for the next event of a `fsPromises.watch` iterator.
+pub async fn exec_watch_next(
+    state: Arc<tokio::sync::Mutex<WatchIteratorState>>,
+    promise: JSObject<JSPromise>,
+) {
+    let state = &mut *state.lock().await;
+    let event = state.receiver.recv().await.transpose();
+    let _hold = get_hold().lock().await;
+    let context = promise.context();
+    match event {
+        Err(err) => promise.reject(&[system_error(
+            &context,
+            &err,
+            "watch",
+            Some(&state.path),
+            None,
+        )]),
+        Ok(None) if state.aborted.load(Ordering::SeqCst) => {
+            promise.reject(&[abort_error(&context)])
+        }
+        Ok(None) => promise.resolve(&[iterator_result(&context, None)]),
+        Ok(Some(event)) => {
+            let mut value = JSObject::<JSObjectGeneric>::new(&context);
+            value
+                .set_property(
+                    &context,
+                    "eventType",
+                    JSValue::string(&context, event.event_type),
+                )
+                .unwrap();
+            value
+                .set_property(
+                    &context,
+                    "filename",
+                    JSValue::string(&context, event.filename),
+                )
+                .unwrap();
+            promise.resolve(&[iterator_result(&context, Some(value.into()))]);
+        }
+    }
+}
+
+/// A file polled by `fs.watchFile`.
+struct StatWatch {
+    listeners: Vec<JSObject<JSProtected>>,
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+/// Should be only used in a single threaded context, like the timeout
+/// cancelers: it's only accessed from javascript or while holding the event
+/// loop hold.
+fn get_stat_watches() -> &'static mut HashMap<String, StatWatch> {
+    maybe_static_unsafe!(HashMap<String, StatWatch>)
+}
+
+/// A running `fs.watchFile` poller, see `exec_watch_file`.
+pub struct StatWatchAction {
+    path: String,
+    interval: Duration,
+    cancel: oneshot::Receiver<()>,
+}
+
+/// Compare the fields that libuv compares to detect a change.
+fn stats_changed(previous: &Option<Metadata>, current: &Option<Metadata>) -> bool {
+    match (previous, current) {
+        (None, None) => false,
+        (Some(previous), Some(current)) => {
+            previous.mtime() != current.mtime()
+                || previous.mtime_nsec() != current.mtime_nsec()
+                || previous.ctime() != current.ctime()
+                || previous.ctime_nsec() != current.ctime_nsec()
+                || previous.size() != current.size()
+                || previous.mode() != current.mode()
+                || previous.uid() != current.uid()
+                || previous.gid() != current.gid()
+                || previous.ino() != current.ino()
+                || previous.dev() != current.dev()
+        }
+        _ => true,
+    }
+}
+
+/// Poll the stats of a file until `fs.unwatchFile` removes the last listener.
+pub async fn exec_watch_file(mut action: StatWatchAction) {
+    let mut previous = tokio::fs::metadata(&action.path).await.ok();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(action.interval) => {},
+            _ = &mut action.cancel => break,
+        }
+        let current = tokio::fs::metadata(&action.path).await.ok();
+        if stats_changed(&previous, &current) {
+            let _hold = get_hold().lock().await;
+            let listeners = match get_stat_watches().get(&action.path) {
+                Some(watch) => watch.listeners.clone(),
+                None => break,
+            };
+            for listener in listeners {
+                let context = listener.context();
+                let result = listener.call_as_function(
+                    &context,
+                    None,
+                    &[
+                        Stats::make(&context, current.as_ref()).into(),
+                        Stats::make(&context, previous.as_ref()).into(),
+                    ],
+                );
+                if let Err(error) = result {
+                    print_uncaught(&context, &error);
+                }
+            }
+        }
+        previous = current;
+    }
+}
+
+#[callback]
+/// Javascript call of `fs.watchFile(filename[, options], listener)`.
+pub fn watch_file(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let path = path_argument(&context, arguments, 0, "filename")?;
+    let (options, listener) = match (arguments.get(1), arguments.get(2)) {
+        (options, Some(listener)) => (options, listener),
+        (Some(listener), None) => (None, listener),
+        _ => return Err(invalid_arg_type(&context, "listener", "of type function")),
+    };
+    if !listener.is_object(&context) {
+        return Err(invalid_arg_type(&context, "listener", "of type function"));
+    }
+    let interval = get_number_option(&context, options, "interval")?.unwrap_or(5007.0);
+    let persistent = get_bool_option(&context, options, "persistent").unwrap_or(true);
+    let key = absolute(Path::new(&path)).to_string_lossy().into_owned();
+
+    let watch = get_stat_watches().entry(key.clone()).or_insert_with(|| {
+        let (cancel_sender, cancel) = oneshot::channel();
+        let action = StatWatchAction {
+            path: key,
+            interval: Duration::from_millis(interval.max(1.0) as u64),
+            cancel,
+        };
+        if persistent {
+            event_loop::append(Action::WatchFile(action));
+        } else {
+            tokio::spawn(exec_watch_file(action));
+        }
+        StatWatch {
+            listeners: vec![],
+            cancel: Some(cancel_sender),
+        }
+    });
+    watch
+        .listeners
+        .push(listener.clone().into_protected_object(&context));
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// Javascript call of `fs.unwatchFile(filename[, listener])`. Without
+/// listener, all the listeners are removed and the polling stops.
+pub fn unwatch_file(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let path = path_argument(&context, arguments, 0, "filename")?;
+    let key = absolute(Path::new(&path)).to_string_lossy().into_owned();
+    let watches = get_stat_watches();
+    if let Some(watch) = watches.get_mut(&key) {
+        match arguments
+            .get(1)
+            .filter(|listener| listener.is_object(&context))
+        {
+            Some(listener) => watch
+                .listeners
+                .retain(|l| !same_value(&context, l.clone().into(), listener.clone())),
+            None => watch.listeners.clear(),
+        }
+        if watch.listeners.is_empty() {
+            if let Some(cancel) = watch.cancel.take() {
+                let _ = cancel.send(());
+            }
+            watches.remove(&key);
+        }
+    }
+    Ok(JSValue::undefined(&context))
+}
@@ -0,0 +1,2189 @@
+//! `util.inspect`: a readable representation of any javascript value, as
+//! NodeJS prints them: `{ a: 1, b: [ 'x' ] }`, `Map(1) { 'key' => true }`,
+//! `[Function: name]`...
+//!
+//! The objects are expanded up to `depth` levels, the deeper ones are
+//! abbreviated as `[Object]`, `[Array]` or `[ClassName]`. The entries are
+//! kept on a single line while it fits in `breakLength` columns and the
+//! object doesn't contain more than `compact` levels, otherwise they are on
+//! their own indented lines. The long arrays are grouped in aligned columns.
+//!
+//! With the `colors` option, the parts of the output are colored as their
+//! style in `util.inspect.styles`, like yellow for the numbers, and the
+//! frames of the runtime are dimmed in the stacks of the errors.
+//!
+//! The type of a value is read from `Object.prototype.toString`, which the C
+//! API doesn't expose, and confirmed with a builtin method which checks its
+//! receiver, like `Map.prototype.has` for the maps.
+
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{
+    js_utils::{array_values, call_global, construct, global_property, same_value},
+    util::util,
+};
+
+/// Below this length, the strings aren't split on their line breaks.
+const MIN_LINE_WIDTH: usize = 16;
+
+/// How the entries of an object are joined, the `compact` option.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Compact {
+    /// `true`: the entries are on as few lines as possible.
+    Wrap,
+    /// A number, 0 for `false`: the objects containing up to this number of
+    /// levels are on a single line if it's short enough.
+    Levels(usize),
+}
+
+/// The `sorted` option, to sort the entries of the objects, maps and sets.
+#[derive(Clone)]
+pub enum Sorted {
+    No,
+    Yes,
+    /// A comparison function, as given to `Array.prototype.sort`.
+    With(JSValue),
+}
+
+/// The `getters` option: which getters are called to show their value.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Getters {
+    No,
+    All,
+    /// Only the getters without setter, `'get'`.
+    Get,
+    /// Only the getters with a setter, `'set'`.
+    Set,
+}
+
+/// Options of `inspect`, the ones of `util.inspect`.
+#[derive(Clone)]
+pub struct InspectOptions {
+    /// Number of nested levels expanded, `f64::INFINITY` for all of them. A
+    /// negative depth abbreviates the value itself.
+    pub depth: f64,
+    pub compact: Compact,
+    /// Number of columns at which the entries are split on several lines.
+    pub break_length: f64,
+    pub sorted: Sorted,
+    /// Show the non-enumerable properties too, like the `length` of arrays.
+    pub show_hidden: bool,
+    /// Maximum number of elements shown for the arrays, maps and sets.
+    pub max_array_length: usize,
+    /// Maximum number of characters shown for the strings.
+    pub max_string_length: usize,
+    pub getters: Getters,
+    /// Call the `[util.inspect.custom]` methods of the objects.
+    pub custom_inspect: bool,
+    /// Color the output with the `util.inspect.styles`.
+    pub colors: bool,
+}
+
+impl Default for InspectOptions {
+    fn default() -> Self {
+        InspectOptions {
+            depth: 2.0,
+            compact: Compact::Levels(3),
+            break_length: 80.0,
+            sorted: Sorted::No,
+            show_hidden: false,
+            max_array_length: 100,
+            max_string_length: 10000,
+            getters: Getters::No,
+            custom_inspect: true,
+            colors: false,
+        }
+    }
+}
+
+impl InspectOptions {
+    /// Read the options object of `util.inspect`, the options not given keep
+    /// their default.
+    pub fn from_object(context: &JSContext, options: &JSValue) -> Result<Self, JSValue> {
+        let mut inspect_options = InspectOptions::default();
+        inspect_options.update(context, options)?;
+        Ok(inspect_options)
+    }
+
+    /// Override the options given in an options object. A null `depth`,
+    /// `maxArrayLength` or `maxStringLength` means no limit.
+    pub fn update(&mut self, context: &JSContext, options: &JSValue) -> Result<(), JSValue> {
+        let options = options.to_object(context)?;
+        let limit = |value: &JSValue| -> Result<Option<usize>, JSValue> {
+            Ok(if value.is_null(context) {
+                Some(usize::MAX)
+            } else if value.is_number(context) {
+                let length = value.to_number(context)?.max(0.0);
+                Some(if length.is_infinite() {
+                    usize::MAX
+                } else {
+                    length as usize
+                })
+            } else {
+                None
+            })
+        };
+        let depth = options.get_property(context, "depth")?;
+        if depth.is_null(context) {
+            self.depth = f64::INFINITY;
+        } else if depth.is_number(context) {
+            self.depth = depth.to_number(context)?;
+        }
+        let compact = options.get_property(context, "compact")?;
+        if compact.is_number(context) {
+            self.compact = Compact::Levels(compact.to_number(context)?.max(0.0) as usize);
+        } else if !compact.is_undefined(context) {
+            self.compact = if compact.to_bool(context) {
+                Compact::Wrap
+            } else {
+                Compact::Levels(0)
+            };
+        }
+        let break_length = options.get_property(context, "breakLength")?;
+        if break_length.is_number(context) {
+            self.break_length = break_length.to_number(context)?;
+        }
+        let sorted = options.get_property(context, "sorted")?;
+        if is_function(context, &sorted)? {
+            self.sorted = Sorted::With(sorted);
+        } else if !sorted.is_undefined(context) {
+            self.sorted = if sorted.to_bool(context) {
+                Sorted::Yes
+            } else {
+                Sorted::No
+            };
+        }
+        let show_hidden = options.get_property(context, "showHidden")?;
+        if !show_hidden.is_undefined(context) {
+            self.show_hidden = show_hidden.to_bool(context);
+        }
+        if let Some(length) = limit(&options.get_property(context, "maxArrayLength")?)? {
+            self.max_array_length = length;
+        }
+        if let Some(length) = limit(&options.get_property(context, "maxStringLength")?)? {
+            self.max_string_length = length;
+        }
+        let getters = options.get_property(context, "getters")?;
+        if getters.is_string(context) {
+            self.getters = match getters.to_js_string(context)?.to_string().as_str() {
+                "get" => Getters::Get,
+                "set" => Getters::Set,
+                _ => Getters::All,
+            };
+        } else if !getters.is_undefined(context) {
+            self.getters = if getters.to_bool(context) {
+                Getters::All
+            } else {
+                Getters::No
+            };
+        }
+        let custom_inspect = options.get_property(context, "customInspect")?;
+        if !custom_inspect.is_undefined(context) {
+            self.custom_inspect = custom_inspect.to_bool(context);
+        }
+        let colors = options.get_property(context, "colors")?;
+        if !colors.is_undefined(context) {
+            self.colors = colors.to_bool(context);
+        }
+        Ok(())
+    }
+
+    /// Make the options object given to the `[util.inspect.custom]` methods.
+    fn to_object(&self, context: &JSContext) -> JSValue {
+        let limit = |length: usize| {
+            JSValue::number(
+                context,
+                if length == usize::MAX {
+                    f64::INFINITY
+                } else {
+                    length as f64
+                },
+            )
+        };
+        let mut object = JSObject::<JSObjectGeneric>::new(context);
+        let stylize = if self.colors {
+            JSValue::callback(context, Some(stylize_with_color_callback))
+        } else {
+            JSValue::callback(context, Some(stylize_callback))
+        };
+        let properties = [
+            ("stylize", stylize),
+            ("showHidden", JSValue::boolean(context, self.show_hidden)),
+            (
+                "depth",
+                if self.depth.is_infinite() {
+                    JSValue::null(context)
+                } else {
+                    JSValue::number(context, self.depth)
+                },
+            ),
+            ("colors", JSValue::boolean(context, self.colors)),
+            (
+                "customInspect",
+                JSValue::boolean(context, self.custom_inspect),
+            ),
+            ("maxArrayLength", limit(self.max_array_length)),
+            ("maxStringLength", limit(self.max_string_length)),
+            ("breakLength", JSValue::number(context, self.break_length)),
+            (
+                "compact",
+                match self.compact {
+                    Compact::Wrap => JSValue::boolean(context, true),
+                    Compact::Levels(levels) => JSValue::number(context, levels as f64),
+                },
+            ),
+            (
+                "sorted",
+                match &self.sorted {
+                    Sorted::No => JSValue::boolean(context, false),
+                    Sorted::Yes => JSValue::boolean(context, true),
+                    Sorted::With(comparator) => comparator.clone(),
+                },
+            ),
+            (
+                "getters",
+                match self.getters {
+                    Getters::No => JSValue::boolean(context, false),
+                    Getters::All => JSValue::boolean(context, true),
+                    Getters::Get => JSValue::string(context, "get"),
+                    Getters::Set => JSValue::string(context, "set"),
+                },
+            ),
+        ];
+        for (name, value) in properties {
+            object.set_property(context, name, value).unwrap();
+        }
+        object.into()
+    }
+}
+
+#[callback]
+/// The `stylize(text, style)` function of the options given to the
+/// `[util.inspect.custom]` methods. Without colors, the text is kept as it
+/// is.
+fn stylize_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let text = match arguments.first() {
+        Some(text) => to_string(&context, text)?,
+        None => String::from("undefined"),
+    };
+    Ok(JSValue::string(&context, text))
+}
+
+#[callback]
+/// The `stylize(text, style)` function given to the `[util.inspect.custom]`
+/// methods with colors: the text is colored as the `style` of
+/// `util.inspect.styles`.
+fn stylize_with_color_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let text = match arguments.first() {
+        Some(text) => to_string(&context, text)?,
+        None => String::from("undefined"),
+    };
+    let style = match arguments.get(1) {
+        Some(style) => to_string(&context, style)?,
+        None => String::from("undefined"),
+    };
+    Ok(JSValue::string(
+        &context,
+        stylize_with_color(&context, &text, &style)?,
+    ))
+}
+
+/// Get a property of the `util.inspect` function, like `colors`.
+fn inspect_property(context: &JSContext, name: &str) -> Result<JSValue, JSValue> {
+    util(context)
+        .get_property(context, "inspect")?
+        .to_object(context)?
+        .get_property(context, name)
+}
+
+/// Get the codes of the escape sequences starting and ending a color of
+/// `util.inspect.colors`, like `("33", "39")` for 'yellow'. None if the
+/// color is unknown.
+pub fn color_codes(context: &JSContext, name: &str) -> Result<Option<(String, String)>, JSValue> {
+    let codes = inspect_property(context, "colors")?
+        .to_object(context)?
+        .get_property(context, name)?;
+    if codes.is_undefined(context) || codes.is_null(context) {
+        return Ok(None);
+    }
+    let codes = codes.to_object(context)?;
+    Ok(Some((
+        to_string(context, &codes.get_property(context, "0")?)?,
+        to_string(context, &codes.get_property(context, "1")?)?,
+    )))
+}
+
+/// Color a text with a color of `util.inspect.colors`, like 'bold'. The text
+/// is kept as it is if the color is unknown.
+pub fn colorize(context: &JSContext, text: &str, color: &str) -> Result<String, JSValue> {
+    Ok(match color_codes(context, color)? {
+        Some((start, end)) => format!("\x1b[{start}m{text}\x1b[{end}m"),
+        None => text.to_string(),
+    })
+}
+
+/// Color a text with the color of a style of `util.inspect.styles`, like
+/// 'number'. The text is kept as it is if the style is unknown.
+fn stylize_with_color(context: &JSContext, text: &str, style: &str) -> Result<String, JSValue> {
+    let color = inspect_property(context, "styles")?
+        .to_object(context)?
+        .get_property(context, style)?;
+    if color.is_undefined(context) {
+        return Ok(text.to_string());
+    }
+    colorize(context, text, &to_string(context, &color)?)
+}
+
+/// Get `util.inspect.custom`, the symbol of the methods replacing the default
+/// inspection of an object: `Symbol.for('nodejs.util.inspect.custom')`.
+pub fn custom_symbol(context: &JSContext) -> JSValue {
+    call_global(
+        context,
+        "Symbol.for",
+        &[JSValue::string(context, "nodejs.util.inspect.custom")],
+    )
+    .unwrap()
+}
+
+/// Get the representation of `value`.
+pub fn inspect(
+    context: &JSContext,
+    value: &JSValue,
+    options: &InspectOptions,
+) -> Result<String, JSValue> {
+    Inspector {
+        context,
+        options: options.clone(),
+        seen: vec![],
+        circular: vec![],
+        indentation: 0,
+        current_depth: 0,
+    }
+    .format_value(value, 0, false)
+}
+
+/// Number of columns taken by `text` in a terminal, the `getStringWidth` of
+/// NodeJS: the escape sequences and the combining characters take none, the
+/// East Asian wide characters two.
+pub fn string_width(text: &str) -> usize {
+    strip_control_sequences(text)
+        .chars()
+        .map(|c| {
+            if is_full_width(c as u32) {
+                2
+            } else if is_zero_width(c as u32) {
+                0
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+/// Remove the ANSI escape sequences, like the colors, from `text`.
+pub fn strip_control_sequences(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+                // Parameters up to the final byte.
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            '\u{9b}' => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            '\x1b' if chars.peek() == Some(&']') => {
+                // Operating system command, up to BEL or ST.
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            c => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+/// Code points taking two columns, from the East Asian Width property.
+fn is_full_width(code: u32) -> bool {
+    code >= 0x1100
+        && (code <= 0x115f // Hangul Jamo
+            || code == 0x2329 // Left-pointing angle bracket
+            || code == 0x232a // Right-pointing angle bracket
+            // CJK Radicals Supplement .. Enclosed CJK Letters and Months
+            || ((0x2e80..=0x3247).contains(&code) && code != 0x303f)
+            // Enclosed CJK Letters and Months .. CJK Unified Ideographs Extension A
+            || (0x3250..=0x4dbf).contains(&code)
+            // CJK Unified Ideographs .. Yi Radicals
+            || (0x4e00..=0xa4c6).contains(&code)
+            // Hangul Jamo Extended-A
+            || (0xa960..=0xa97c).contains(&code)
+            // Hangul Syllables
+            || (0xac00..=0xd7a3).contains(&code)
+            // CJK Compatibility Ideographs
+            || (0xf900..=0xfaff).contains(&code)
+            // Vertical Forms
+            || (0xfe10..=0xfe19).contains(&code)
+            // CJK Compatibility Forms .. Small Form Variants
+            || (0xfe30..=0xfe6b).contains(&code)
+            // Halfwidth and Fullwidth Forms
+            || (0xff01..=0xff60).contains(&code)
+            || (0xffe0..=0xffe6).contains(&code)
+            // Kana Supplement
+            || (0x1b000..=0x1b001).contains(&code)
+            // Enclosed Ideographic Supplement
+            || (0x1f200..=0x1f251).contains(&code)
+            // Miscellaneous Symbols and Pictographs .. Emoticons
+            || (0x1f300..=0x1f64f).contains(&code)
+            // CJK Unified Ideographs Extension B .. Tertiary Ideographic Plane
+            || (0x20000..=0x3fffd).contains(&code))
+}
+
+/// Control characters, combining marks and invisible characters.
+fn is_zero_width(code: u32) -> bool {
+    code <= 0x1f // C0 control codes
+        || (0x7f..=0x9f).contains(&code) // C1 control codes
+        || (0x300..=0x36f).contains(&code) // Combining Diacritical Marks
+        || (0x200b..=0x200f).contains(&code) // Modifying invisible characters
+        || (0x20d0..=0x20ff).contains(&code) // Combining Diacritical Marks for Symbols
+        || (0xfe00..=0xfe0f).contains(&code) // Variation Selectors
+        || (0xfe20..=0xfe2f).contains(&code) // Combining Half Marks
+        || (0xe0100..=0xe01ef).contains(&code) // Variation Selectors Supplement
+}
+
+/// Quote a string as NodeJS: single quotes, unless the string contains some
+/// and no double quotes (or backticks). The control characters are escaped.
+pub fn quote(text: &str) -> String {
+    let quote = if !text.contains('\'') {
+        '\''
+    } else if !text.contains('"') {
+        '"'
+    } else if !text.contains('`') && !text.contains("${") {
+        '`'
+    } else {
+        '\''
+    };
+    format!("{quote}{}{quote}", escape(text, quote))
+}
+
+/// Escape the control characters, the backslashes and `quote`.
+fn escape(text: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\x08' => escaped.push_str("\\b"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\x0c' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c <= '\x1f' || ('\x7f'..='\u{9f}').contains(&c) => {
+                escaped.push_str(&format!("\\x{:02X}", c as u32));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether a key is a valid index of an array, `Object.keys` lists them
+/// first.
+pub fn is_array_index(key: &str) -> bool {
+    key.parse::<u32>()
+        .map_or(false, |index| index != u32::MAX && index.to_string() == key)
+}
+
+/// Whether a property name is shown without quotes.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The type of a value given by `Object.prototype.toString`, like `Array`,
+/// `Map` or `AsyncFunction`.
+pub fn type_tag(context: &JSContext, value: &JSValue) -> Result<String, JSValue> {
+    let tag = apply(context, "Object.prototype.toString", value, &[])?
+        .to_js_string(context)?
+        .to_string();
+    Ok(tag
+        .trim_start_matches("[object ")
+        .trim_end_matches(']')
+        .to_string())
+}
+
+/// Call the builtin method at `path` on `this`, which may be a primitive.
+pub fn apply(
+    context: &JSContext,
+    path: &str,
+    this: &JSValue,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let arguments = JSObject::new_array(context, arguments)?;
+    call_global(
+        context,
+        "Reflect.apply",
+        &[
+            global_property(context, path)?,
+            this.clone(),
+            arguments.into(),
+        ],
+    )
+}
+
+/// Call the getter `key` of the builtin prototype at `path` on `value`. The
+/// getters of the builtins check the type of their receiver.
+pub fn builtin_getter(
+    context: &JSContext,
+    path: &str,
+    key: JSValue,
+    value: &JSValue,
+) -> Result<JSValue, JSValue> {
+    let descriptor = call_global(
+        context,
+        "Object.getOwnPropertyDescriptor",
+        &[global_property(context, path)?, key],
+    )?;
+    let getter = descriptor
+        .to_object(context)?
+        .get_property(context, "get")?;
+    let arguments = JSObject::new_array(context, &[])?;
+    call_global(
+        context,
+        "Reflect.apply",
+        &[getter, value.clone(), arguments.into()],
+    )
+}
+
+/// `Reflect.get(object, key)`, for the keys which may be symbols.
+fn get(context: &JSContext, object: &JSValue, key: &JSValue) -> Result<JSValue, JSValue> {
+    call_global(context, "Reflect.get", &[object.clone(), key.clone()])
+}
+
+/// `Object.prototype.hasOwnProperty.call(object, key)`, for the keys which may
+/// be symbols.
+fn has_own(context: &JSContext, object: &JSValue, key: &JSValue) -> Result<bool, JSValue> {
+    Ok(apply(
+        context,
+        "Object.prototype.hasOwnProperty",
+        object,
+        &[key.clone()],
+    )?
+    .to_bool(context))
+}
+
+/// Whether a property key is the string `name`.
+fn is_key(context: &JSContext, key: &JSValue, name: &str) -> bool {
+    key.is_string(context)
+        && key
+            .to_js_string(context)
+            .map_or(false, |key| key.to_string() == name)
+}
+
+/// Whether a value is a function, which may be a class.
+fn is_function(context: &JSContext, value: &JSValue) -> Result<bool, JSValue> {
+    Ok(value.is_object(context) && type_tag(context, value)?.ends_with("Function"))
+}
+
+/// Get `String(value)`, which accepts the symbols.
+pub fn to_string(context: &JSContext, value: &JSValue) -> Result<String, JSValue> {
+    Ok(call_global(context, "String", &[value.clone()])?
+        .to_js_string(context)?
+        .to_string())
+}
+
+/// Format a number as NodeJS, which keeps the sign of -0.
+pub fn format_number(context: &JSContext, number: f64) -> Result<String, JSValue> {
+    let value = JSValue::number(context, number);
+    if same_value(context, value.clone(), JSValue::number(context, -0.0)) {
+        return Ok(String::from("-0"));
+    }
+    Ok(value.to_js_string(context)?.to_string())
+}
+
+/// Length of a string in javascript, in UTF-16 code units.
+fn js_length(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Keep the first `length` UTF-16 code units of `text`.
+fn js_truncate(text: &str, length: usize) -> &str {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        units += c.len_utf16();
+        if units > length {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+fn plural(count: usize) -> &'static str {
+    if count > 1 {
+        "s"
+    } else {
+        ""
+    }
+}
+
+/// The last entry of a truncated array, map or set.
+fn remaining_text(remaining: usize) -> String {
+    format!("... {remaining} more item{}", plural(remaining))
+}
+
+/// The prefix of an object which isn't of its default class, like `Foo`,
+/// `Foo [bar]` or `[Map(2): null prototype]`, followed by a space.
+fn prefix(constructor: Option<&str>, tag: &str, fallback: &str, size: &str) -> String {
+    match constructor {
+        None if !tag.is_empty() && fallback != tag => {
+            format!("[{fallback}{size}: null prototype] [{tag}] ")
+        }
+        None => format!("[{fallback}{size}: null prototype] "),
+        Some(constructor) if !tag.is_empty() && constructor != tag => {
+            format!("{constructor}{size} [{tag}] ")
+        }
+        Some(constructor) => format!("{constructor}{size} "),
+    }
+}
+
+/// The kinds of objects formatted differently.
+enum Kind {
+    Array,
+    Set,
+    Map,
+    /// With the name of the class, like `Uint8Array`.
+    TypedArray(String),
+    /// With its type, like `AsyncFunction`.
+    Function(String),
+    RegExp,
+    Date,
+    Error,
+    ArrayBuffer,
+    DataView,
+    Promise,
+    WeakSet,
+    WeakMap,
+    /// A boxed primitive, with its type like `Number`.
+    Boxed(String),
+    Object,
+}
+
+/// How a property is shown.
+#[derive(Clone, Copy, PartialEq)]
+enum Extras {
+    /// `key: value`.
+    Object,
+    /// `key: value` after the elements of an array or a typed array.
+    Array,
+    /// An element of an array, without its key.
+    Element,
+}
+
+/// Formats the entries of an object before its properties.
+enum Formatter {
+    None,
+    Array,
+    Set,
+    Map,
+    /// A typed array and its length.
+    TypedArray(usize),
+    ArrayBuffer,
+    /// The contents can't be read, like the state of a promise.
+    Unknown(&'static str),
+}
+
+struct Inspector<'a> {
+    context: &'a JSContext,
+    options: InspectOptions,
+    /// The objects being formatted, a circular reference is one of them.
+    seen: Vec<JSValue>,
+    /// The objects referenced circularly, the first one is `*1`.
+    circular: Vec<JSValue>,
+    /// Number of spaces before the current line.
+    indentation: usize,
+    /// Level of the last object formatted, to know how many levels an object
+    /// contains.
+    current_depth: usize,
+}
+
+impl Inspector<'_> {
+    /// Style a part of the output, like the numbers or the strings. Without
+    /// colors, or if the styles can't be read, the text is kept as it is.
+    fn stylize(&self, text: &str, style: &str) -> String {
+        if !self.options.colors {
+            return text.to_string();
+        }
+        stylize_with_color(self.context, text, style).unwrap_or_else(|_| text.to_string())
+    }
+
+    fn format_value(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+        typed_array: bool,
+    ) -> Result<String, JSValue> {
+        if !value.is_object(self.context) {
+            return self.format_primitive(value);
+        }
+        if self.options.custom_inspect {
+            if let Some(text) = self.custom_inspect(value, recurse_times)? {
+                return Ok(text);
+            }
+        }
+        if self.position(&self.seen, value).is_some() {
+            let index = match self.position(&self.circular, value) {
+                Some(index) => index + 1,
+                None => {
+                    self.circular.push(value.clone());
+                    self.circular.len()
+                }
+            };
+            return Ok(self.stylize(&format!("[Circular *{index}]"), "special"));
+        }
+        self.format_raw(value, recurse_times, typed_array)
+    }
+
+    fn position(&self, values: &[JSValue], value: &JSValue) -> Option<usize> {
+        values
+            .iter()
+            .position(|other| same_value(self.context, other.clone(), value.clone()))
+    }
+
+    /// Call the `[util.inspect.custom](depth, options, inspect)` method of an
+    /// object. None if it has none, or if it returned the object itself.
+    fn custom_inspect(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+    ) -> Result<Option<String>, JSValue> {
+        let context = self.context;
+        let custom = get(context, value, &custom_symbol(context))?;
+        if !is_function(context, &custom)? {
+            return Ok(None);
+        }
+        let inspect_function = util(context).get_property(context, "inspect")?;
+        if same_value(context, custom.clone(), inspect_function.clone()) {
+            return Ok(None);
+        }
+        // The method of a prototype is for its instances.
+        let object = value.to_object(context)?;
+        let constructor = object.get_property(context, "constructor")?;
+        if constructor.is_object(context) {
+            let prototype = constructor
+                .to_object(context)?
+                .get_property(context, "prototype")?;
+            if same_value(context, prototype, value.clone()) {
+                return Ok(None);
+            }
+        }
+        let depth = if self.options.depth.is_infinite() {
+            JSValue::null(context)
+        } else {
+            JSValue::number(context, self.options.depth - recurse_times as f64)
+        };
+        let result = custom.to_object(context)?.call_as_function(
+            context,
+            Some(&object),
+            &[depth, self.options.to_object(context), inspect_function],
+        )?;
+        if same_value(context, result.clone(), value.clone()) {
+            return Ok(None);
+        }
+        if !result.is_string(context) {
+            return self.format_value(&result, recurse_times, false).map(Some);
+        }
+        let text = result.to_js_string(context)?.to_string();
+        let indentation = format!("\n{}", " ".repeat(self.indentation));
+        Ok(Some(text.replace('\n', &indentation)))
+    }
+
+    fn format_primitive(&self, value: &JSValue) -> Result<String, JSValue> {
+        let context = self.context;
+        if value.is_string(context) {
+            let mut text = value.to_js_string(context)?.to_string();
+            let mut trailer = String::new();
+            let length = js_length(&text);
+            if length > self.options.max_string_length {
+                let remaining = length - self.options.max_string_length;
+                text = js_truncate(&text, self.options.max_string_length).to_string();
+                trailer = format!("... {remaining} more character{}", plural(remaining));
+            }
+            let length = js_length(&text) as f64;
+            if self.options.compact != Compact::Wrap
+                && length > MIN_LINE_WIDTH as f64
+                && length > self.options.break_length - self.indentation as f64 - 4.0
+            {
+                let separator = format!(" +\n{}", " ".repeat(self.indentation + 2));
+                let lines: Vec<String> = text
+                    .split_inclusive('\n')
+                    .map(|line| self.stylize(&quote(line), "string"))
+                    .collect();
+                return Ok(lines.join(&separator) + &trailer);
+            }
+            return Ok(self.stylize(&quote(&text), "string") + &trailer);
+        }
+        if value.is_null(context) {
+            return Ok(self.stylize("null", "null"));
+        }
+        if value.is_undefined(context) {
+            return Ok(self.stylize("undefined", "undefined"));
+        }
+        if value.is_number(context) {
+            return Ok(self.stylize(
+                &format_number(context, value.to_number(context)?)?,
+                "number",
+            ));
+        }
+        let text = to_string(context, value)?;
+        Ok(match type_tag(context, value)?.as_str() {
+            "BigInt" => self.stylize(&format!("{text}n"), "bigint"),
+            "Boolean" => self.stylize(&text, "boolean"),
+            _ => self.stylize(&text, "symbol"),
+        })
+    }
+
+    fn format_raw(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+        typed_array: bool,
+    ) -> Result<String, JSValue> {
+        let context = self.context;
+        let constructor = self.constructor_name(value, recurse_times)?;
+        let name = constructor.as_deref();
+        let tag = self.string_tag(value)?;
+        let mut base = String::new();
+        let mut braces = [String::from("{"), String::from("}")];
+        let mut extras = Extras::Object;
+        let mut formatter = Formatter::None;
+        let mut keys;
+        match self.kind(value)? {
+            Kind::Array => {
+                let length = value
+                    .to_object(context)?
+                    .get_property(context, "length")?
+                    .to_number(context)?;
+                let prefix = if name != Some("Array") || !tag.is_empty() {
+                    prefix(name, &tag, "Array", &format!("({length})"))
+                } else {
+                    String::new()
+                };
+                keys = self.non_index_keys(value)?;
+                braces = [format!("{prefix}["), String::from("]")];
+                if length == 0.0 && keys.is_empty() {
+                    return Ok(format!("{}]", braces[0]));
+                }
+                extras = Extras::Array;
+                formatter = Formatter::Array;
+            }
+            kind @ (Kind::Set | Kind::Map) => {
+                let fallback = if matches!(kind, Kind::Set) {
+                    "Set"
+                } else {
+                    "Map"
+                };
+                let size = value
+                    .to_object(context)?
+                    .get_property(context, "size")?
+                    .to_number(context)?;
+                let prefix = prefix(name, &tag, fallback, &format!("({size})"));
+                keys = self.keys(value)?;
+                if size == 0.0 && keys.is_empty() {
+                    return Ok(format!("{prefix}{{}}"));
+                }
+                braces[0] = format!("{prefix}{{");
+                formatter = if matches!(kind, Kind::Set) {
+                    Formatter::Set
+                } else {
+                    Formatter::Map
+                };
+            }
+            Kind::TypedArray(type_name) => {
+                let length = value
+                    .to_object(context)?
+                    .get_property(context, "length")?
+                    .to_number(context)? as usize;
+                let fallback = if name.is_none() {
+                    type_name.as_str()
+                } else {
+                    ""
+                };
+                let prefix = prefix(name, &tag, fallback, &format!("({length})"));
+                keys = self.non_index_keys(value)?;
+                braces = [format!("{prefix}["), String::from("]")];
+                if length == 0 && keys.is_empty() && !self.options.show_hidden {
+                    return Ok(format!("{}]", braces[0]));
+                }
+                extras = Extras::Array;
+                formatter = Formatter::TypedArray(length);
+            }
+            _ if name == Some("Object") => {
+                keys = self.keys(value)?;
+                if !tag.is_empty() {
+                    braces[0] = format!("{}{{", prefix(name, &tag, "Object", ""));
+                }
+                if keys.is_empty() {
+                    return Ok(format!("{}}}", braces[0]));
+                }
+            }
+            Kind::Function(type_name) => {
+                keys = self.keys(value)?;
+                base = self.function_base(value, name, &tag, &type_name)?;
+                if keys.is_empty() {
+                    return Ok(self.stylize(&base, "special"));
+                }
+            }
+            Kind::RegExp => {
+                keys = self.keys(value)?;
+                base = to_string(
+                    context,
+                    &apply(context, "RegExp.prototype.toString", value, &[])?,
+                )?;
+                let prefix = prefix(name, &tag, "RegExp", "");
+                if prefix != "RegExp " {
+                    base = format!("{prefix}{base}");
+                }
+                if keys.is_empty() || recurse_times as f64 > self.options.depth {
+                    return Ok(self.stylize(&base, "regexp"));
+                }
+            }
+            Kind::Date => {
+                keys = self.keys(value)?;
+                let time = apply(context, "Date.prototype.getTime", value, &[])?;
+                let method = if time.to_number(context)?.is_nan() {
+                    "Date.prototype.toString"
+                } else {
+                    "Date.prototype.toISOString"
+                };
+                base = to_string(context, &apply(context, method, value, &[])?)?;
+                let prefix = prefix(name, &tag, "Date", "");
+                if prefix != "Date " {
+                    base = format!("{prefix}{base}");
+                }
+                if keys.is_empty() {
+                    return Ok(self.stylize(&base, "date"));
+                }
+            }
+            Kind::Error => {
+                keys = self.keys(value)?;
+                base = self.format_error(value, name, &tag, &mut keys)?;
+                if keys.is_empty() {
+                    return Ok(base);
+                }
+            }
+            Kind::ArrayBuffer => {
+                keys = self.keys(value)?;
+                let prefix = prefix(name, &tag, "ArrayBuffer", "");
+                if !typed_array {
+                    formatter = Formatter::ArrayBuffer;
+                } else if keys.is_empty() {
+                    let length = value
+                        .to_object(context)?
+                        .get_property(context, "byteLength")?
+                        .to_number(context)?;
+                    return Ok(format!(
+                        "{prefix}{{ byteLength: {} }}",
+                        self.stylize(&format_number(context, length)?, "number")
+                    ));
+                }
+                braces[0] = format!("{prefix}{{");
+                keys.insert(0, JSValue::string(context, "byteLength"));
+            }
+            Kind::DataView => {
+                keys = self.keys(value)?;
+                braces[0] = format!("{}{{", prefix(name, &tag, "DataView", ""));
+                let names = ["byteLength", "byteOffset", "buffer"];
+                keys.splice(0..0, names.map(|name| JSValue::string(context, name)));
+            }
+            Kind::Promise => {
+                keys = self.keys(value)?;
+                braces[0] = format!("{}{{", prefix(name, &tag, "Promise", ""));
+                formatter = Formatter::Unknown("<state unknown>");
+            }
+            kind @ (Kind::WeakSet | Kind::WeakMap) => {
+                keys = self.keys(value)?;
+                let fallback = if matches!(kind, Kind::WeakSet) {
+                    "WeakSet"
+                } else {
+                    "WeakMap"
+                };
+                braces[0] = format!("{}{{", prefix(name, &tag, fallback, ""));
+                formatter = Formatter::Unknown("<items unknown>");
+            }
+            Kind::Boxed(type_name) => {
+                keys = self.keys(value)?;
+                base = self.boxed_base(value, name, &tag, &type_name, &mut keys)?;
+                if keys.is_empty() {
+                    return Ok(base);
+                }
+            }
+            Kind::Object => {
+                keys = self.keys(value)?;
+                if keys.is_empty() {
+                    return Ok(format!("{}{{}}", prefix(name, &tag, "Object", "")));
+                }
+                braces[0] = format!("{}{{", prefix(name, &tag, "Object", ""));
+            }
+        }
+        if recurse_times as f64 > self.options.depth {
+            let prefix = prefix(name, &tag, "Object", "");
+            let abbreviation = prefix.trim_end();
+            return Ok(match constructor {
+                Some(_) => self.stylize(&format!("[{abbreviation}]"), "special"),
+                None => self.stylize(abbreviation, "special"),
+            });
+        }
+        let recurse_times = recurse_times + 1;
+        self.seen.push(value.clone());
+        self.current_depth = recurse_times;
+        let mut output = match formatter {
+            Formatter::None => vec![],
+            Formatter::Array => self.format_array(value, recurse_times)?,
+            Formatter::Set => self.format_set(value, recurse_times)?,
+            Formatter::Map => self.format_map(value, recurse_times)?,
+            Formatter::TypedArray(length) => {
+                self.format_typed_array(value, length, recurse_times)?
+            }
+            Formatter::ArrayBuffer => self.format_array_buffer(value)?,
+            Formatter::Unknown(text) => vec![self.stylize(text, "special")],
+        };
+        for key in &keys {
+            output.push(self.format_property(value, recurse_times, key, extras)?);
+        }
+        if let Some(index) = self.position(&self.circular, value) {
+            let reference = self.stylize(&format!("<ref *{}>", index + 1), "special");
+            if self.options.compact != Compact::Wrap {
+                base = if base.is_empty() {
+                    reference
+                } else {
+                    format!("{reference} {base}")
+                };
+            } else {
+                braces[0] = format!("{reference} {}", braces[0]);
+            }
+        }
+        self.seen.pop();
+        if !matches!(self.options.sorted, Sorted::No) {
+            // Only the properties of an array are sorted, not its elements.
+            let start = match extras {
+                Extras::Object => 0,
+                _ if keys.len() > 1 => output.len() - keys.len(),
+                _ => output.len(),
+            };
+            self.sort(&mut output[start..])?;
+        }
+        self.reduce_to_single_string(output, &base, &braces, extras, recurse_times, value)
+    }
+
+    /// Find how to format an object.
+    fn kind(&self, value: &JSValue) -> Result<Kind, JSValue> {
+        let context = self.context;
+        if call_global(context, "Array.isArray", &[value.clone()])?.to_bool(context) {
+            return Ok(Kind::Array);
+        }
+        let tag = type_tag(context, value)?;
+        let checked = |path: &str| apply(context, path, value, &[]).is_ok();
+        Ok(match tag.as_str() {
+            "Set" if checked("Set.prototype.has") => Kind::Set,
+            "Map" if checked("Map.prototype.has") => Kind::Map,
+            "WeakSet" if checked("WeakSet.prototype.has") => Kind::WeakSet,
+            "WeakMap" if checked("WeakMap.prototype.has") => Kind::WeakMap,
+            "Function" | "AsyncFunction" | "GeneratorFunction" | "AsyncGeneratorFunction"
+                if checked("Function.prototype.toString") =>
+            {
+                Kind::Function(tag)
+            }
+            "RegExp" => Kind::RegExp,
+            "Date" if checked("Date.prototype.getTime") => Kind::Date,
+            "Error" => Kind::Error,
+            "ArrayBuffer"
+                if builtin_getter(
+                    context,
+                    "ArrayBuffer.prototype",
+                    JSValue::string(context, "byteLength"),
+                    value,
+                )
+                .is_ok() =>
+            {
+                Kind::ArrayBuffer
+            }
+            "DataView"
+                if builtin_getter(
+                    context,
+                    "DataView.prototype",
+                    JSValue::string(context, "byteLength"),
+                    value,
+                )
+                .is_ok() =>
+            {
+                Kind::DataView
+            }
+            "Promise" => Kind::Promise,
+            "Number" | "String" | "Boolean" | "Symbol" | "BigInt"
+                if checked(&format!("{tag}.prototype.valueOf")) =>
+            {
+                Kind::Boxed(tag)
+            }
+            _ => {
+                // The getter of the `Symbol.toStringTag` of the typed arrays
+                // gives their class, undefined for the other values.
+                let class = builtin_getter(
+                    context,
+                    "TypedArray.prototype",
+                    global_property(context, "Symbol.toStringTag")?,
+                    value,
+                )?;
+                if class.is_string(context) {
+                    Kind::TypedArray(to_string(context, &class)?)
+                } else if checked("Function.prototype.toString") {
+                    Kind::Function(String::from("Function"))
+                } else {
+                    Kind::Object
+                }
+            }
+        })
+    }
+
+    /// The name of the first constructor in the prototype chain whose
+    /// `prototype` is in the chain, None for the objects without prototype.
+    fn constructor_name(
+        &self,
+        value: &JSValue,
+        recurse_times: usize,
+    ) -> Result<Option<String>, JSValue> {
+        let context = self.context;
+        let mut object = value.clone();
+        let mut first_prototype = None;
+        while !object.is_null(context) {
+            let descriptor = call_global(
+                context,
+                "Object.getOwnPropertyDescriptor",
+                &[object.clone(), JSValue::string(context, "constructor")],
+            )?;
+            if descriptor.is_object(context) {
+                let constructor = descriptor
+                    .to_object(context)?
+                    .get_property(context, "value")?;
+                if is_function(context, &constructor)? {
+                    let name = constructor
+                        .to_object(context)?
+                        .get_property(context, "name")?;
+                    let name = to_string(context, &name)?;
+                    if !name.is_empty() && self.is_instance(value, &constructor)? {
+                        return Ok(Some(name));
+                    }
+                }
+            }
+            object = call_global(context, "Object.getPrototypeOf", &[object])?;
+            if first_prototype.is_none() {
+                first_prototype = Some(object.clone());
+            }
+        }
+        let first_prototype = match first_prototype {
+            Some(prototype) if !prototype.is_null(context) => prototype,
+            _ => return Ok(None),
+        };
+        let name = type_tag(context, value)?;
+        if recurse_times as f64 > self.options.depth {
+            return Ok(Some(format!("{name} <Complex prototype>")));
+        }
+        Ok(Some(
+            match self.constructor_name(&first_prototype, recurse_times + 1)? {
+                Some(prototype) => format!("{name} <{prototype}>"),
+                None => {
+                    let options = InspectOptions {
+                        depth: -1.0,
+                        custom_inspect: false,
+                        ..self.options.clone()
+                    };
+                    format!("{name} <{}>", inspect(context, &first_prototype, &options)?)
+                }
+            },
+        ))
+    }
+
+    /// `value instanceof constructor`, false if it throws.
+    fn is_instance(&self, value: &JSValue, constructor: &JSValue) -> Result<bool, JSValue> {
+        let context = self.context;
+        let prototype = constructor
+            .to_object(context)?
+            .get_property(context, "prototype")?;
+        if !prototype.is_object(context) {
+            return Ok(false);
+        }
+        Ok(apply(
+            context,
+            "Object.prototype.isPrototypeOf",
+            &prototype,
+            &[value.clone()],
+        )
+        .map_or(false, |result| result.to_bool(context)))
+    }
+
+    /// The inherited `Symbol.toStringTag` of an object, empty if it's an own
+    /// property, which is shown with the other ones.
+    fn string_tag(&self, value: &JSValue) -> Result<String, JSValue> {
+        let context = self.context;
+        let key = global_property(context, "Symbol.toStringTag")?;
+        let tag = get(context, value, &key)?;
+        if !tag.is_string(context) {
+            return Ok(String::new());
+        }
+        let tag = to_string(context, &tag)?;
+        let method = if self.options.show_hidden {
+            "Object.prototype.hasOwnProperty"
+        } else {
+            "Object.prototype.propertyIsEnumerable"
+        };
+        if !tag.is_empty() && apply(context, method, value, &[key])?.to_bool(context) {
+            return Ok(String::new());
+        }
+        Ok(tag)
+    }
+
+    /// The keys of the properties shown: the enumerable ones, strings then
+    /// symbols, or all of them with `showHidden`.
+    fn keys(&self, value: &JSValue) -> Result<Vec<JSValue>, JSValue> {
+        let context = self.context;
+        let symbols = array_values(
+            context,
+            &call_global(context, "Object.getOwnPropertySymbols", &[value.clone()])?,
+        )?;
+        if self.options.show_hidden {
+            let mut keys = array_values(
+                context,
+                &call_global(context, "Object.getOwnPropertyNames", &[value.clone()])?,
+            )?;
+            keys.extend(symbols);
+            return Ok(keys);
+        }
+        let mut keys = array_values(
+            context,
+            &call_global(context, "Object.keys", &[value.clone()])?,
+        )?;
+        for symbol in symbols {
+            let enumerable = apply(
+                context,
+                "Object.prototype.propertyIsEnumerable",
+                value,
+                &[symbol.clone()],
+            )?;
+            if enumerable.to_bool(context) {
+                keys.push(symbol);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// The keys of the properties which aren't elements, of the arrays.
+    fn non_index_keys(&self, value: &JSValue) -> Result<Vec<JSValue>, JSValue> {
+        let context = self.context;
+        let mut keys = vec![];
+        for key in self.keys(value)? {
+            if !key.is_string(context) || !is_array_index(&to_string(context, &key)?) {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// `[Function: name]`, `[AsyncFunction (anonymous)]` or
+    /// `[class A extends B]`.
+    fn function_base(
+        &self,
+        value: &JSValue,
+        constructor: Option<&str>,
+        tag: &str,
+        type_name: &str,
+    ) -> Result<String, JSValue> {
+        let context = self.context;
+        let function = value.to_object(context)?;
+        let source = to_string(
+            context,
+            &apply(context, "Function.prototype.toString", value, &[])?,
+        )?;
+        if let Some(body) = source
+            .strip_prefix("class")
+            .and_then(|source| source.strip_suffix('}'))
+        {
+            if body
+                .find('{')
+                .map_or(false, |start| !body[..start].contains('('))
+            {
+                return self.class_base(value, constructor, tag);
+            }
+        }
+        let name = to_string(context, &function.get_property(context, "name")?)?;
+        let mut base = format!("[{type_name}");
+        if constructor.is_none() {
+            base.push_str(" (null prototype)");
+        }
+        if name.is_empty() {
+            base.push_str(" (anonymous)");
+        } else {
+            base.push_str(&format!(": {name}"));
+        }
+        base.push(']');
+        match constructor {
+            Some(constructor) if constructor != type_name => {
+                base.push_str(&format!(" {constructor}"));
+            }
+            _ => {}
+        }
+        if !tag.is_empty() && constructor != Some(tag) {
+            base.push_str(&format!(" [{tag}]"));
+        }
+        Ok(base)
+    }
+
+    fn class_base(
+        &self,
+        value: &JSValue,
+        constructor: Option<&str>,
+        tag: &str,
+    ) -> Result<String, JSValue> {
+        let context = self.context;
+        let mut name = String::new();
+        if has_own(context, value, &JSValue::string(context, "name"))? {
+            let own_name = value.to_object(context)?.get_property(context, "name")?;
+            if own_name.to_bool(context) {
+                name = to_string(context, &own_name)?;
+            }
+        }
+        if name.is_empty() {
+            name = String::from("(anonymous)");
+        }
+        let mut base = format!("class {name}");
+        match constructor {
+            Some("Function") | None => {}
+            Some(constructor) => base.push_str(&format!(" [{constructor}]")),
+        }
+        if !tag.is_empty() && constructor != Some(tag) {
+            base.push_str(&format!(" [{tag}]"));
+        }
+        if constructor.is_some() {
+            let parent = call_global(context, "Object.getPrototypeOf", &[value.clone()])?;
+            if parent.is_object(context) {
+                let parent_name = parent.to_object(context)?.get_property(context, "name")?;
+                if parent_name.to_bool(context) {
+                    base.push_str(&format!(" extends {}", to_string(context, &parent_name)?));
+                }
+            }
+        } else {
+            base.push_str(" extends [null prototype]");
+        }
+        Ok(format!("[{base}]"))
+    }
+
+    /// `[Number: 3]`, `[String: 'abc']`... The indexes of a string are
+    /// removed from the keys.
+    fn boxed_base(
+        &self,
+        value: &JSValue,
+        constructor: Option<&str>,
+        tag: &str,
+        type_name: &str,
+        keys: &mut Vec<JSValue>,
+    ) -> Result<String, JSValue> {
+        let context = self.context;
+        let primitive = apply(
+            context,
+            &format!("{type_name}.prototype.valueOf"),
+            value,
+            &[],
+        )?;
+        if type_name == "String" {
+            let length = js_length(&to_string(context, &primitive)?);
+            keys.drain(..length.min(keys.len()));
+        }
+        let mut base = format!("[{type_name}");
+        match constructor {
+            Some(constructor) if constructor == type_name => {}
+            Some(constructor) => base.push_str(&format!(" ({constructor})")),
+            None => base.push_str(" (null prototype)"),
+        }
+        // The primitive isn't colored, the whole base is.
+        let primitive = strip_control_sequences(&self.format_primitive(&primitive)?);
+        base.push_str(&format!(": {primitive}]"));
+        if !tag.is_empty() && constructor != Some(tag) {
+            base.push_str(&format!(" [{tag}]"));
+        }
+        if !keys.is_empty() {
+            return Ok(base);
+        }
+        Ok(self.stylize(&base, &type_name.to_lowercase()))
+    }
+
+    /// The stack of an error, renamed after its class if its name doesn't
+    /// say it, or `[Name: message]` without stack. The keys already in the
+    /// stack are removed, and the `cause` and `errors` ones added.
+    fn format_error(
+        &self,
+        value: &JSValue,
+        constructor: Option<&str>,
+        tag: &str,
+        keys: &mut Vec<JSValue>,
+    ) -> Result<String, JSValue> {
+        let context = self.context;
+        let error = value.to_object(context)?;
+        let name = error.get_property(context, "name")?;
+        let name = if name.is_null(context) || name.is_undefined(context) {
+            String::from("Error")
+        } else {
+            to_string(context, &name)?
+        };
+        let mut stack = error_stack(context, value)?;
+        if !self.options.show_hidden {
+            for property in ["name", "message", "stack"] {
+                let position = keys.iter().position(|key| is_key(context, key, property));
+                if let Some(position) = position {
+                    let text = to_string(context, &error.get_property(context, property)?)?;
+                    if stack.contains(&text) {
+                        keys.remove(position);
+                    }
+                }
+            }
+        }
+        for property in ["cause", "errors"] {
+            let present = if property == "cause" {
+                call_global(
+                    context,
+                    "Reflect.has",
+                    &[value.clone(), JSValue::string(context, property)],
+                )?
+                .to_bool(context)
+            } else {
+                call_global(
+                    context,
+                    "Array.isArray",
+                    &[error.get_property(context, property)?],
+                )?
+                .to_bool(context)
+            };
+            let listed = keys.iter().any(|key| is_key(context, key, property));
+            if present && !listed {
+                keys.push(JSValue::string(context, property));
+            }
+        }
+        stack = improve_stack(&stack, constructor, &name, tag);
+        let message = error.get_property(context, "message")?;
+        let mut position = 0;
+        if message.to_bool(context) {
+            let message = to_string(context, &message)?;
+            if let Some(index) = stack.find(&message).filter(|&index| index > 0) {
+                position = index + message.len();
+            }
+        }
+        match stack[position..].find("\n    at") {
+            None => stack = format!("[{stack}]"),
+            Some(start) if self.options.colors => {
+                stack = self.highlight_stack(&stack, position + start)
+            }
+            Some(_) => {}
+        }
+        if self.indentation != 0 {
+            let indentation = format!("\n{}", " ".repeat(self.indentation));
+            stack = stack.replace('\n', &indentation);
+        }
+        Ok(stack)
+    }
+
+    /// Color the stack of an error whose frames start at `start`: the message
+    /// is highlighted and the frames of the runtime are dimmed, to find the
+    /// ones of the script at a glance.
+    fn highlight_stack(&self, stack: &str, start: usize) -> String {
+        let message = &stack[..start];
+        let mut highlighted =
+            colorize(self.context, message, "bold").unwrap_or_else(|_| message.to_string());
+        for frame in stack[start + 1..].split('\n') {
+            highlighted.push('\n');
+            if is_internal_frame(frame) {
+                highlighted.push_str(&self.stylize(frame, "undefined"));
+            } else {
+                highlighted.push_str(frame);
+            }
+        }
+        highlighted
+    }
+
+    /// The elements of an array, up to `maxArrayLength`.
+    fn format_array(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+    ) -> Result<Vec<String>, JSValue> {
+        let context = self.context;
+        let length = value
+            .to_object(context)?
+            .get_property(context, "length")?
+            .to_number(context)? as usize;
+        let shown = length.min(self.options.max_array_length);
+        let mut output = vec![];
+        for index in 0..shown {
+            let key = JSValue::string(context, index.to_string());
+            if !has_own(context, value, &key)? {
+                return self.format_special_array(
+                    value,
+                    recurse_times,
+                    length,
+                    shown,
+                    output,
+                    index,
+                );
+            }
+            output.push(self.format_property(value, recurse_times, &key, Extras::Element)?);
+        }
+        if length > shown {
+            output.push(remaining_text(length - shown));
+        }
+        Ok(output)
+    }
+
+    /// Continue `format_array` from its first hole, the consecutive holes are
+    /// shown as `<3 empty items>`.
+    fn format_special_array(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+        length: usize,
+        max_length: usize,
+        mut output: Vec<String>,
+        start: usize,
+    ) -> Result<Vec<String>, JSValue> {
+        let context = self.context;
+        let keys = array_values(
+            context,
+            &call_global(context, "Object.keys", &[value.clone()])?,
+        )?;
+        let mut index = start;
+        // The keys before the first hole are its indexes.
+        for key in keys.iter().skip(start) {
+            if output.len() >= max_length {
+                break;
+            }
+            let name = to_string(context, key)?;
+            if !is_array_index(&name) {
+                break;
+            }
+            let key_index: usize = name.parse().unwrap();
+            if key_index != index {
+                let empty = key_index - index;
+                output.push(self.stylize(
+                    &format!("<{empty} empty item{}>", plural(empty)),
+                    "undefined",
+                ));
+                index = key_index;
+                if output.len() == max_length {
+                    break;
+                }
+            }
+            output.push(self.format_property(value, recurse_times, key, Extras::Element)?);
+            index += 1;
+        }
+        let remaining = length - index;
+        if output.len() != max_length {
+            if remaining > 0 {
+                output.push(self.stylize(
+                    &format!("<{remaining} empty item{}>", plural(remaining)),
+                    "undefined",
+                ));
+            }
+        } else if remaining > 0 {
+            output.push(remaining_text(remaining));
+        }
+        Ok(output)
+    }
+
+    fn format_set(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+    ) -> Result<Vec<String>, JSValue> {
+        let values = array_values(self.context, value)?;
+        let shown = values.len().min(self.options.max_array_length);
+        self.indentation += 2;
+        let mut output = vec![];
+        for value in &values[..shown] {
+            output.push(self.format_value(value, recurse_times, false)?);
+        }
+        if values.len() > shown {
+            output.push(remaining_text(values.len() - shown));
+        }
+        self.indentation -= 2;
+        Ok(output)
+    }
+
+    fn format_map(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+    ) -> Result<Vec<String>, JSValue> {
+        let context = self.context;
+        let entries = array_values(context, value)?;
+        let shown = entries.len().min(self.options.max_array_length);
+        self.indentation += 2;
+        let mut output = vec![];
+        for entry in &entries[..shown] {
+            let entry = entry.to_object(context)?;
+            let key =
+                self.format_value(&entry.get_property(context, "0")?, recurse_times, false)?;
+            let value =
+                self.format_value(&entry.get_property(context, "1")?, recurse_times, false)?;
+            output.push(format!("{key} => {value}"));
+        }
+        if entries.len() > shown {
+            output.push(remaining_text(entries.len() - shown));
+        }
+        self.indentation -= 2;
+        Ok(output)
+    }
+
+    /// The elements of a typed array, and its hidden properties with
+    /// `showHidden`.
+    fn format_typed_array(
+        &mut self,
+        value: &JSValue,
+        length: usize,
+        recurse_times: usize,
+    ) -> Result<Vec<String>, JSValue> {
+        let context = self.context;
+        let array = value.to_object(context)?;
+        let shown = length.min(self.options.max_array_length);
+        let mut output = vec![];
+        for index in 0..shown {
+            let element = array.get_property(context, &index.to_string())?;
+            output.push(self.format_primitive(&element)?);
+        }
+        if length > shown {
+            output.push(remaining_text(length - shown));
+        }
+        if self.options.show_hidden {
+            self.indentation += 2;
+            for key in [
+                "BYTES_PER_ELEMENT",
+                "length",
+                "byteLength",
+                "byteOffset",
+                "buffer",
+            ] {
+                let property = array.get_property(context, key)?;
+                let text = self.format_value(&property, recurse_times, true)?;
+                output.push(format!("[{key}]: {text}"));
+            }
+            self.indentation -= 2;
+        }
+        Ok(output)
+    }
+
+    /// The bytes of an `ArrayBuffer`, in hexadecimal.
+    fn format_array_buffer(&self, value: &JSValue) -> Result<Vec<String>, JSValue> {
+        let context = self.context;
+        let bytes = match construct(context, "Uint8Array", &[value.clone()]) {
+            Ok(bytes) => bytes.to_object(context)?,
+            Err(_) => return Ok(vec![self.stylize("(detached)", "special")]),
+        };
+        let length = bytes.get_property(context, "length")?.to_number(context)? as usize;
+        let shown = length.min(self.options.max_array_length);
+        let mut hex = vec![];
+        for index in 0..shown {
+            let byte = bytes.get_property(context, &index.to_string())?;
+            hex.push(format!("{:02x}", byte.to_number(context)? as u8));
+        }
+        let mut contents = hex.join(" ");
+        if length > shown {
+            let remaining = length - shown;
+            contents.push_str(&format!(" ... {remaining} more byte{}", plural(remaining)));
+        }
+        Ok(vec![format!(
+            "{}: <{contents}>",
+            self.stylize("[Uint8Contents]", "special")
+        )])
+    }
+
+    /// Format a property, `key: value` for the objects. The accessors are
+    /// shown as `[Getter]`, or called as asked by the `getters` option.
+    fn format_property(
+        &mut self,
+        value: &JSValue,
+        recurse_times: usize,
+        key: &JSValue,
+        extras: Extras,
+    ) -> Result<String, JSValue> {
+        let context = self.context;
+        let descriptor = call_global(
+            context,
+            "Object.getOwnPropertyDescriptor",
+            &[value.clone(), key.clone()],
+        )?;
+        let (data, getter, setter, enumerable) = if descriptor.is_object(context) {
+            let descriptor = descriptor.to_object(context)?;
+            (
+                descriptor.get_property(context, "value")?,
+                descriptor.get_property(context, "get")?,
+                descriptor.get_property(context, "set")?,
+                descriptor
+                    .get_property(context, "enumerable")?
+                    .to_bool(context),
+            )
+        } else {
+            (
+                get(context, value, key)?,
+                JSValue::undefined(context),
+                JSValue::undefined(context),
+                true,
+            )
+        };
+        let mut separator = String::from(" ");
+        let text = if !data.is_undefined(context) {
+            let difference = if self.options.compact != Compact::Wrap || extras != Extras::Object {
+                2
+            } else {
+                3
+            };
+            self.indentation += difference;
+            let text = self.format_value(&data, recurse_times, false)?;
+            if difference == 3 && self.options.break_length < string_width(&text) as f64 {
+                separator = format!("\n{}", " ".repeat(self.indentation));
+            }
+            self.indentation -= difference;
+            text
+        } else if !getter.is_undefined(context) {
+            let has_setter = !setter.is_undefined(context);
+            let label = if has_setter {
+                "Getter/Setter"
+            } else {
+                "Getter"
+            };
+            let call = match self.options.getters {
+                Getters::No => false,
+                Getters::All => true,
+                Getters::Get => !has_setter,
+                Getters::Set => has_setter,
+            };
+            if call {
+                let this = value.to_object(context)?;
+                match getter
+                    .to_object(context)?
+                    .call_as_function(context, Some(&this), &[])
+                {
+                    Ok(result) => {
+                        self.indentation += 2;
+                        let text = if result.is_object(context) {
+                            format!(
+                                "{} {}",
+                                self.stylize(&format!("[{label}]"), "special"),
+                                self.format_value(&result, recurse_times, false)?
+                            )
+                        } else {
+                            format!(
+                                "{} {}{}",
+                                self.stylize(&format!("[{label}:"), "special"),
+                                self.format_primitive(&result)?,
+                                self.stylize("]", "special")
+                            )
+                        };
+                        self.indentation -= 2;
+                        text
+                    }
+                    Err(error) => {
+                        let message = match error.to_object(context) {
+                            Ok(error) => {
+                                to_string(context, &error.get_property(context, "message")?)?
+                            }
+                            Err(_) => to_string(context, &error)?,
+                        };
+                        format!(
+                            "{} <Inspection threw ({message})>{}",
+                            self.stylize(&format!("[{label}:"), "special"),
+                            self.stylize("]", "special")
+                        )
+                    }
+                }
+            } else {
+                self.stylize(&format!("[{label}]"), "special")
+            }
+        } else if !setter.is_undefined(context) {
+            self.stylize("[Setter]", "special")
+        } else {
+            self.stylize("undefined", "undefined")
+        };
+        if extras == Extras::Element {
+            return Ok(text);
+        }
+        let name = if !key.is_string(context) {
+            format!(
+                "[{}]",
+                self.stylize(&escape(&to_string(context, key)?, '\''), "symbol")
+            )
+        } else {
+            let key = to_string(context, key)?;
+            if key == "__proto__" {
+                String::from("['__proto__']")
+            } else if !enumerable {
+                format!("[{}]", escape(&key, '\''))
+            } else if is_identifier(&key) {
+                self.stylize(&key, "name")
+            } else {
+                self.stylize(&quote(&key), "string")
+            }
+        };
+        Ok(format!("{name}:{separator}{text}"))
+    }
+
+    /// Sort entries with the `sorted` option: in the code point order, or
+    /// with its comparison function.
+    fn sort(&self, entries: &mut [String]) -> Result<(), JSValue> {
+        let context = self.context;
+        let comparator = match &self.options.sorted {
+            Sorted::No => return Ok(()),
+            Sorted::Yes => {
+                entries.sort();
+                return Ok(());
+            }
+            Sorted::With(comparator) => comparator.to_object(context)?,
+        };
+        let mut error = None;
+        entries.sort_by(|a, b| {
+            if error.is_some() {
+                return std::cmp::Ordering::Equal;
+            }
+            let arguments = [
+                JSValue::string(context, a.as_str()),
+                JSValue::string(context, b.as_str()),
+            ];
+            match comparator
+                .call_as_function(context, None, &arguments)
+                .and_then(|order| order.to_number(context))
+            {
+                Ok(order) => order.partial_cmp(&0.0).unwrap_or(std::cmp::Ordering::Equal),
+                Err(thrown) => {
+                    error = Some(thrown);
+                    std::cmp::Ordering::Equal
+                }
+            }
+        });
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Join the entries of an object, on a single line if it's short enough.
+    fn reduce_to_single_string(
+        &self,
+        mut output: Vec<String>,
+        base: &str,
+        braces: &[String; 2],
+        extras: Extras,
+        recurse_times: usize,
+        value: &JSValue,
+    ) -> Result<String, JSValue> {
+        let base_prefix = if base.is_empty() {
+            String::new()
+        } else {
+            format!("{base} ")
+        };
+        let Compact::Levels(levels) = self.options.compact else {
+            if self.is_below_break_length(&output, 0, base) {
+                let base = if base.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {base}")
+                };
+                return Ok(format!(
+                    "{}{base} {} {}",
+                    braces[0],
+                    output.join(", "),
+                    braces[1]
+                ));
+            }
+            let indentation = " ".repeat(self.indentation);
+            let start = if base.is_empty() && js_length(&braces[0]) == 1 {
+                String::from(" ")
+            } else {
+                let base = if base.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {base}")
+                };
+                format!("{base}\n{indentation}  ")
+            };
+            return Ok(format!(
+                "{}{start}{} {}",
+                braces[0],
+                output.join(&format!(",\n{indentation}  ")),
+                braces[1]
+            ));
+        };
+        if levels >= 1 {
+            let entries = output.len();
+            if extras == Extras::Array && entries > 6 {
+                output = self.group_array_elements(output, value)?;
+            }
+            if self.current_depth - recurse_times < levels && entries == output.len() {
+                let start =
+                    output.len() + self.indentation + js_length(&braces[0]) + js_length(base) + 10;
+                if self.is_below_break_length(&output, start, base) {
+                    let joined = output.join(", ");
+                    if !joined.contains('\n') {
+                        return Ok(format!("{base_prefix}{} {joined} {}", braces[0], braces[1]));
+                    }
+                }
+            }
+        }
+        let indentation = format!("\n{}", " ".repeat(self.indentation));
+        Ok(format!(
+            "{base_prefix}{}{indentation}  {}{indentation}{}",
+            braces[0],
+            output.join(&format!(",{indentation}  ")),
+            braces[1]
+        ))
+    }
+
+    /// Whether the entries fit on a line of `breakLength` columns, counting
+    /// `start` columns before them and their separators. The colors take no
+    /// column.
+    fn is_below_break_length(&self, output: &[String], start: usize, base: &str) -> bool {
+        let break_length = self.options.break_length;
+        let mut total_length = output.len() + start;
+        if (total_length + output.len()) as f64 > break_length {
+            return false;
+        }
+        for entry in output {
+            total_length += if self.options.colors {
+                js_length(&strip_control_sequences(entry))
+            } else {
+                js_length(entry)
+            };
+            if total_length as f64 > break_length {
+                return false;
+            }
+        }
+        !base.contains('\n')
+    }
+
+    /// Group the elements of a long array in aligned columns, the numbers
+    /// are aligned on the right.
+    fn group_array_elements(
+        &self,
+        output: Vec<String>,
+        value: &JSValue,
+    ) -> Result<Vec<String>, JSValue> {
+        const SEPARATOR_SPACE: usize = 2;
+        let context = self.context;
+        let mut total_length = 0;
+        let mut max_length = 0;
+        let mut output_length = output.len();
+        // The `... more items` entry isn't grouped.
+        if self.options.max_array_length < output.len() {
+            output_length -= 1;
+        }
+        let widths: Vec<usize> = output[..output_length]
+            .iter()
+            .map(|entry| string_width(entry))
+            .collect();
+        for &width in &widths {
+            total_length += width + SEPARATOR_SPACE;
+            max_length = max_length.max(width);
+        }
+        let actual_max = max_length + SEPARATOR_SPACE;
+        if (actual_max * 3 + self.indentation) as f64 >= self.options.break_length
+            || (total_length as f64 / actual_max as f64 <= 5.0 && max_length > 6)
+        {
+            return Ok(output);
+        }
+        let approximate_char_heights = 2.5;
+        let average_bias = (actual_max as f64 - total_length as f64 / output.len() as f64).sqrt();
+        let biased_max = (actual_max as f64 - 3.0 - average_bias).max(1.0);
+        let Compact::Levels(levels) = self.options.compact else {
+            return Ok(output);
+        };
+        let columns = ((approximate_char_heights * biased_max * output_length as f64).sqrt()
+            / biased_max)
+            .round()
+            .min(
+                ((self.options.break_length - self.indentation as f64) / actual_max as f64).floor(),
+            )
+            .min(levels as f64 * 4.0)
+            .min(15.0);
+        if columns <= 1.0 {
+            return Ok(output);
+        }
+        let columns = columns as usize;
+        let max_line_length: Vec<usize> = (0..columns)
+            .map(|column| {
+                widths
+                    .iter()
+                    .skip(column)
+                    .step_by(columns)
+                    .max()
+                    .copied()
+                    .unwrap_or(0)
+                    + SEPARATOR_SPACE
+            })
+            .collect();
+        let array = value.to_object(context)?;
+        let mut numbers = true;
+        for index in 0..output.len() {
+            let element = array.get_property(context, &index.to_string())?;
+            if !element.is_number(context)
+                && (element.is_object(context) || type_tag(context, &element)? != "BigInt")
+            {
+                numbers = false;
+                break;
+            }
+        }
+        let pad = |text: &str, width: usize| {
+            let padding = " ".repeat(width.saturating_sub(js_length(text)));
+            if numbers {
+                format!("{padding}{text}")
+            } else {
+                format!("{text}{padding}")
+            }
+        };
+        let mut grouped = vec![];
+        for start in (0..output_length).step_by(columns) {
+            let end = (start + columns).min(output_length);
+            let mut line = String::new();
+            for index in start..end - 1 {
+                let width =
+                    max_line_length[index - start] + js_length(&output[index]) - widths[index];
+                line.push_str(&pad(&format!("{}, ", output[index]), width));
+            }
+            let last = end - 1;
+            if numbers {
+                let width = max_line_length[last - start] + js_length(&output[last])
+                    - widths[last]
+                    - SEPARATOR_SPACE;
+                line.push_str(&pad(&output[last], width));
+            } else {
+                line.push_str(&output[last]);
+            }
+            grouped.push(line);
+        }
+        if self.options.max_array_length < output.len() {
+            grouped.push(output[output_length].clone());
+        }
+        Ok(grouped)
+    }
+}
+
+/// Get the stack of an error as NodeJS shows it: `Name: message` and a
+/// `    at function@file:line:column` line per frame. JavaScriptCore only
+/// keeps the frames in the `stack` property, a stack set by hand starting
+/// with the message is kept as it is.
+pub fn error_stack(context: &JSContext, error: &JSValue) -> Result<String, JSValue> {
+    let header = to_string(
+        context,
+        &apply(context, "Error.prototype.toString", error, &[])?,
+    )?;
+    let stack = error.to_object(context)?.get_property(context, "stack")?;
+    if !stack.to_bool(context) {
+        return Ok(header);
+    }
+    let stack = to_string(context, &stack)?;
+    if stack.starts_with(&header) {
+        return Ok(stack);
+    }
+    let mut text = header;
+    for frame in stack.lines().filter(|frame| !frame.is_empty()) {
+        text.push_str("\n    at ");
+        text.push_str(frame);
+    }
+    Ok(text)
+}
+
+/// Whether a frame of a stack is in the runtime rather than in the scripts:
+/// a native function, `forEach@[native code]`, or a function of a `node:`
+/// module. The scripts of the runtime are named `node:internal/<name>` by
+/// their `sourceURL` comment.
+fn is_internal_frame(frame: &str) -> bool {
+    let frame = frame.trim_start();
+    let frame = frame.strip_prefix("at ").unwrap_or(frame);
+    let location = frame
+        .rsplit(['@', '('])
+        .next()
+        .unwrap_or(frame)
+        .trim_end_matches(')');
+    location == "[native code]" || location.starts_with("node:")
+}
+
+/// Name the errors after their class when their name doesn't say it, like
+/// `ValidationError: message` for an `Error` thrown by a subclass.
+fn improve_stack(stack: &str, constructor: Option<&str>, name: &str, tag: &str) -> String {
+    let mut length = name.len();
+    let named = name.ends_with("Error")
+        && stack.starts_with(name)
+        && matches!(stack.as_bytes().get(length), None | Some(b':' | b'\n'));
+    if constructor.is_some() && !named {
+        return stack.to_string();
+    }
+    let mut fallback = String::from("Error");
+    if constructor.is_none() {
+        // The name at the start of the stack.
+        let start = stack.split([':', '\n']).next().unwrap_or("");
+        let valid = |c: char| c.is_ascii_alphanumeric() || " _[]()-".contains(c);
+        let found = if start.len() > 1
+            && start.starts_with(|c: char| c.is_ascii_uppercase())
+            && start.chars().all(valid)
+            && start.len() < stack.len()
+        {
+            start
+        } else if stack.ends_with("Error")
+            && stack
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_-".contains(c))
+        {
+            stack
+        } else {
+            ""
+        };
+        length = found.len();
+        if !found.is_empty() {
+            fallback = found.to_string();
+        }
+    }
+    let prefix = prefix(constructor, tag, &fallback, "");
+    let prefix = &prefix[..prefix.len() - 1];
+    if name == prefix {
+        return stack.to_string();
+    }
+    if prefix.contains(name) {
+        if length == 0 {
+            format!("{prefix}: {stack}")
+        } else {
+            format!("{prefix}{}", &stack[length..])
+        }
+    } else {
+        format!("{prefix} [{name}]{}", &stack[length..])
+    }
+}
@@ -0,0 +1,63 @@
+//! The helpers shared by the modules written in javascript: `codeError`,
+//! `abortError`, `nextTick`, the uncaught errors, the process warnings and
+//! the closing of the file streams.
+//!
+//! They are written in javascript (`js/internals.js`) and evaluated once.
+//! The object is given to the scripts of `events`, `stream`, `web_streams`,
+//! `util` and the file streams, along with their own binding. The warnings
+//! are printed by a native binding on `process.stderr`.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{inspect::to_string, js_utils::call_method, stdio::stderr};
+
+#[callback]
+/// `printWarning(text)`: print a process warning, like the deprecations of
+/// `node:util` or the leaks of `node:events`, on `process.stderr`.
+fn print_warning(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let undefined = JSValue::undefined(&context);
+    let text = to_string(&context, arguments.first().unwrap_or(&undefined))?;
+    let text = JSValue::string(&context, format!("(needjs) {text}\n"));
+    // A closed output isn't an error for the script.
+    let _ = call_method(&context, &stderr(&context), "write", &[text]);
+    Ok(undefined)
+}
+
+/// The native functions given to the script.
+fn binding(context: &JSContext) -> JSValue {
+    let mut binding = JSObject::<JSObjectGeneric>::new(context);
+    binding
+        .set_property(
+            context,
+            "printWarning",
+            JSValue::callback(context, Some(print_warning)),
+        )
+        .unwrap();
+    binding.into()
+}
+
+/// Get the shared helpers. The script is evaluated on the first call.
+pub fn internals(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let internals = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    internals
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/internals.js"), 1)
+                .expect("cannot evaluate the internals")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(context, None, &[binding(context)])
+                .expect("cannot initialize the internals")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
@@ -0,0 +1,18 @@
+// The `Console` class of `console.Console`. Evaluated once by console.rs, the
+// script is a function called with the native `create`, which checks the
+// arguments and makes the console, and `isConsole`.
+//
+// A native function can't be called with `new`, this one can, and without
+// it as in NodeJS. The consoles are native objects, so `instanceof` is
+// answered by `isConsole`.
+(function (create, isConsole) {
+  "use strict";
+
+  function Console(...args) {
+    return create(...args);
+  }
+
+  Object.defineProperty(Console, Symbol.hasInstance, { value: isConsole });
+  return Console;
+})
+//# sourceURL=node:internal/console
@@ -0,0 +1,768 @@
+// The `node:events` module: EventEmitter, EventTarget, Event and the `once`
+// and `on` helpers. Evaluated once by events.rs, the script is a function
+// called with the shared helpers of internals.rs. It returns the module,
+// which is the EventEmitter constructor itself as in NodeJS.
+(function (internals) {
+  "use strict";
+
+  let defaultMaxListeners = 10;
+  const errorMonitor = Symbol("events.errorMonitor");
+  const captureRejectionSymbol = Symbol.for("nodejs.rejection");
+  const kCapture = Symbol("kCapture");
+  // State of the EventTarget and Event objects.
+  const kTarget = Symbol("kTarget");
+  const kEvent = Symbol("kEvent");
+  const { codeError, abortError, nextTick, warn } = internals;
+
+  function checkListener(listener) {
+    if (typeof listener !== "function") {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "listener" argument must be of type function'
+      );
+    }
+  }
+
+  function checkMaxListeners(n, name) {
+    if (typeof n !== "number" || n < 0 || Number.isNaN(n)) {
+      throw codeError(
+        RangeError,
+        "ERR_OUT_OF_RANGE",
+        `The value of "${name}" is out of range. It must be a non-negative number. Received ${String(n)}`
+      );
+    }
+  }
+
+  function show(value) {
+    try {
+      return typeof value === "string" ? `'${value}'` : String(value);
+    } catch {
+      return Object.prototype.toString.call(value);
+    }
+  }
+
+  // EventEmitter
+
+  function EventEmitter(options) {
+    EventEmitter.init.call(this, options);
+  }
+
+  EventEmitter.prototype._events = undefined;
+  EventEmitter.prototype._eventsCount = 0;
+  EventEmitter.prototype._maxListeners = undefined;
+
+  // Also called by the native objects inheriting from EventEmitter.
+  EventEmitter.init = function (options) {
+    if (
+      this._events === undefined ||
+      this._events === Object.getPrototypeOf(this)._events
+    ) {
+      this._events = Object.create(null);
+      this._eventsCount = 0;
+    }
+    this._maxListeners = this._maxListeners || undefined;
+    if (options !== undefined && options.captureRejections !== undefined) {
+      if (typeof options.captureRejections !== "boolean") {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "options.captureRejections" property must be of type boolean'
+        );
+      }
+      this[kCapture] = options.captureRejections;
+    } else {
+      this[kCapture] = EventEmitter.captureRejections;
+    }
+  };
+
+  function eventsOf(emitter) {
+    if (
+      emitter._events === undefined ||
+      emitter._events === Object.getPrototypeOf(emitter)._events
+    ) {
+      emitter._events = Object.create(null);
+      emitter._eventsCount = 0;
+    }
+    return emitter._events;
+  }
+
+  function maxListenersOf(emitter) {
+    return emitter._maxListeners === undefined
+      ? defaultMaxListeners
+      : emitter._maxListeners;
+  }
+
+  function _addListener(emitter, type, listener, prepend) {
+    checkListener(listener);
+    let events = eventsOf(emitter);
+    if (events.newListener !== undefined) {
+      emitter.emit("newListener", type, listener.listener ?? listener);
+      // A 'newListener' listener may have reset the listeners.
+      events = eventsOf(emitter);
+    }
+    const listeners = events[type];
+    if (listeners === undefined) {
+      events[type] = [listener];
+      emitter._eventsCount++;
+      return emitter;
+    }
+    if (prepend) {
+      listeners.unshift(listener);
+    } else {
+      listeners.push(listener);
+    }
+    const max = maxListenersOf(emitter);
+    if (max > 0 && listeners.length > max && !listeners.warned) {
+      listeners.warned = true;
+      const name = emitter.constructor?.name || "EventEmitter";
+      warn(
+        "MaxListenersExceededWarning",
+        `Possible EventEmitter memory leak detected. ${listeners.length} ${String(type)} listeners added to [${name}]. MaxListeners is ${max}. Use emitter.setMaxListeners() to increase limit`
+      );
+    }
+    return emitter;
+  }
+
+  function onceWrapper(emitter, type, listener) {
+    const state = { fired: false, emitter, type, listener };
+    const wrapped = function (...args) {
+      if (state.fired) return undefined;
+      state.fired = true;
+      state.emitter.removeListener(state.type, wrapped);
+      return state.listener.apply(state.emitter, args);
+    };
+    wrapped.listener = listener;
+    return wrapped;
+  }
+
+  function addCatch(emitter, promise, type, args) {
+    promise.then(undefined, (error) => {
+      if (typeof emitter[captureRejectionSymbol] === "function") {
+        emitter[captureRejectionSymbol](error, type, ...args);
+        return;
+      }
+      // Don't capture the rejections of the 'error' listeners.
+      const capture = emitter[kCapture];
+      try {
+        emitter[kCapture] = false;
+        emitter.emit("error", error);
+      } finally {
+        emitter[kCapture] = capture;
+      }
+    });
+  }
+
+  EventEmitter.prototype.setMaxListeners = function setMaxListeners(n) {
+    checkMaxListeners(n, "n");
+    this._maxListeners = n;
+    return this;
+  };
+
+  EventEmitter.prototype.getMaxListeners = function getMaxListeners() {
+    return maxListenersOf(this);
+  };
+
+  EventEmitter.prototype.emit = function emit(type, ...args) {
+    const events = this._events;
+    if (type === "error") {
+      if (events !== undefined && events[errorMonitor] !== undefined) {
+        this.emit(errorMonitor, ...args);
+      }
+      if (events === undefined || events.error === undefined) {
+        const error = args[0];
+        if (error instanceof Error) throw error;
+        const unhandled = codeError(
+          Error,
+          "ERR_UNHANDLED_ERROR",
+          `Unhandled error. (${show(error)})`
+        );
+        unhandled.context = error;
+        throw unhandled;
+      }
+    }
+    const listeners = events?.[type];
+    if (listeners === undefined) return false;
+    // Copied, the listeners are allowed to add or remove listeners.
+    for (const listener of listeners.slice()) {
+      const result = listener.apply(this, args);
+      if (
+        this[kCapture] &&
+        result !== undefined &&
+        result !== null &&
+        typeof result.then === "function"
+      ) {
+        addCatch(this, result, type, args);
+      }
+    }
+    return true;
+  };
+
+  EventEmitter.prototype.addListener = function addListener(type, listener) {
+    return _addListener(this, type, listener, false);
+  };
+
+  EventEmitter.prototype.on = EventEmitter.prototype.addListener;
+
+  EventEmitter.prototype.prependListener = function prependListener(
+    type,
+    listener
+  ) {
+    return _addListener(this, type, listener, true);
+  };
+
+  // Going through `on` lets a subclass overriding `on` see the listener.
+  EventEmitter.prototype.once = function once(type, listener) {
+    checkListener(listener);
+    this.on(type, onceWrapper(this, type, listener));
+    return this;
+  };
+
+  EventEmitter.prototype.prependOnceListener = function prependOnceListener(
+    type,
+    listener
+  ) {
+    checkListener(listener);
+    this.prependListener(type, onceWrapper(this, type, listener));
+    return this;
+  };
+
+  // Remove the last registration of the listener.
+  EventEmitter.prototype.removeListener = function removeListener(
+    type,
+    listener
+  ) {
+    checkListener(listener);
+    const events = this._events;
+    const listeners = events?.[type];
+    if (listeners === undefined) return this;
+    let index = listeners.length - 1;
+    while (
+      index >= 0 &&
+      listeners[index] !== listener &&
+      listeners[index].listener !== listener
+    ) {
+      index--;
+    }
+    if (index < 0) return this;
+    const [removed] = listeners.splice(index, 1);
+    if (listeners.length === 0) {
+      delete events[type];
+      this._eventsCount--;
+    }
+    if (events.removeListener !== undefined) {
+      this.emit("removeListener", type, removed.listener ?? removed);
+    }
+    return this;
+  };
+
+  EventEmitter.prototype.off = EventEmitter.prototype.removeListener;
+
+  EventEmitter.prototype.removeAllListeners = function removeAllListeners(
+    type
+  ) {
+    const events = this._events;
+    if (events === undefined) return this;
+    if (events.removeListener === undefined) {
+      if (type === undefined) {
+        this._events = Object.create(null);
+        this._eventsCount = 0;
+      } else if (events[type] !== undefined) {
+        delete events[type];
+        this._eventsCount--;
+      }
+      return this;
+    }
+    // Remove one by one to emit 'removeListener', itself removed last.
+    if (type === undefined) {
+      for (const name of Reflect.ownKeys(events)) {
+        if (name !== "removeListener") this.removeAllListeners(name);
+      }
+      this.removeAllListeners("removeListener");
+      this._events = Object.create(null);
+      this._eventsCount = 0;
+      return this;
+    }
+    const listeners = events[type];
+    if (listeners !== undefined) {
+      for (let index = listeners.length - 1; index >= 0; index--) {
+        this.removeListener(type, listeners[index]);
+      }
+    }
+    return this;
+  };
+
+  EventEmitter.prototype.listeners = function listeners(type) {
+    const listeners = this._events?.[type];
+    return listeners === undefined
+      ? []
+      : listeners.map((listener) => listener.listener ?? listener);
+  };
+
+  EventEmitter.prototype.rawListeners = function rawListeners(type) {
+    const listeners = this._events?.[type];
+    return listeners === undefined ? [] : listeners.slice();
+  };
+
+  EventEmitter.prototype.listenerCount = function listenerCount(
+    type,
+    listener
+  ) {
+    const listeners = this._events?.[type];
+    if (listeners === undefined) return 0;
+    if (listener === undefined) return listeners.length;
+    return listeners.filter(
+      (registered) =>
+        registered === listener || registered.listener === listener
+    ).length;
+  };
+
+  EventEmitter.prototype.eventNames = function eventNames() {
+    return this._eventsCount > 0 ? Reflect.ownKeys(this._events) : [];
+  };
+
+  // EventTarget and Event
+
+  class Event {
+    constructor(type, options = {}) {
+      if (arguments.length === 0) {
+        throw codeError(
+          TypeError,
+          "ERR_MISSING_ARGS",
+          'The "type" argument must be specified'
+        );
+      }
+      this[kEvent] = {
+        type: String(type),
+        bubbles: Boolean(options?.bubbles),
+        cancelable: Boolean(options?.cancelable),
+        composed: Boolean(options?.composed),
+        defaultPrevented: false,
+        stopped: false,
+        target: null,
+        currentTarget: null,
+        timeStamp: Date.now(),
+      };
+    }
+
+    get type() {
+      return this[kEvent].type;
+    }
+
+    get bubbles() {
+      return this[kEvent].bubbles;
+    }
+
+    get cancelable() {
+      return this[kEvent].cancelable;
+    }
+
+    get composed() {
+      return this[kEvent].composed;
+    }
+
+    get defaultPrevented() {
+      return this[kEvent].cancelable && this[kEvent].defaultPrevented;
+    }
+
+    get returnValue() {
+      return !this.defaultPrevented;
+    }
+
+    get target() {
+      return this[kEvent].target;
+    }
+
+    get currentTarget() {
+      return this[kEvent].currentTarget;
+    }
+
+    get srcElement() {
+      return this[kEvent].target;
+    }
+
+    get eventPhase() {
+      return this[kEvent].currentTarget === null ? Event.NONE : Event.AT_TARGET;
+    }
+
+    get timeStamp() {
+      return this[kEvent].timeStamp;
+    }
+
+    get isTrusted() {
+      return false;
+    }
+
+    get cancelBubble() {
+      return this[kEvent].stopped;
+    }
+
+    set cancelBubble(value) {
+      if (value) this.stopPropagation();
+    }
+
+    composedPath() {
+      return this[kEvent].currentTarget === null
+        ? []
+        : [this[kEvent].currentTarget];
+    }
+
+    preventDefault() {
+      this[kEvent].defaultPrevented = true;
+    }
+
+    // There is no propagation out of an EventTarget, only the immediate
+    // variant has an effect.
+    stopPropagation() {
+      this[kEvent].stopped = true;
+    }
+
+    stopImmediatePropagation() {
+      this[kEvent].stopped = true;
+    }
+  }
+
+  Object.assign(Event, {
+    NONE: 0,
+    CAPTURING_PHASE: 1,
+    AT_TARGET: 2,
+    BUBBLING_PHASE: 3,
+  });
+
+  function flatten(options) {
+    return typeof options === "boolean"
+      ? { capture: options }
+      : {
+          capture: Boolean(options?.capture),
+          once: Boolean(options?.once),
+          passive: Boolean(options?.passive),
+          signal: options?.signal,
+        };
+  }
+
+  class EventTarget {
+    constructor() {
+      this[kTarget] = { listeners: new Map(), maxListeners: defaultMaxListeners };
+    }
+
+    addEventListener(type, listener, options) {
+      if (listener === null || listener === undefined) return;
+      const { capture, once, passive, signal } = flatten(options);
+      if (signal?.aborted) return;
+      const state = targetState(this);
+      type = String(type);
+      let listeners = state.listeners.get(type);
+      if (listeners === undefined) {
+        listeners = [];
+        state.listeners.set(type, listeners);
+      }
+      if (listeners.some((l) => l.listener === listener && l.capture === capture)) {
+        return;
+      }
+      const registration = { listener, capture, once, passive, removed: false };
+      listeners.push(registration);
+      const max = state.maxListeners;
+      if (max > 0 && listeners.length > max && !listeners.warned) {
+        listeners.warned = true;
+        warn(
+          "MaxListenersExceededWarning",
+          `Possible EventTarget memory leak detected. ${listeners.length} ${type} listeners added to [${this.constructor.name}]. MaxListeners is ${max}. Use events.setMaxListeners() to increase limit`
+        );
+      }
+      if (signal !== undefined) {
+        signal.addEventListener(
+          "abort",
+          () => this.removeEventListener(type, listener, { capture }),
+          { once: true }
+        );
+      }
+    }
+
+    removeEventListener(type, listener, options) {
+      const { capture } = flatten(options);
+      const listeners = targetState(this).listeners.get(String(type));
+      if (listeners === undefined) return;
+      const index = listeners.findIndex(
+        (l) => l.listener === listener && l.capture === capture
+      );
+      if (index >= 0) {
+        listeners[index].removed = true;
+        listeners.splice(index, 1);
+      }
+    }
+
+    dispatchEvent(event) {
+      if (!(event instanceof Event)) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "event" argument must be an instance of Event'
+        );
+      }
+      const state = event[kEvent];
+      if (state.currentTarget !== null) {
+        throw codeError(
+          Error,
+          "ERR_EVENT_RECURSION",
+          `The event "${state.type}" is already being dispatched`
+        );
+      }
+      state.target = this;
+      state.currentTarget = this;
+      const listeners = targetState(this).listeners.get(state.type) ?? [];
+      for (const registration of listeners.slice()) {
+        if (registration.removed) continue;
+        if (registration.once) {
+          this.removeEventListener(state.type, registration.listener, {
+            capture: registration.capture,
+          });
+        }
+        try {
+          const { listener } = registration;
+          if (typeof listener === "function") {
+            listener.call(this, event);
+          } else if (typeof listener?.handleEvent === "function") {
+            listener.handleEvent(event);
+          }
+        } catch (error) {
+          // As an uncaught exception, without stopping the dispatch.
+          nextTick(() => {
+            throw error;
+          });
+        }
+        if (state.stopped) break;
+      }
+      state.currentTarget = null;
+      state.stopped = false;
+      return !event.defaultPrevented;
+    }
+  }
+
+  function targetState(target) {
+    if (!(target instanceof EventTarget) || target[kTarget] === undefined) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_THIS",
+        'Value of "this" must be of type EventTarget'
+      );
+    }
+    return target[kTarget];
+  }
+
+  // Helpers accepting both an EventEmitter and an EventTarget.
+
+  function listen(emitter, name, listener, once) {
+    if (typeof emitter.on === "function") {
+      if (once) {
+        emitter.once(name, listener);
+      } else {
+        emitter.on(name, listener);
+      }
+    } else if (typeof emitter.addEventListener === "function") {
+      emitter.addEventListener(name, listener, { once });
+    } else {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "emitter" argument must be an instance of EventEmitter or EventTarget'
+      );
+    }
+  }
+
+  function unlisten(emitter, name, listener) {
+    if (typeof emitter.removeListener === "function") {
+      emitter.removeListener(name, listener);
+    } else if (typeof emitter.removeEventListener === "function") {
+      emitter.removeEventListener(name, listener);
+    }
+  }
+
+  function checkSignal(signal) {
+    if (
+      signal !== undefined &&
+      (signal === null ||
+        typeof signal !== "object" ||
+        !("aborted" in signal))
+    ) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "options.signal" property must be an instance of AbortSignal'
+      );
+    }
+  }
+
+  // Resolves with the arguments of the next `name` event, rejects on 'error'.
+  function once(emitter, name, options = {}) {
+    const signal = options?.signal;
+    checkSignal(signal);
+    if (signal?.aborted) return Promise.reject(abortError(signal));
+    return new Promise((resolve, reject) => {
+      const isTarget =
+        typeof emitter.on !== "function" &&
+        typeof emitter.addEventListener === "function";
+      const cleanup = () => {
+        unlisten(emitter, name, resolver);
+        if (!isTarget && name !== "error") {
+          unlisten(emitter, "error", errorListener);
+        }
+        signal?.removeEventListener("abort", abortListener);
+      };
+      const resolver = (...args) => {
+        cleanup();
+        resolve(args);
+      };
+      const errorListener = (error) => {
+        cleanup();
+        reject(error);
+      };
+      const abortListener = () => {
+        cleanup();
+        reject(abortError(signal));
+      };
+      listen(emitter, name, resolver, true);
+      if (!isTarget && name !== "error") {
+        listen(emitter, "error", errorListener, true);
+      }
+      signal?.addEventListener("abort", abortListener, { once: true });
+    });
+  }
+
+  // Async iterator over the arguments of the `name` events, throws on
+  // 'error'.
+  function on(emitter, name, options = {}) {
+    const signal = options?.signal;
+    checkSignal(signal);
+    if (signal?.aborted) throw abortError(signal);
+    const events = [];
+    const waiting = [];
+    let error = null;
+    let finished = false;
+
+    const eventHandler = (...args) => {
+      const next = waiting.shift();
+      if (next !== undefined) {
+        next.resolve({ value: args, done: false });
+      } else {
+        events.push(args);
+      }
+    };
+    const errorHandler = (err) => {
+      const next = waiting.shift();
+      if (next !== undefined) {
+        next.reject(err);
+      } else {
+        error = err;
+      }
+      iterator.return();
+    };
+    const abortListener = () => errorHandler(abortError(signal));
+
+    const iterator = {
+      next() {
+        if (events.length > 0) {
+          return Promise.resolve({ value: events.shift(), done: false });
+        }
+        if (error !== null) {
+          const rejected = Promise.reject(error);
+          error = null;
+          return rejected;
+        }
+        if (finished) return Promise.resolve({ value: undefined, done: true });
+        return new Promise((resolve, reject) => waiting.push({ resolve, reject }));
+      },
+      return() {
+        unlisten(emitter, name, eventHandler);
+        unlisten(emitter, "error", errorHandler);
+        signal?.removeEventListener("abort", abortListener);
+        finished = true;
+        for (const next of waiting.splice(0)) {
+          next.resolve({ value: undefined, done: true });
+        }
+        return Promise.resolve({ value: undefined, done: true });
+      },
+      throw(err) {
+        if (!(err instanceof Error)) {
+          throw codeError(
+            TypeError,
+            "ERR_INVALID_ARG_TYPE",
+            'The "EventEmitter.AsyncIterator" property must be an instance of Error'
+          );
+        }
+        error = err;
+        unlisten(emitter, name, eventHandler);
+        unlisten(emitter, "error", errorHandler);
+      },
+      [Symbol.asyncIterator]() {
+        return this;
+      },
+    };
+
+    listen(emitter, name, eventHandler, false);
+    if (name !== "error" && typeof emitter.on === "function") {
+      listen(emitter, "error", errorHandler, false);
+    }
+    signal?.addEventListener("abort", abortListener, { once: true });
+    return iterator;
+  }
+
+  function getEventListeners(emitter, name) {
+    if (typeof emitter.listeners === "function") {
+      return emitter.listeners(name);
+    }
+    const listeners = targetState(emitter).listeners.get(String(name)) ?? [];
+    return listeners.map((registration) => registration.listener);
+  }
+
+  function setMaxListeners(n = defaultMaxListeners, ...targets) {
+    checkMaxListeners(n, "n");
+    if (targets.length === 0) {
+      defaultMaxListeners = n;
+      return;
+    }
+    for (const target of targets) {
+      if (target instanceof EventTarget) {
+        targetState(target).maxListeners = n;
+      } else if (typeof target.setMaxListeners === "function") {
+        target.setMaxListeners(n);
+      } else {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "eventTargets" argument must be an instance of EventEmitter or EventTarget'
+        );
+      }
+    }
+  }
+
+  Object.defineProperty(EventEmitter, "defaultMaxListeners", {
+    enumerable: true,
+    get() {
+      return defaultMaxListeners;
+    },
+    set(n) {
+      checkMaxListeners(n, "defaultMaxListeners");
+      defaultMaxListeners = n;
+    },
+  });
+
+  Object.assign(EventEmitter, {
+    EventEmitter,
+    EventTarget,
+    Event,
+    once,
+    on,
+    getEventListeners,
+    setMaxListeners,
+    errorMonitor,
+    captureRejectionSymbol,
+    captureRejections: false,
+    usingDomains: false,
+    listenerCount(emitter, type) {
+      return emitter.listenerCount(type);
+    },
+  });
+
+  return EventEmitter;
+});
+//# sourceURL=node:internal/events
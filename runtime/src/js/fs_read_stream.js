@@ -0,0 +1,95 @@
+// The `fs.ReadStream` class, a Readable over a file. Evaluated once by
+// fs_read_stream.rs, the script is a function called with the `node:stream`
+// module, the callback `fs` API and the shared helpers of internals.rs, which
+// close the file once the stream is destroyed. It returns the class,
+// constructed with the options already checked and normalized by
+// `fs.createReadStream`.
+(function (stream, fs, internals) {
+  "use strict";
+
+  const { kIsPerformingIO, kIoDone, destroyFile } = internals;
+
+  class ReadStream extends stream.Readable {
+    constructor(path, options) {
+      super({
+        highWaterMark: options.highWaterMark,
+        encoding: options.encoding,
+        emitClose: options.emitClose,
+        autoDestroy: options.autoClose,
+        signal: options.signal,
+      });
+      this.path = path;
+      this.fd = options.fd ?? null;
+      this.flags = options.flags;
+      this.mode = options.mode;
+      this.start = options.start;
+      // Inclusive, as in NodeJS.
+      this.end = options.end ?? Infinity;
+      this.pos = options.start;
+      this.autoClose = options.autoClose;
+      this.bytesRead = 0;
+      this[kIsPerformingIO] = false;
+    }
+
+    get pending() {
+      return this.fd === null;
+    }
+
+    _construct(callback) {
+      // NodeJS doesn't emit 'open' and 'ready' for a given fd.
+      if (this.fd !== null) {
+        callback();
+        return;
+      }
+      fs.open(this.path, this.flags, this.mode, (error, fd) => {
+        if (error) {
+          callback(error);
+          return;
+        }
+        this.fd = fd;
+        callback();
+        this.emit("open", fd);
+        this.emit("ready");
+      });
+    }
+
+    _read(size) {
+      const position = this.pos ?? this.bytesRead;
+      size = Math.min(this.end - position + 1, size);
+      if (size <= 0) {
+        this.push(null);
+        return;
+      }
+      const buffer = new Uint8Array(size);
+      this[kIsPerformingIO] = true;
+      fs.read(this.fd, buffer, 0, size, this.pos ?? null, (error, bytesRead) => {
+        this[kIsPerformingIO] = false;
+        if (this.destroyed) {
+          this.emit(kIoDone, error);
+          return;
+        }
+        if (error) {
+          this.destroy(error);
+        } else if (bytesRead > 0) {
+          if (this.pos !== undefined) this.pos += bytesRead;
+          this.bytesRead += bytesRead;
+          this.push(bytesRead === size ? buffer : buffer.subarray(0, bytesRead));
+        } else {
+          this.push(null);
+        }
+      });
+    }
+
+    _destroy(error, callback) {
+      destroyFile(this, fs, error, callback);
+    }
+
+    close(callback) {
+      if (typeof callback === "function") stream.finished(this, callback);
+      this.destroy();
+    }
+  }
+
+  return ReadStream;
+});
+//# sourceURL=node:internal/fs_read_stream
@@ -0,0 +1,155 @@
+// The `fs.WriteStream` class, a Writable over a file. Evaluated once by
+// fs_write_stream.rs, the script is a function called with the `node:stream`
+// module, the callback `fs` API and the shared helpers of internals.rs, which
+// close the file once the stream is destroyed. It returns the class,
+// constructed with the options already checked and normalized by
+// `fs.createWriteStream`.
+//
+// The chunks buffered while a write is running are written at once with
+// `fs.writev`. A short write is followed by the write of the rest, as the
+// `writeAll` and `writevAll` of NodeJS.
+(function (stream, fs, internals) {
+  "use strict";
+
+  const { kIsPerformingIO, kIoDone, destroyFile } = internals;
+  // The writes giving no byte in a row before failing.
+  const kMaxRetries = 5;
+
+  // The part of `buffers` left once `size` bytes are written.
+  function remaining(buffers, size) {
+    const rest = [];
+    for (const buffer of buffers) {
+      if (size >= buffer.length) {
+        size -= buffer.length;
+        continue;
+      }
+      rest.push(size > 0 ? buffer.subarray(size) : buffer);
+      size = 0;
+    }
+    return rest;
+  }
+
+  class WriteStream extends stream.Writable {
+    constructor(path, options) {
+      super({
+        highWaterMark: options.highWaterMark,
+        defaultEncoding: options.encoding,
+        decodeStrings: true,
+        emitClose: options.emitClose,
+        autoDestroy: options.autoClose,
+        signal: options.signal,
+      });
+      this.path = path;
+      this.fd = options.fd ?? null;
+      this.flags = options.flags;
+      this.mode = options.mode;
+      this.start = options.start;
+      this.pos = options.start;
+      this.autoClose = options.autoClose;
+      this.flush = options.flush;
+      this.bytesWritten = 0;
+      this[kIsPerformingIO] = false;
+    }
+
+    get pending() {
+      return this.fd === null;
+    }
+
+    _construct(callback) {
+      // NodeJS doesn't emit 'open' and 'ready' for a given fd.
+      if (this.fd !== null) {
+        callback();
+        return;
+      }
+      fs.open(this.path, this.flags, this.mode, (error, fd) => {
+        if (error) {
+          callback(error);
+          return;
+        }
+        this.fd = fd;
+        callback();
+        this.emit("open", fd);
+        this.emit("ready");
+      });
+    }
+
+    // Write all the `buffers` at `position`, null for the current position
+    // of the file, then call back whatever the state of the stream.
+    _writeAll(buffers, position, callback, retries = 0) {
+      const written = (error, bytesWritten) => {
+        if (error?.code === "EAGAIN") {
+          error = null;
+          bytesWritten = 0;
+        }
+        if (this.destroyed || error) {
+          this[kIsPerformingIO] = false;
+          callback(error);
+          if (this.destroyed) this.emit(kIoDone, error);
+          return;
+        }
+        this.bytesWritten += bytesWritten;
+        retries = bytesWritten ? 0 : retries + 1;
+        const rest = remaining(buffers, bytesWritten);
+        if (rest.length > 0 && retries <= kMaxRetries) {
+          const next = position === null ? null : position + bytesWritten;
+          this._writeAll(rest, next, callback, retries);
+          return;
+        }
+        this[kIsPerformingIO] = false;
+        callback(rest.length > 0 ? new Error("writeAll failed") : undefined);
+      };
+      this[kIsPerformingIO] = true;
+      if (buffers.length === 1) {
+        const [buffer] = buffers;
+        fs.write(this.fd, buffer, 0, buffer.length, position, written);
+      } else {
+        fs.writev(this.fd, buffers, position, written);
+      }
+    }
+
+    _write(chunk, encoding, callback) {
+      this._writeAll([chunk], this.pos ?? null, callback);
+      if (this.pos !== undefined) this.pos += chunk.length;
+    }
+
+    _writev(chunks, callback) {
+      const buffers = chunks.map(({ chunk }) => chunk);
+      this._writeAll(buffers, this.pos ?? null, callback);
+      if (this.pos !== undefined) {
+        for (const buffer of buffers) this.pos += buffer.length;
+      }
+    }
+
+    _final(callback) {
+      if (!this.flush) {
+        callback();
+        return;
+      }
+      fs.fsync(this.fd, callback);
+    }
+
+    _destroy(error, callback) {
+      destroyFile(this, fs, error, callback);
+    }
+
+    close(callback) {
+      if (callback) {
+        if (this.closed) {
+          Promise.resolve().then(callback);
+          return;
+        }
+        this.on("close", callback);
+      }
+      // Without autoClose, the stream isn't destroyed once finished.
+      if (!this.autoClose) this.on("finish", this.destroy);
+      this.end();
+    }
+
+    destroySoon() {
+      this.end();
+    }
+  }
+
+  return WriteStream;
+});
+//# sourceURL=node:internal/fs_write_stream
@@ -0,0 +1,79 @@
+// The helpers shared by the modules written in javascript: the errors with a
+// code, nextTick, the uncaught errors, the process warnings and the closing
+// of the file streams. Evaluated once by internals.rs, the script is a
+// function called with the native `binding` ({ printWarning }). It returns
+// the helpers, given to the other scripts along with their own binding.
+(function (binding) {
+  "use strict";
+
+  function codeError(Base, code, message) {
+    const error = new Base(message);
+    error.code = code;
+    return error;
+  }
+
+  function abortError(signal) {
+    const error = codeError(Error, "ABORT_ERR", "The operation was aborted");
+    error.name = "AbortError";
+    if (signal !== undefined) error.cause = signal.reason;
+    return error;
+  }
+
+  // Run a function after the current operation, as process.nextTick. An
+  // error thrown there is uncaught.
+  function nextTick(callback, ...args) {
+    Promise.resolve().then(() => {
+      try {
+        callback(...args);
+      } catch (error) {
+        uncaught(error);
+      }
+    });
+  }
+
+  function uncaught(error) {
+    const print = console.error || console.log;
+    print.call(console, "Uncaught " + (error?.stack ?? error));
+  }
+
+  // Print a process warning on the standard error, as `name: message`.
+  function warn(name, message) {
+    binding.printWarning(`${name}: ${message}`);
+  }
+
+  // The file streams of `fs` set `kIsPerformingIO` while a read or a write
+  // is running, and emit `kIoDone` at its end if they were destroyed
+  // meanwhile.
+  const kIsPerformingIO = Symbol("kIsPerformingIO");
+  const kIoDone = Symbol("kIoDone");
+
+  // The `_destroy` of the file streams: the file is closed by `fs.close`
+  // once the pending operation is done, unless autoClose is off.
+  function destroyFile(stream, fs, error, callback) {
+    const close = (error) => {
+      if (stream.fd === null || !stream.autoClose) {
+        callback(error);
+        return;
+      }
+      const fd = stream.fd;
+      stream.fd = null;
+      fs.close(fd, (closeError) => callback(closeError || error));
+    };
+    if (stream[kIsPerformingIO]) {
+      stream.once(kIoDone, (ioError) => close(error || ioError));
+    } else {
+      close(error);
+    }
+  }
+
+  return {
+    codeError,
+    abortError,
+    nextTick,
+    warn,
+    kIsPerformingIO,
+    kIoDone,
+    destroyFile,
+  };
+});
+//# sourceURL=node:internal/internals
@@ -0,0 +1,50 @@
+// The builtins used by the native modules, captured before any script runs
+// as the primordials of NodeJS: a script replacing `Array.from` or
+// `Object.prototype.hasOwnProperty` doesn't change the runtime. Evaluated
+// once by js_utils.rs, the script is a function called with the global
+// object. It returns the builtins keyed by their dotted path, like
+// `Reflect.apply`, `Symbol.toStringTag` or `TypedArray.prototype`.
+(function (global) {
+  "use strict";
+
+  const names = [
+    "AggregateError", "Array", "ArrayBuffer", "BigInt", "BigInt64Array",
+    "BigUint64Array", "Boolean", "DataView", "Date", "Error", "EvalError",
+    "Float32Array", "Float64Array", "Function", "Int8Array", "Int16Array",
+    "Int32Array", "JSON", "Map", "Math", "Number", "Object", "Promise",
+    "RangeError", "ReferenceError", "Reflect", "RegExp", "Set",
+    "SharedArrayBuffer", "String", "Symbol", "SyntaxError", "TypeError",
+    "URIError", "Uint8Array", "Uint8ClampedArray", "Uint16Array",
+    "Uint32Array", "WeakMap", "WeakRef", "WeakSet", "parseFloat", "parseInt",
+  ];
+  const primordials = Object.create(null);
+
+  // Keep `value` at `path`, with its own methods and constants.
+  function capture(path, value) {
+    primordials[path] = value;
+    if (typeof value !== "function" && (typeof value !== "object" || value === null)) {
+      return;
+    }
+    for (const key of Reflect.ownKeys(value)) {
+      const descriptor = Reflect.getOwnPropertyDescriptor(value, key);
+      if (typeof key === "string" && "value" in descriptor) {
+        primordials[`${path}.${key}`] = descriptor.value;
+      }
+    }
+  }
+
+  function captureClass(name, constructor) {
+    capture(name, constructor);
+    if (typeof constructor === "function" && constructor.prototype) {
+      capture(`${name}.prototype`, constructor.prototype);
+    }
+  }
+
+  for (const name of names) {
+    captureClass(name, global[name]);
+  }
+  // The parent class of the typed arrays isn't a global.
+  captureClass("TypedArray", Reflect.getPrototypeOf(Uint8Array));
+  return primordials;
+});
+//# sourceURL=node:internal/primordials
@@ -0,0 +1,67 @@
+// The class of `process.stdout` and `process.stderr`, a Writable over a
+// standard output. Evaluated once by stdio.rs, the script is a function
+// called with the `node:stream` module and the native binding of stdio.rs.
+// It returns the class, constructed with the file descriptor and its type:
+// 'tty', 'pipe' or 'fs'.
+//
+// The writes on a terminal or a file are synchronous. The ones on a pipe are
+// queued by the binding: the chunk is given right away, so it keeps its
+// order with the writes of the other output. Once the queued bytes reach
+// the highWaterMark, the write is only done when the reader took them: the
+// next chunks wait in the Writable, `write` returns false and 'drain' is
+// emitted as for any stream. A failed write destroys the stream.
+(function (stream, binding) {
+  "use strict";
+
+  class StdioWriteStream extends stream.Writable {
+    constructor(fd, type) {
+      super({ decodeStrings: false });
+      this.fd = fd;
+      this._type = type;
+      this._isStdio = true;
+      if (type === "tty") this.isTTY = true;
+      // Bytes queued by the binding and not written yet.
+      this._queued = 0;
+    }
+
+    _write(chunk, encoding, callback) {
+      if (this._type === "pipe") {
+        let called = false;
+        const length = binding.write(this.fd, chunk, encoding, (error) => {
+          this._queued -= length;
+          if (!called) {
+            callback(error);
+          } else if (error) {
+            this.destroy(error);
+          }
+        });
+        this._queued += length;
+        if (this._queued < this.writableHighWaterMark) {
+          called = true;
+          callback();
+        }
+        return;
+      }
+      try {
+        binding.writeSync(this.fd, chunk, encoding);
+      } catch (error) {
+        callback(error);
+        return;
+      }
+      callback();
+    }
+
+    // The colors supported by the terminal, as the WriteStream of
+    // `node:tty`.
+    getColorDepth() {
+      return binding.colorDepth();
+    }
+
+    hasColors(count = 16) {
+      return count <= 2 ** binding.colorDepth();
+    }
+  }
+
+  return StdioWriteStream;
+});
+//# sourceURL=node:internal/stdio
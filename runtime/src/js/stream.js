@@ -0,0 +1,2367 @@
+// The `node:stream` module: Readable, Writable, Duplex, Transform,
+// PassThrough, pipeline and finished. Evaluated once by stream.rs, the
+// script is a function called with the EventEmitter constructor, the
+// native `binding` converting strings and bytes ({ encode, decode,
+// completeLength, normalize }), the WHATWG streams, for the `toWeb` and
+// `fromWeb` conversions, and the shared helpers of internals.rs. It returns
+// the module, which is the legacy Stream constructor as in NodeJS.
+//
+// The chunks of the byte streams are Uint8Array, the runtime doesn't have
+// Buffer yet.
+(function (EventEmitter, binding, web, internals) {
+  "use strict";
+
+  let defaultHighWaterMark = 16 * 1024;
+  const defaultObjectHighWaterMark = 16;
+  const kConstruct = Symbol("kConstruct");
+  const kDestroy = Symbol("kDestroy");
+  const kOnFinished = Symbol("kOnFinished");
+  const kCallback = Symbol("kCallback");
+
+  const { codeError, abortError, nextTick } = internals;
+
+  function nop() {}
+
+  const errors = {
+    destroyed: (method) =>
+      codeError(
+        Error,
+        "ERR_STREAM_DESTROYED",
+        `Cannot call ${method} after a stream was destroyed`
+      ),
+    multipleCallback: () =>
+      codeError(Error, "ERR_MULTIPLE_CALLBACK", "Callback called multiple times"),
+    notImplemented: (method) =>
+      codeError(
+        Error,
+        "ERR_METHOD_NOT_IMPLEMENTED",
+        `The ${method} method is not implemented`
+      ),
+    nullValues: () =>
+      codeError(
+        TypeError,
+        "ERR_STREAM_NULL_VALUES",
+        "May not write null values to stream"
+      ),
+    prematureClose: () =>
+      codeError(Error, "ERR_STREAM_PREMATURE_CLOSE", "Premature close"),
+    invalidChunk: () =>
+      codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "chunk" argument must be of type string or an instance of Buffer, TypedArray, or DataView'
+      ),
+  };
+
+  // Bytes
+
+  function toBytes(view) {
+    return view instanceof Uint8Array
+      ? view
+      : new Uint8Array(view.buffer, view.byteOffset, view.byteLength);
+  }
+
+  function concat(chunks, length) {
+    const bytes = new Uint8Array(length);
+    let offset = 0;
+    for (const chunk of chunks) {
+      bytes.set(chunk, offset);
+      offset += chunk.length;
+    }
+    return bytes;
+  }
+
+  function sliceChunk(chunk, start, end) {
+    return typeof chunk === "string"
+      ? chunk.slice(start, end)
+      : chunk.subarray(start, end);
+  }
+
+  // Decode byte chunks into strings, keeping a character split between two
+  // chunks for the next one.
+  class Decoder {
+    constructor(encoding) {
+      this.encoding = binding.normalize(encoding ?? "utf8");
+      this.rest = null;
+    }
+
+    write(bytes) {
+      const all = this.rest ? concat([this.rest, bytes], this.rest.length + bytes.length) : bytes;
+      const complete = binding.completeLength(all, this.encoding);
+      this.rest = complete < all.length ? all.slice(complete) : null;
+      return complete > 0 ? binding.decode(all.subarray(0, complete), this.encoding) : "";
+    }
+
+    end() {
+      const rest = this.rest;
+      this.rest = null;
+      return rest ? binding.decode(rest, this.encoding) : "";
+    }
+  }
+
+  function getHighWaterMark(options, duplexKey, isDuplex, objectMode) {
+    const value =
+      options?.highWaterMark ?? (isDuplex ? options?.[duplexKey] : undefined);
+    if (value === undefined || value === null) {
+      return objectMode ? defaultObjectHighWaterMark : defaultHighWaterMark;
+    }
+    if (!Number.isInteger(value) || value < 0) {
+      const name = options?.highWaterMark != null ? "options.highWaterMark" : `options.${duplexKey}`;
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_VALUE",
+        `The property '${name}' is invalid. Received ${value}`
+      );
+    }
+    return value;
+  }
+
+  // Destruction, shared by the readable and writable sides.
+
+  function checkError(error, w, r) {
+    if (error) {
+      if (w && !w.errored) w.errored = error;
+      if (r && !r.errored) r.errored = error;
+    }
+  }
+
+  function destroy(error, callback) {
+    const r = this._readableState;
+    const w = this._writableState;
+    const state = w || r;
+    if (w?.destroyed || r?.destroyed) {
+      if (typeof callback === "function") callback();
+      return this;
+    }
+    checkError(error, w, r);
+    if (w) w.destroyed = true;
+    if (r) r.destroyed = true;
+    if (!state.constructed) {
+      this.once(kDestroy, (er) => _destroy(this, error || er, callback));
+    } else {
+      _destroy(this, error, callback);
+    }
+    return this;
+  }
+
+  function _destroy(stream, error, callback) {
+    let called = false;
+    function onDestroy(error) {
+      if (called) return;
+      called = true;
+      const r = stream._readableState;
+      const w = stream._writableState;
+      checkError(error, w, r);
+      if (w) w.closed = true;
+      if (r) r.closed = true;
+      if (typeof callback === "function") callback(error);
+      if (error) {
+        nextTick(emitErrorCloseNT, stream, error);
+      } else {
+        nextTick(emitCloseNT, stream);
+      }
+    }
+    try {
+      stream._destroy(error || null, onDestroy);
+    } catch (error) {
+      onDestroy(error);
+    }
+  }
+
+  function emitErrorCloseNT(stream, error) {
+    emitErrorNT(stream, error);
+    emitCloseNT(stream);
+  }
+
+  function emitCloseNT(stream) {
+    const r = stream._readableState;
+    const w = stream._writableState;
+    if (w) w.closeEmitted = true;
+    if (r) r.closeEmitted = true;
+    if (w?.emitClose || r?.emitClose) stream.emit("close");
+  }
+
+  function emitErrorNT(stream, error) {
+    const r = stream._readableState;
+    const w = stream._writableState;
+    if (w?.errorEmitted || r?.errorEmitted) return;
+    if (w) w.errorEmitted = true;
+    if (r) r.errorEmitted = true;
+    stream.emit("error", error);
+  }
+
+  // Destroy the stream if it's auto destroyed, emit 'error' otherwise.
+  function errorOrDestroy(stream, error, sync) {
+    const r = stream._readableState;
+    const w = stream._writableState;
+    if (w?.destroyed || r?.destroyed) return;
+    if (r?.autoDestroy || w?.autoDestroy) {
+      stream.destroy(error);
+    } else if (error) {
+      checkError(error, w, r);
+      if (sync) {
+        nextTick(emitErrorNT, stream, error);
+      } else {
+        emitErrorNT(stream, error);
+      }
+    }
+  }
+
+  // Call `stream._construct` before any read or write. A Duplex calls it
+  // once for both sides.
+  function construct(stream, callback) {
+    if (typeof stream._construct !== "function") return;
+    const r = stream._readableState;
+    const w = stream._writableState;
+    if (r) r.constructed = false;
+    if (w) w.constructed = false;
+    stream.once(kConstruct, callback);
+    if (stream.listenerCount(kConstruct) > 1) return;
+    nextTick(constructNT, stream);
+  }
+
+  function constructNT(stream) {
+    let called = false;
+    function onConstruct(error) {
+      if (called) {
+        errorOrDestroy(stream, error ?? errors.multipleCallback());
+        return;
+      }
+      called = true;
+      const r = stream._readableState;
+      const w = stream._writableState;
+      const state = w || r;
+      if (r) r.constructed = true;
+      if (w) w.constructed = true;
+      if (state.destroyed) {
+        stream.emit(kDestroy, error);
+      } else if (error) {
+        errorOrDestroy(stream, error, true);
+      } else {
+        nextTick(() => stream.emit(kConstruct));
+      }
+    }
+    try {
+      stream._construct((error) => nextTick(onConstruct, error));
+    } catch (error) {
+      nextTick(onConstruct, error);
+    }
+  }
+
+  function addAbortSignal(signal, stream) {
+    if (signal === null || typeof signal !== "object" || !("aborted" in signal)) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "signal" argument must be an instance of AbortSignal'
+      );
+    }
+    const onAbort = () => stream.destroy(abortError(signal));
+    if (signal.aborted) {
+      onAbort();
+    } else {
+      signal.addEventListener("abort", onAbort, { once: true });
+      eos(stream, () => signal.removeEventListener("abort", onAbort));
+    }
+    return stream;
+  }
+
+  // Legacy Stream
+
+  function Stream(options) {
+    EventEmitter.call(this, options);
+  }
+  Object.setPrototypeOf(Stream.prototype, EventEmitter.prototype);
+  Object.setPrototypeOf(Stream, EventEmitter);
+
+  // Readable
+
+  function ReadableState(options, isDuplex) {
+    this.objectMode = Boolean(
+      options?.objectMode || (isDuplex && options?.readableObjectMode)
+    );
+    this.highWaterMark = getHighWaterMark(
+      options,
+      "readableHighWaterMark",
+      isDuplex,
+      this.objectMode
+    );
+    this.buffer = [];
+    this.length = 0;
+    this.pipes = [];
+    this.flowing = null;
+    this.ended = false;
+    this.endEmitted = false;
+    this.reading = false;
+    this.constructed = true;
+    // True while inside `_read`, the pushed chunks are emitted later.
+    this.sync = true;
+    this.needReadable = false;
+    this.emittedReadable = false;
+    this.readableListening = false;
+    this.resumeScheduled = false;
+    this.paused = null;
+    this.errorEmitted = false;
+    this.emitClose = options?.emitClose !== false;
+    this.autoDestroy = options?.autoDestroy !== false;
+    this.destroyed = false;
+    this.errored = null;
+    this.closed = false;
+    this.closeEmitted = false;
+    this.defaultEncoding = options?.defaultEncoding || "utf8";
+    this.awaitDrainWriters = null;
+    this.multiAwaitDrain = false;
+    this.readingMore = false;
+    this.dataEmitted = false;
+    this.decoder = null;
+    this.encoding = null;
+    if (options?.encoding) {
+      this.decoder = new Decoder(options.encoding);
+      this.encoding = this.decoder.encoding;
+    }
+  }
+
+  function Readable(options) {
+    if (!(this instanceof Readable)) return new Readable(options);
+    const isDuplex = this instanceof Duplex;
+    this._readableState = new ReadableState(options, isDuplex);
+    if (options) {
+      if (typeof options.read === "function") this._read = options.read;
+      if (typeof options.destroy === "function") this._destroy = options.destroy;
+      if (typeof options.construct === "function") this._construct = options.construct;
+      if (options.signal && !isDuplex) addAbortSignal(options.signal, this);
+    }
+    Stream.call(this, options);
+    construct(this, () => {
+      if (this._readableState.needReadable) {
+        maybeReadMore(this, this._readableState);
+      }
+    });
+  }
+  Object.setPrototypeOf(Readable.prototype, Stream.prototype);
+  Object.setPrototypeOf(Readable, Stream);
+
+  Readable.prototype.destroy = destroy;
+
+  Readable.prototype._destroy = function (error, callback) {
+    callback(error);
+  };
+
+  Readable.prototype._read = function () {
+    throw errors.notImplemented("_read()");
+  };
+
+  Readable.prototype.push = function (chunk, encoding) {
+    return readableAddChunk(this, chunk, encoding, false);
+  };
+
+  Readable.prototype.unshift = function (chunk, encoding) {
+    return readableAddChunk(this, chunk, encoding, true);
+  };
+
+  function readableAddChunk(stream, chunk, encoding, addToFront) {
+    const state = stream._readableState;
+    let error;
+    if (!state.objectMode) {
+      if (typeof chunk === "string") {
+        encoding = encoding || state.defaultEncoding;
+        if (state.encoding !== encoding) {
+          if (addToFront && state.encoding) {
+            chunk = binding.decode(binding.encode(chunk, encoding), state.encoding);
+          } else {
+            chunk = binding.encode(chunk, encoding);
+            encoding = "";
+          }
+        }
+      } else if (ArrayBuffer.isView(chunk)) {
+        chunk = toBytes(chunk);
+        encoding = "";
+      } else if (chunk !== null && chunk !== undefined) {
+        error = errors.invalidChunk();
+      }
+    }
+    if (error) {
+      errorOrDestroy(stream, error);
+    } else if (chunk === null) {
+      state.reading = false;
+      onEofChunk(stream, state);
+    } else if (state.objectMode || chunk.length > 0) {
+      if (addToFront) {
+        if (state.endEmitted) {
+          errorOrDestroy(
+            stream,
+            codeError(
+              Error,
+              "ERR_STREAM_UNSHIFT_AFTER_END_EVENT",
+              "stream.unshift() after end event"
+            )
+          );
+        } else if (state.destroyed || state.errored) {
+          return false;
+        } else {
+          addChunk(stream, state, chunk, true);
+        }
+      } else if (state.ended) {
+        errorOrDestroy(
+          stream,
+          codeError(Error, "ERR_STREAM_PUSH_AFTER_EOF", "stream.push() after EOF")
+        );
+      } else if (state.destroyed || state.errored) {
+        return false;
+      } else {
+        state.reading = false;
+        if (state.decoder && !encoding) {
+          chunk = state.decoder.write(chunk);
+          if (state.objectMode || chunk.length !== 0) {
+            addChunk(stream, state, chunk, false);
+          } else {
+            maybeReadMore(stream, state);
+          }
+        } else {
+          addChunk(stream, state, chunk, false);
+        }
+      }
+    } else if (!addToFront) {
+      state.reading = false;
+      maybeReadMore(stream, state);
+    }
+    return !state.ended && (state.length < state.highWaterMark || state.length === 0);
+  }
+
+  function addChunk(stream, state, chunk, addToFront) {
+    if (
+      state.flowing &&
+      state.length === 0 &&
+      !state.sync &&
+      stream.listenerCount("data") > 0
+    ) {
+      // Straight to the consumer, without buffering.
+      clearAwaitDrain(state);
+      state.dataEmitted = true;
+      stream.emit("data", chunk);
+    } else {
+      state.length += state.objectMode ? 1 : chunk.length;
+      if (addToFront) {
+        state.buffer.unshift(chunk);
+      } else {
+        state.buffer.push(chunk);
+      }
+      if (state.needReadable) emitReadable(stream);
+    }
+    maybeReadMore(stream, state);
+  }
+
+  function clearAwaitDrain(state) {
+    if (state.multiAwaitDrain) {
+      state.awaitDrainWriters.clear();
+    } else {
+      state.awaitDrainWriters = null;
+    }
+  }
+
+  Readable.prototype.isPaused = function () {
+    const state = this._readableState;
+    return state.paused === true || state.flowing === false;
+  };
+
+  Readable.prototype.setEncoding = function (encoding) {
+    const state = this._readableState;
+    const decoder = new Decoder(encoding);
+    state.decoder = decoder;
+    state.encoding = decoder.encoding;
+    let content = "";
+    for (const chunk of state.buffer) {
+      content += typeof chunk === "string" ? chunk : decoder.write(chunk);
+    }
+    state.buffer = content ? [content] : [];
+    state.length = content.length;
+    return this;
+  };
+
+  function computeNewHighWaterMark(n) {
+    // The next power of 2, up to 1GiB.
+    if (n > 0x40000000) {
+      throw codeError(
+        RangeError,
+        "ERR_OUT_OF_RANGE",
+        `The value of "size" is out of range. It must be <= 1GiB. Received ${n}`
+      );
+    }
+    let size = 1;
+    while (size < n) size *= 2;
+    return size;
+  }
+
+  function howMuchToRead(n, state) {
+    if (n <= 0 || (state.length === 0 && state.ended)) return 0;
+    if (state.objectMode) return 1;
+    if (Number.isNaN(n)) {
+      // Everything, or the first chunk when flowing.
+      return state.flowing && state.length ? state.buffer[0].length : state.length;
+    }
+    if (n <= state.length) return n;
+    return state.ended ? state.length : 0;
+  }
+
+  Readable.prototype.read = function (n) {
+    if (n === undefined) {
+      n = NaN;
+    } else if (!Number.isInteger(n)) {
+      n = Number.parseInt(n, 10);
+    }
+    const state = this._readableState;
+    const nOrig = n;
+    if (n > state.highWaterMark) state.highWaterMark = computeNewHighWaterMark(n);
+    if (n !== 0) state.emittedReadable = false;
+    if (
+      n === 0 &&
+      state.needReadable &&
+      ((state.highWaterMark !== 0
+        ? state.length >= state.highWaterMark
+        : state.length > 0) ||
+        state.ended)
+    ) {
+      if (state.length === 0 && state.ended) {
+        endReadable(this);
+      } else {
+        emitReadable(this);
+      }
+      return null;
+    }
+    n = howMuchToRead(n, state);
+    if (n === 0 && state.ended) {
+      if (state.length === 0) endReadable(this);
+      return null;
+    }
+    let doRead = state.needReadable;
+    if (state.length === 0 || state.length - n < state.highWaterMark) doRead = true;
+    if (
+      state.ended ||
+      state.reading ||
+      state.destroyed ||
+      state.errored ||
+      !state.constructed
+    ) {
+      doRead = false;
+    } else if (doRead) {
+      state.reading = true;
+      state.sync = true;
+      if (state.length === 0) state.needReadable = true;
+      try {
+        this._read(state.highWaterMark);
+      } catch (error) {
+        errorOrDestroy(this, error);
+      }
+      state.sync = false;
+      // A synchronous push changes what can be returned.
+      if (!state.reading) n = howMuchToRead(nOrig, state);
+    }
+    const chunk = n > 0 ? fromList(n, state) : null;
+    if (chunk === null) {
+      state.needReadable = state.length <= state.highWaterMark;
+      n = 0;
+    } else {
+      state.length -= n;
+      clearAwaitDrain(state);
+    }
+    if (state.length === 0) {
+      if (!state.ended) state.needReadable = true;
+      if (nOrig !== n && state.ended) endReadable(this);
+    }
+    if (chunk !== null && !state.errorEmitted && !state.closeEmitted) {
+      state.dataEmitted = true;
+      this.emit("data", chunk);
+    }
+    return chunk;
+  };
+
+  // Take `n` bytes or characters (one object in object mode) from the buffer.
+  function fromList(n, state) {
+    if (state.length === 0) return null;
+    const buffer = state.buffer;
+    if (state.objectMode) return buffer.shift();
+    if (n >= state.length) {
+      let all;
+      if (state.decoder) {
+        all = buffer.join("");
+      } else if (buffer.length === 1) {
+        all = buffer[0];
+      } else {
+        all = concat(buffer, state.length);
+      }
+      buffer.length = 0;
+      return all;
+    }
+    if (n < buffer[0].length) {
+      const head = buffer[0];
+      buffer[0] = sliceChunk(head, n);
+      return sliceChunk(head, 0, n);
+    }
+    if (n === buffer[0].length) return buffer.shift();
+    const parts = [];
+    let taken = 0;
+    while (taken < n) {
+      const chunk = buffer[0];
+      const needed = n - taken;
+      if (chunk.length <= needed) {
+        parts.push(chunk);
+        taken += chunk.length;
+        buffer.shift();
+      } else {
+        parts.push(sliceChunk(chunk, 0, needed));
+        buffer[0] = sliceChunk(chunk, needed);
+        taken = n;
+      }
+    }
+    return state.decoder ? parts.join("") : concat(parts, n);
+  }
+
+  function onEofChunk(stream, state) {
+    if (state.ended) return;
+    if (state.decoder) {
+      const chunk = state.decoder.end();
+      if (chunk.length) {
+        state.buffer.push(chunk);
+        state.length += state.objectMode ? 1 : chunk.length;
+      }
+    }
+    state.ended = true;
+    if (state.sync) {
+      emitReadable(stream);
+    } else {
+      state.needReadable = false;
+      state.emittedReadable = true;
+      emitReadable_(stream);
+    }
+  }
+
+  function emitReadable(stream) {
+    const state = stream._readableState;
+    state.needReadable = false;
+    if (!state.emittedReadable) {
+      state.emittedReadable = true;
+      nextTick(emitReadable_, stream);
+    }
+  }
+
+  function emitReadable_(stream) {
+    const state = stream._readableState;
+    if (!state.destroyed && !state.errored && (state.length || state.ended)) {
+      stream.emit("readable");
+      state.emittedReadable = false;
+    }
+    state.needReadable =
+      !state.flowing && !state.ended && state.length <= state.highWaterMark;
+    flow(stream);
+  }
+
+  // Read ahead up to the highWaterMark.
+  function maybeReadMore(stream, state) {
+    if (!state.readingMore && state.constructed) {
+      state.readingMore = true;
+      nextTick(maybeReadMore_, stream, state);
+    }
+  }
+
+  function maybeReadMore_(stream, state) {
+    while (
+      !state.reading &&
+      !state.ended &&
+      (state.length < state.highWaterMark || (state.flowing && state.length === 0))
+    ) {
+      const length = state.length;
+      stream.read(0);
+      if (length === state.length) break;
+    }
+    state.readingMore = false;
+  }
+
+  function flow(stream) {
+    const state = stream._readableState;
+    while (state.flowing && stream.read() !== null);
+  }
+
+  function endReadable(stream) {
+    const state = stream._readableState;
+    if (!state.endEmitted) {
+      state.ended = true;
+      nextTick(endReadableNT, state, stream);
+    }
+  }
+
+  function endReadableNT(state, stream) {
+    if (
+      !state.errored &&
+      !state.closeEmitted &&
+      !state.endEmitted &&
+      state.length === 0
+    ) {
+      state.endEmitted = true;
+      stream.emit("end");
+      if (stream.writable && stream.allowHalfOpen === false) {
+        nextTick(endWritableNT, stream);
+      } else if (state.autoDestroy) {
+        // A Duplex is destroyed once both sides are done.
+        const w = stream._writableState;
+        if (!w || (w.autoDestroy && (w.finished || w.writable === false))) {
+          stream.destroy();
+        }
+      }
+    }
+  }
+
+  function endWritableNT(stream) {
+    if (stream.writable && !stream.writableEnded && !stream.destroyed) {
+      stream.end();
+    }
+  }
+
+  // A 'data' listener switches to the flowing mode, a 'readable' listener to
+  // the paused mode.
+  Readable.prototype.on = function (event, listener) {
+    const result = Stream.prototype.on.call(this, event, listener);
+    const state = this._readableState;
+    if (event === "data") {
+      state.readableListening = this.listenerCount("readable") > 0;
+      if (state.flowing !== false) this.resume();
+    } else if (event === "readable") {
+      if (!state.endEmitted && !state.readableListening) {
+        state.readableListening = state.needReadable = true;
+        state.flowing = false;
+        state.emittedReadable = false;
+        if (state.length) {
+          emitReadable(this);
+        } else if (!state.reading) {
+          nextTick(() => this.read(0));
+        }
+      }
+    }
+    return result;
+  };
+
+  Readable.prototype.addListener = Readable.prototype.on;
+
+  Readable.prototype.removeListener = function (event, listener) {
+    const result = Stream.prototype.removeListener.call(this, event, listener);
+    if (event === "readable") nextTick(updateReadableListening, this);
+    return result;
+  };
+
+  Readable.prototype.off = Readable.prototype.removeListener;
+
+  Readable.prototype.removeAllListeners = function (event) {
+    const result = Stream.prototype.removeAllListeners.apply(this, arguments);
+    if (event === "readable" || event === undefined) {
+      nextTick(updateReadableListening, this);
+    }
+    return result;
+  };
+
+  function updateReadableListening(stream) {
+    const state = stream._readableState;
+    state.readableListening = stream.listenerCount("readable") > 0;
+    if (state.resumeScheduled && state.paused === false) {
+      state.flowing = true;
+    } else if (stream.listenerCount("data") > 0) {
+      stream.resume();
+    } else if (!state.readableListening) {
+      state.flowing = null;
+    }
+  }
+
+  Readable.prototype.resume = function () {
+    const state = this._readableState;
+    if (!state.flowing) {
+      state.flowing = !state.readableListening;
+      if (!state.resumeScheduled) {
+        state.resumeScheduled = true;
+        nextTick(resume_, this, state);
+      }
+    }
+    state.paused = false;
+    return this;
+  };
+
+  function resume_(stream, state) {
+    if (!state.reading) stream.read(0);
+    state.resumeScheduled = false;
+    stream.emit("resume");
+    flow(stream);
+    if (state.flowing && !state.reading) stream.read(0);
+  }
+
+  Readable.prototype.pause = function () {
+    const state = this._readableState;
+    if (state.flowing !== false) {
+      state.flowing = false;
+      this.emit("pause");
+    }
+    state.paused = true;
+    return this;
+  };
+
+  Readable.prototype.pipe = function (dest, options) {
+    const src = this;
+    const state = this._readableState;
+    if (state.pipes.length === 1 && !state.multiAwaitDrain) {
+      state.multiAwaitDrain = true;
+      state.awaitDrainWriters = new Set(
+        state.awaitDrainWriters ? [state.awaitDrainWriters] : []
+      );
+    }
+    state.pipes.push(dest);
+
+    const endFn = options?.end !== false ? onend : unpipe;
+    if (state.endEmitted) {
+      nextTick(endFn);
+    } else {
+      src.once("end", endFn);
+    }
+    dest.on("unpipe", onunpipe);
+
+    function onunpipe(readable, info) {
+      if (readable === src && info?.hasUnpiped === false) {
+        info.hasUnpiped = true;
+        cleanup();
+      }
+    }
+
+    function onend() {
+      dest.end();
+    }
+
+    let ondrain;
+    let cleanedUp = false;
+    function cleanup() {
+      dest.removeListener("close", onclose);
+      dest.removeListener("finish", onfinish);
+      if (ondrain) dest.removeListener("drain", ondrain);
+      dest.removeListener("error", onerror);
+      dest.removeListener("unpipe", onunpipe);
+      src.removeListener("end", onend);
+      src.removeListener("end", unpipe);
+      src.removeListener("data", ondata);
+      cleanedUp = true;
+      if (ondrain && state.awaitDrainWriters && (!dest._writableState || dest._writableState.needDrain)) {
+        ondrain();
+      }
+    }
+
+    function pause() {
+      if (!cleanedUp) {
+        if (state.pipes.length === 1 && state.pipes[0] === dest) {
+          state.awaitDrainWriters = dest;
+          state.multiAwaitDrain = false;
+        } else if (state.pipes.length > 1 && state.pipes.includes(dest)) {
+          state.awaitDrainWriters.add(dest);
+        }
+        src.pause();
+      }
+      if (!ondrain) {
+        ondrain = pipeOnDrain(src, dest);
+        dest.on("drain", ondrain);
+      }
+    }
+
+    function ondata(chunk) {
+      if (dest.write(chunk) === false) pause();
+    }
+    src.on("data", ondata);
+
+    // The destination errors are reported by the destination only.
+    function onerror(error) {
+      unpipe();
+      dest.removeListener("error", onerror);
+      if (dest.listenerCount("error") === 0) {
+        const destState = dest._writableState || dest._readableState;
+        if (destState && !destState.errorEmitted) {
+          errorOrDestroy(dest, error);
+        } else {
+          dest.emit("error", error);
+        }
+      }
+    }
+    dest.prependListener("error", onerror);
+
+    function onclose() {
+      dest.removeListener("finish", onfinish);
+      unpipe();
+    }
+    dest.once("close", onclose);
+
+    function onfinish() {
+      dest.removeListener("close", onclose);
+      unpipe();
+    }
+    dest.once("finish", onfinish);
+
+    function unpipe() {
+      src.unpipe(dest);
+    }
+
+    dest.emit("pipe", src);
+    if (dest.writableNeedDrain === true) {
+      pause();
+    } else if (!state.flowing) {
+      src.resume();
+    }
+    return dest;
+  };
+
+  function pipeOnDrain(src, dest) {
+    return function () {
+      const state = src._readableState;
+      if (state.awaitDrainWriters === dest) {
+        state.awaitDrainWriters = null;
+      } else if (state.multiAwaitDrain) {
+        state.awaitDrainWriters.delete(dest);
+      }
+      if (
+        (!state.awaitDrainWriters || state.awaitDrainWriters.size === 0) &&
+        src.listenerCount("data")
+      ) {
+        src.resume();
+      }
+    };
+  }
+
+  Readable.prototype.unpipe = function (dest) {
+    const state = this._readableState;
+    if (state.pipes.length === 0) return this;
+    if (!dest) {
+      const dests = state.pipes;
+      state.pipes = [];
+      this.pause();
+      for (const dest of dests) {
+        dest.emit("unpipe", this, { hasUnpiped: false });
+      }
+      return this;
+    }
+    const index = state.pipes.indexOf(dest);
+    if (index === -1) return this;
+    state.pipes.splice(index, 1);
+    if (state.pipes.length === 0) this.pause();
+    dest.emit("unpipe", this, { hasUnpiped: false });
+    return this;
+  };
+
+  Readable.prototype[Symbol.asyncIterator] = function () {
+    return createAsyncIterator(this);
+  };
+
+  Readable.prototype.iterator = function (options) {
+    return createAsyncIterator(this, options);
+  };
+
+  async function* createAsyncIterator(stream, options) {
+    let callback = nop;
+    function next(resolve) {
+      if (this === stream) {
+        callback();
+        callback = nop;
+      } else {
+        callback = resolve;
+      }
+    }
+    stream.on("readable", next);
+    let error;
+    const cleanup = eos(stream, { writable: false }, (err) => {
+      error = err ? err : null;
+      callback();
+      callback = nop;
+    });
+    try {
+      while (true) {
+        const chunk = stream.destroyed ? null : stream.read();
+        if (chunk !== null) {
+          yield chunk;
+        } else if (error) {
+          throw error;
+        } else if (error === null) {
+          return;
+        } else {
+          await new Promise(next);
+        }
+      }
+    } catch (err) {
+      error = err;
+      throw error;
+    } finally {
+      if (
+        (error || options?.destroyOnReturn !== false) &&
+        (error === undefined || stream._readableState.autoDestroy)
+      ) {
+        stream.destroy(null);
+      } else {
+        stream.off("readable", next);
+        cleanup();
+      }
+    }
+  }
+
+  Readable.from = function (iterable, options) {
+    if (typeof iterable === "string" || ArrayBuffer.isView(iterable)) {
+      return new Readable({
+        objectMode: true,
+        ...options,
+        read() {
+          this.push(iterable);
+          this.push(null);
+        },
+      });
+    }
+    let iterator;
+    let isAsync;
+    if (iterable && typeof iterable[Symbol.asyncIterator] === "function") {
+      isAsync = true;
+      iterator = iterable[Symbol.asyncIterator]();
+    } else if (iterable && typeof iterable[Symbol.iterator] === "function") {
+      isAsync = false;
+      iterator = iterable[Symbol.iterator]();
+    } else {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "iterable" argument must be an instance of Iterable'
+      );
+    }
+    const readable = new Readable({ objectMode: true, highWaterMark: 1, ...options });
+    let reading = false;
+    readable._read = function () {
+      if (!reading) {
+        reading = true;
+        next();
+      }
+    };
+    readable._destroy = function (error, callback) {
+      close(error).then(
+        () => nextTick(callback, error),
+        (err) => nextTick(callback, err || error)
+      );
+    };
+    async function close(error) {
+      if (error !== undefined && error !== null && typeof iterator.throw === "function") {
+        const { value, done } = await iterator.throw(error);
+        await value;
+        if (done) return;
+      }
+      if (typeof iterator.return === "function") {
+        const { value } = await iterator.return();
+        await value;
+      }
+    }
+    async function next() {
+      for (;;) {
+        try {
+          const { value, done } = isAsync ? await iterator.next() : iterator.next();
+          if (done) {
+            readable.push(null);
+          } else {
+            const chunk = value && typeof value.then === "function" ? await value : value;
+            if (chunk === null) {
+              reading = false;
+              throw errors.nullValues();
+            } else if (readable.push(chunk)) {
+              continue;
+            } else {
+              reading = false;
+            }
+          }
+        } catch (error) {
+          readable.destroy(error);
+        }
+        break;
+      }
+    }
+    return readable;
+  };
+
+  function defineGetters(prototype, getters) {
+    for (const [name, get] of Object.entries(getters)) {
+      const descriptor = typeof get === "function" ? { get } : get;
+      Object.defineProperty(prototype, name, { enumerable: false, configurable: true, ...descriptor });
+    }
+  }
+
+  defineGetters(Readable.prototype, {
+    readable: {
+      get() {
+        const r = this._readableState;
+        return Boolean(r) && r.readable !== false && !r.destroyed && !r.errorEmitted && !r.endEmitted;
+      },
+      set(value) {
+        if (this._readableState) this._readableState.readable = Boolean(value);
+      },
+    },
+    readableDidRead() {
+      return this._readableState.dataEmitted;
+    },
+    readableAborted() {
+      const r = this._readableState;
+      return Boolean(r.readable !== false && (r.destroyed || r.errored) && !r.endEmitted);
+    },
+    readableHighWaterMark() {
+      return this._readableState.highWaterMark;
+    },
+    readableBuffer() {
+      return this._readableState.buffer;
+    },
+    readableFlowing: {
+      get() {
+        return this._readableState.flowing;
+      },
+      set(value) {
+        this._readableState.flowing = value;
+      },
+    },
+    readableLength() {
+      return this._readableState.length;
+    },
+    readableObjectMode() {
+      return this._readableState.objectMode;
+    },
+    readableEncoding() {
+      return this._readableState.encoding;
+    },
+    errored() {
+      return this._readableState.errored;
+    },
+    closed() {
+      return this._readableState.closed;
+    },
+    destroyed: {
+      get() {
+        return this._readableState.destroyed;
+      },
+      set(value) {
+        this._readableState.destroyed = value;
+      },
+    },
+    readableEnded() {
+      return this._readableState.endEmitted;
+    },
+  });
+
+  // Writable
+
+  function WritableState(options, stream, isDuplex) {
+    this.objectMode = Boolean(
+      options?.objectMode || (isDuplex && options?.writableObjectMode)
+    );
+    this.highWaterMark = getHighWaterMark(
+      options,
+      "writableHighWaterMark",
+      isDuplex,
+      this.objectMode
+    );
+    this.finalCalled = false;
+    this.needDrain = false;
+    // `end()` has been called.
+    this.ending = false;
+    this.ended = false;
+    // 'finish' has been emitted.
+    this.finished = false;
+    this.destroyed = false;
+    this.decodeStrings = options?.decodeStrings !== false;
+    this.defaultEncoding = options?.defaultEncoding || "utf8";
+    this.length = 0;
+    this.writing = false;
+    this.corked = 0;
+    // True while inside `_write`, the callback is then called later.
+    this.sync = true;
+    this.bufferProcessing = false;
+    this.onwrite = onwrite.bind(undefined, stream);
+    this.writecb = null;
+    this.writelen = 0;
+    this.afterWriteTickInfo = null;
+    this.buffered = [];
+    this.bufferedIndex = 0;
+    this.allBuffers = true;
+    this.allNoop = true;
+    this.pendingcb = 0;
+    this.constructed = true;
+    this.prefinished = false;
+    this.errorEmitted = false;
+    this.emitClose = options?.emitClose !== false;
+    this.autoDestroy = options?.autoDestroy !== false;
+    this.errored = null;
+    this.closed = false;
+    this.closeEmitted = false;
+    this[kOnFinished] = [];
+  }
+
+  function resetBuffer(state) {
+    state.buffered = [];
+    state.bufferedIndex = 0;
+    state.allBuffers = true;
+    state.allNoop = true;
+  }
+
+  function Writable(options) {
+    const isDuplex = this instanceof Duplex;
+    if (!isDuplex && !(this instanceof Writable)) return new Writable(options);
+    this._writableState = new WritableState(options, this, isDuplex);
+    if (options) {
+      if (typeof options.write === "function") this._write = options.write;
+      if (typeof options.writev === "function") this._writev = options.writev;
+      if (typeof options.destroy === "function") this._destroy = options.destroy;
+      if (typeof options.final === "function") this._final = options.final;
+      if (typeof options.construct === "function") this._construct = options.construct;
+      if (options.signal) addAbortSignal(options.signal, this);
+    }
+    Stream.call(this, options);
+    construct(this, () => {
+      const state = this._writableState;
+      if (!state.writing) clearBuffer(this, state);
+      finishMaybe(this, state);
+    });
+  }
+  Object.setPrototypeOf(Writable.prototype, Stream.prototype);
+  Object.setPrototypeOf(Writable, Stream);
+
+  // A Duplex is a Writable too, without inheriting from it.
+  Object.defineProperty(Writable, Symbol.hasInstance, {
+    value(object) {
+      if (Function.prototype[Symbol.hasInstance].call(this, object)) return true;
+      if (this !== Writable) return false;
+      return Boolean(object && object._writableState instanceof WritableState);
+    },
+  });
+
+  Writable.prototype.pipe = function () {
+    errorOrDestroy(this, codeError(Error, "ERR_STREAM_CANNOT_PIPE", "Cannot pipe, not readable"));
+  };
+
+  Writable.prototype.write = function (chunk, encoding, callback) {
+    return _write(this, chunk, encoding, callback) === true;
+  };
+
+  function _write(stream, chunk, encoding, callback) {
+    const state = stream._writableState;
+    if (typeof encoding === "function") {
+      callback = encoding;
+      encoding = state.defaultEncoding;
+    } else {
+      if (!encoding) {
+        encoding = state.defaultEncoding;
+      } else if (encoding !== "buffer") {
+        encoding = binding.normalize(encoding);
+      }
+      if (typeof callback !== "function") callback = nop;
+    }
+    if (chunk === null) {
+      throw errors.nullValues();
+    } else if (!state.objectMode) {
+      if (typeof chunk === "string") {
+        if (state.decodeStrings) {
+          chunk = binding.encode(chunk, encoding);
+          encoding = "buffer";
+        }
+      } else if (ArrayBuffer.isView(chunk)) {
+        chunk = toBytes(chunk);
+        encoding = "buffer";
+      } else {
+        throw errors.invalidChunk();
+      }
+    }
+    let error;
+    if (state.ending) {
+      error = codeError(Error, "ERR_STREAM_WRITE_AFTER_END", "write after end");
+    } else if (state.destroyed) {
+      error = errors.destroyed("write");
+    }
+    if (error) {
+      nextTick(callback, error);
+      errorOrDestroy(stream, error, true);
+      return error;
+    }
+    state.pendingcb++;
+    return writeOrBuffer(stream, state, chunk, encoding, callback);
+  }
+
+  function writeOrBuffer(stream, state, chunk, encoding, callback) {
+    const length = state.objectMode ? 1 : chunk.length;
+    state.length += length;
+    const belowHighWaterMark = state.length < state.highWaterMark;
+    if (!belowHighWaterMark) state.needDrain = true;
+    if (state.writing || state.corked || state.errored || !state.constructed) {
+      state.buffered.push({ chunk, encoding, callback });
+      if (state.allBuffers && encoding !== "buffer") state.allBuffers = false;
+      if (state.allNoop && callback !== nop) state.allNoop = false;
+    } else {
+      state.writelen = length;
+      state.writecb = callback;
+      state.writing = true;
+      state.sync = true;
+      stream._write(chunk, encoding, state.onwrite);
+      state.sync = false;
+    }
+    return belowHighWaterMark && !state.errored && !state.destroyed;
+  }
+
+  function doWrite(stream, state, writev, length, chunk, encoding, callback) {
+    state.writelen = length;
+    state.writecb = callback;
+    state.writing = true;
+    state.sync = true;
+    if (state.destroyed) {
+      state.onwrite(errors.destroyed("write"));
+    } else if (writev) {
+      stream._writev(chunk, state.onwrite);
+    } else {
+      stream._write(chunk, encoding, state.onwrite);
+    }
+    state.sync = false;
+  }
+
+  function onwriteError(stream, state, error, callback) {
+    --state.pendingcb;
+    callback(error);
+    errorBuffer(state);
+    errorOrDestroy(stream, error);
+  }
+
+  function onwrite(stream, error) {
+    const state = stream._writableState;
+    const sync = state.sync;
+    const callback = state.writecb;
+    if (typeof callback !== "function") {
+      errorOrDestroy(stream, errors.multipleCallback());
+      return;
+    }
+    state.writing = false;
+    state.writecb = null;
+    state.length -= state.writelen;
+    state.writelen = 0;
+    if (error) {
+      if (!state.errored) state.errored = error;
+      if (stream._readableState && !stream._readableState.errored) {
+        stream._readableState.errored = error;
+      }
+      if (sync) {
+        nextTick(onwriteError, stream, state, error, callback);
+      } else {
+        onwriteError(stream, state, error, callback);
+      }
+    } else {
+      if (state.buffered.length > state.bufferedIndex) clearBuffer(stream, state);
+      if (sync) {
+        // The callbacks of the synchronous writes are grouped in one tick.
+        if (state.afterWriteTickInfo?.callback === callback) {
+          state.afterWriteTickInfo.count++;
+        } else {
+          state.afterWriteTickInfo = { count: 1, callback, stream, state };
+          nextTick(afterWriteTick, state.afterWriteTickInfo);
+        }
+      } else {
+        afterWrite(stream, state, 1, callback);
+      }
+    }
+  }
+
+  function afterWriteTick({ stream, state, count, callback }) {
+    state.afterWriteTickInfo = null;
+    afterWrite(stream, state, count, callback);
+  }
+
+  function afterWrite(stream, state, count, callback) {
+    const needDrain =
+      !state.ending && !stream.destroyed && state.length === 0 && state.needDrain;
+    if (needDrain) {
+      state.needDrain = false;
+      stream.emit("drain");
+    }
+    while (count-- > 0) {
+      state.pendingcb--;
+      callback(null);
+    }
+    if (state.destroyed) errorBuffer(state);
+    finishMaybe(stream, state);
+  }
+
+  // Fail the buffered writes of a destroyed stream.
+  function errorBuffer(state) {
+    if (state.writing) return;
+    for (let index = state.bufferedIndex; index < state.buffered.length; ++index) {
+      const { chunk, callback } = state.buffered[index];
+      state.length -= state.objectMode ? 1 : chunk.length;
+      callback(state.errored ?? errors.destroyed("write"));
+    }
+    for (const callback of state[kOnFinished].splice(0)) {
+      callback(state.errored ?? errors.destroyed("end"));
+    }
+    resetBuffer(state);
+  }
+
+  // Write the buffered chunks, at once with `_writev` if there is one.
+  function clearBuffer(stream, state) {
+    if (state.corked || state.bufferProcessing || state.destroyed || !state.constructed) {
+      return;
+    }
+    const { buffered, bufferedIndex, objectMode } = state;
+    const bufferedLength = buffered.length - bufferedIndex;
+    if (!bufferedLength) return;
+    let index = bufferedIndex;
+    state.bufferProcessing = true;
+    if (bufferedLength > 1 && stream._writev) {
+      state.pendingcb -= bufferedLength - 1;
+      const callback = state.allNoop
+        ? nop
+        : (error) => {
+            for (let n = index; n < buffered.length; ++n) {
+              buffered[n].callback(error);
+            }
+          };
+      const chunks = state.allNoop && index === 0 ? buffered : buffered.slice(index);
+      chunks.allBuffers = state.allBuffers;
+      doWrite(stream, state, true, state.length, chunks, "", callback);
+      resetBuffer(state);
+    } else {
+      do {
+        const { chunk, encoding, callback } = buffered[index];
+        buffered[index++] = null;
+        const length = objectMode ? 1 : chunk.length;
+        doWrite(stream, state, false, length, chunk, encoding, callback);
+      } while (index < buffered.length && !state.writing);
+      if (index === buffered.length) {
+        resetBuffer(state);
+      } else if (index > 256) {
+        buffered.splice(0, index);
+        state.bufferedIndex = 0;
+      } else {
+        state.bufferedIndex = index;
+      }
+    }
+    state.bufferProcessing = false;
+  }
+
+  Writable.prototype._write = function (chunk, encoding, callback) {
+    if (this._writev) {
+      this._writev([{ chunk, encoding }], callback);
+    } else {
+      throw errors.notImplemented("_write()");
+    }
+  };
+
+  Writable.prototype._writev = null;
+
+  Writable.prototype.cork = function () {
+    this._writableState.corked++;
+  };
+
+  Writable.prototype.uncork = function () {
+    const state = this._writableState;
+    if (state.corked) {
+      state.corked--;
+      if (!state.writing) clearBuffer(this, state);
+    }
+  };
+
+  Writable.prototype.setDefaultEncoding = function (encoding) {
+    this._writableState.defaultEncoding = binding.normalize(encoding);
+    return this;
+  };
+
+  Writable.prototype.end = function (chunk, encoding, callback) {
+    const state = this._writableState;
+    if (typeof chunk === "function") {
+      callback = chunk;
+      chunk = null;
+      encoding = null;
+    } else if (typeof encoding === "function") {
+      callback = encoding;
+      encoding = null;
+    }
+    let error;
+    if (chunk !== null && chunk !== undefined) {
+      const result = _write(this, chunk, encoding);
+      if (result instanceof Error) error = result;
+    }
+    if (state.corked) {
+      state.corked = 1;
+      this.uncork();
+    }
+    if (error) {
+      // Reported by `write`.
+    } else if (!state.errored && !state.ending) {
+      state.ending = true;
+      finishMaybe(this, state, true);
+      state.ended = true;
+    } else if (state.finished) {
+      error = codeError(
+        Error,
+        "ERR_STREAM_ALREADY_FINISHED",
+        "Cannot call end after a stream was finished"
+      );
+    } else if (state.destroyed) {
+      error = errors.destroyed("end");
+    }
+    if (typeof callback === "function") {
+      if (error || state.finished) {
+        nextTick(callback, error);
+      } else {
+        state[kOnFinished].push(callback);
+      }
+    }
+    return this;
+  };
+
+  function needFinish(state) {
+    return (
+      state.ending &&
+      !state.destroyed &&
+      state.constructed &&
+      state.length === 0 &&
+      !state.errored &&
+      state.buffered.length === 0 &&
+      !state.finished &&
+      !state.writing &&
+      !state.errorEmitted &&
+      !state.closeEmitted
+    );
+  }
+
+  function callFinal(stream, state) {
+    let called = false;
+    function onFinish(error) {
+      if (called) {
+        errorOrDestroy(stream, error ?? errors.multipleCallback());
+        return;
+      }
+      called = true;
+      state.pendingcb--;
+      if (error) {
+        for (const callback of state[kOnFinished].splice(0)) callback(error);
+        errorOrDestroy(stream, error, state.sync);
+      } else if (needFinish(state)) {
+        state.prefinished = true;
+        stream.emit("prefinish");
+        state.pendingcb++;
+        nextTick(finish, stream, state);
+      }
+    }
+    state.sync = true;
+    state.pendingcb++;
+    try {
+      stream._final(onFinish);
+    } catch (error) {
+      onFinish(error);
+    }
+    state.sync = false;
+  }
+
+  function prefinish(stream, state) {
+    if (!state.prefinished && !state.finalCalled) {
+      if (typeof stream._final === "function" && !state.destroyed) {
+        state.finalCalled = true;
+        callFinal(stream, state);
+      } else {
+        state.prefinished = true;
+        stream.emit("prefinish");
+      }
+    }
+  }
+
+  function finishMaybe(stream, state, sync) {
+    if (needFinish(state)) {
+      prefinish(stream, state);
+      if (state.pendingcb === 0) {
+        if (sync) {
+          state.pendingcb++;
+          nextTick(() => {
+            if (needFinish(state)) {
+              finish(stream, state);
+            } else {
+              state.pendingcb--;
+            }
+          });
+        } else if (needFinish(state)) {
+          state.pendingcb++;
+          finish(stream, state);
+        }
+      }
+    }
+  }
+
+  function finish(stream, state) {
+    state.pendingcb--;
+    state.finished = true;
+    for (const callback of state[kOnFinished].splice(0)) callback(null);
+    stream.emit("finish");
+    if (state.autoDestroy) {
+      // A Duplex is destroyed once both sides are done.
+      const r = stream._readableState;
+      if (!r || (r.autoDestroy && (r.endEmitted || r.readable === false))) {
+        stream.destroy();
+      }
+    }
+  }
+
+  Writable.prototype.destroy = function (error, callback) {
+    const state = this._writableState;
+    if (
+      !state.destroyed &&
+      (state.bufferedIndex < state.buffered.length || state[kOnFinished].length)
+    ) {
+      nextTick(errorBuffer, state);
+    }
+    destroy.call(this, error, callback);
+    return this;
+  };
+
+  Writable.prototype._destroy = function (error, callback) {
+    callback(error);
+  };
+
+  defineGetters(Writable.prototype, {
+    writable: {
+      get() {
+        const w = this._writableState;
+        return (
+          Boolean(w) &&
+          w.writable !== false &&
+          !w.destroyed &&
+          !w.errored &&
+          !w.ending &&
+          !w.ended
+        );
+      },
+      set(value) {
+        if (this._writableState) this._writableState.writable = Boolean(value);
+      },
+    },
+    writableFinished() {
+      return this._writableState?.finished ?? false;
+    },
+    writableObjectMode() {
+      return this._writableState?.objectMode ?? false;
+    },
+    writableBuffer() {
+      const state = this._writableState;
+      return state.buffered.slice(state.bufferedIndex);
+    },
+    writableEnded() {
+      return this._writableState?.ending ?? false;
+    },
+    writableNeedDrain() {
+      const state = this._writableState;
+      return Boolean(state) && !state.destroyed && !state.ending && state.needDrain;
+    },
+    writableHighWaterMark() {
+      return this._writableState?.highWaterMark;
+    },
+    writableCorked() {
+      return this._writableState?.corked ?? 0;
+    },
+    writableLength() {
+      return this._writableState?.length;
+    },
+    writableAborted() {
+      const w = this._writableState;
+      return Boolean(w.writable !== false && (w.destroyed || w.errored) && !w.finished);
+    },
+    errored() {
+      return this._writableState?.errored ?? null;
+    },
+    closed() {
+      return this._writableState?.closed ?? false;
+    },
+    destroyed: {
+      get() {
+        return this._writableState?.destroyed ?? false;
+      },
+      set(value) {
+        if (this._writableState) this._writableState.destroyed = value;
+      },
+    },
+  });
+
+  // Duplex
+
+  function Duplex(options) {
+    if (!(this instanceof Duplex)) return new Duplex(options);
+    Readable.call(this, options);
+    Writable.call(this, options);
+    this.allowHalfOpen = options?.allowHalfOpen !== false;
+    if (options?.readable === false) {
+      this._readableState.readable = false;
+      this._readableState.ended = true;
+      this._readableState.endEmitted = true;
+    }
+    if (options?.writable === false) {
+      this._writableState.writable = false;
+      this._writableState.ending = true;
+      this._writableState.ended = true;
+      this._writableState.finished = true;
+    }
+  }
+  Object.setPrototypeOf(Duplex.prototype, Readable.prototype);
+  Object.setPrototypeOf(Duplex, Readable);
+
+  // The writable side, without overriding the readable one.
+  for (const name of Object.getOwnPropertyNames(Writable.prototype)) {
+    if (!(name in Duplex.prototype)) {
+      const descriptor = Object.getOwnPropertyDescriptor(Writable.prototype, name);
+      Object.defineProperty(Duplex.prototype, name, descriptor);
+    }
+  }
+
+  Duplex.prototype.destroy = Writable.prototype.destroy;
+
+  defineGetters(Duplex.prototype, {
+    destroyed: {
+      get() {
+        const r = this._readableState;
+        const w = this._writableState;
+        return r !== undefined && w !== undefined && r.destroyed && w.destroyed;
+      },
+      set(value) {
+        if (this._readableState && this._writableState) {
+          this._readableState.destroyed = value;
+          this._writableState.destroyed = value;
+        }
+      },
+    },
+  });
+
+  // Transform and PassThrough
+
+  function Transform(options) {
+    if (!(this instanceof Transform)) return new Transform(options);
+    Duplex.call(this, options);
+    // The transformed chunks are pushed asynchronously, emit them right away.
+    this._readableState.sync = false;
+    this[kCallback] = null;
+    if (options) {
+      if (typeof options.transform === "function") this._transform = options.transform;
+      if (typeof options.flush === "function") this._flush = options.flush;
+    }
+    this.on("prefinish", transformPrefinish);
+  }
+  Object.setPrototypeOf(Transform.prototype, Duplex.prototype);
+  Object.setPrototypeOf(Transform, Duplex);
+
+  function transformFinal(callback) {
+    if (typeof this._flush === "function" && !this.destroyed) {
+      this._flush((error, data) => {
+        if (error) {
+          if (callback) {
+            callback(error);
+          } else {
+            this.destroy(error);
+          }
+          return;
+        }
+        if (data !== null && data !== undefined) this.push(data);
+        this.push(null);
+        if (callback) callback();
+      });
+    } else {
+      this.push(null);
+      if (callback) callback();
+    }
+  }
+
+  function transformPrefinish() {
+    if (this._final !== transformFinal) transformFinal.call(this);
+  }
+
+  Transform.prototype._final = transformFinal;
+
+  Transform.prototype._transform = function () {
+    throw errors.notImplemented("_transform()");
+  };
+
+  Transform.prototype._write = function (chunk, encoding, callback) {
+    const r = this._readableState;
+    const w = this._writableState;
+    const length = r.length;
+    this._transform(chunk, encoding, (error, value) => {
+      if (error) {
+        callback(error);
+        return;
+      }
+      if (value !== null && value !== undefined) this.push(value);
+      if (w.ended || length === r.length || r.length < r.highWaterMark) {
+        callback();
+      } else {
+        // Wait for the readable side to be consumed.
+        this[kCallback] = callback;
+      }
+    });
+  };
+
+  Transform.prototype._read = function () {
+    if (this[kCallback]) {
+      const callback = this[kCallback];
+      this[kCallback] = null;
+      callback();
+    }
+  };
+
+  function PassThrough(options) {
+    if (!(this instanceof PassThrough)) return new PassThrough(options);
+    Transform.call(this, options);
+  }
+  Object.setPrototypeOf(PassThrough.prototype, Transform.prototype);
+  Object.setPrototypeOf(PassThrough, Transform);
+
+  PassThrough.prototype._transform = function (chunk, encoding, callback) {
+    callback(null, chunk);
+  };
+
+  // finished and pipeline
+
+  function isReadableStream(stream) {
+    return Boolean(
+      stream &&
+        typeof stream.pipe === "function" &&
+        typeof stream.on === "function" &&
+        (!stream._writableState ||
+          (stream._readableState && stream._readableState.readable !== false))
+    );
+  }
+
+  function isWritableStream(stream) {
+    return Boolean(
+      stream &&
+        typeof stream.write === "function" &&
+        typeof stream.on === "function" &&
+        (!stream._readableState ||
+          (stream._writableState && stream._writableState.writable !== false))
+    );
+  }
+
+  function isStream(stream) {
+    return Boolean(
+      stream &&
+        (stream._readableState ||
+          stream._writableState ||
+          (typeof stream.write === "function" && typeof stream.on === "function") ||
+          (typeof stream.pipe === "function" && typeof stream.on === "function"))
+    );
+  }
+
+  function isIterable(value) {
+    return Boolean(
+      value &&
+        typeof value !== "string" &&
+        (typeof value[Symbol.asyncIterator] === "function" ||
+          typeof value[Symbol.iterator] === "function")
+    );
+  }
+
+  // Call `callback` once the stream is done: ended, finished, errored or
+  // prematurely closed. Returns a function removing the listeners.
+  function eos(stream, options, callback) {
+    if (typeof options === "function") {
+      callback = options;
+      options = {};
+    }
+    options = options ?? {};
+    if (typeof callback !== "function") {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "callback" argument must be of type function'
+      );
+    }
+    let called = false;
+    const done = (error) => {
+      if (!called) {
+        called = true;
+        callback.call(stream, error);
+      }
+    };
+    const r = stream._readableState;
+    const w = stream._writableState;
+    const readable = options.readable ?? isReadableStream(stream);
+    const writable = options.writable ?? isWritableStream(stream);
+    const state = w || r;
+    let willEmitClose =
+      Boolean(state?.autoDestroy && state.emitClose && state.closed === false) &&
+      isReadableStream(stream) === readable &&
+      isWritableStream(stream) === writable;
+
+    let writableFinished = Boolean(w?.finished);
+    const onfinish = () => {
+      writableFinished = true;
+      if (stream.destroyed) willEmitClose = false;
+      if (willEmitClose && (!stream.readable || readable)) return;
+      if (!readable || readableFinished) done();
+    };
+
+    let readableFinished = Boolean(r?.endEmitted);
+    const onend = () => {
+      readableFinished = true;
+      if (stream.destroyed) willEmitClose = false;
+      if (willEmitClose && (!stream.writable || writable)) return;
+      if (!writable || writableFinished) done();
+    };
+
+    const onerror = (error) => done(error);
+
+    const onclose = () => {
+      const errored = w?.errored || r?.errored;
+      if (errored && typeof errored !== "boolean") {
+        done(errored);
+        return;
+      }
+      if (readable && !readableFinished && isReadableStream(stream) && !r?.endEmitted) {
+        done(errors.prematureClose());
+        return;
+      }
+      if (writable && !writableFinished && !w?.finished) {
+        done(errors.prematureClose());
+        return;
+      }
+      done();
+    };
+
+    stream.on("end", onend);
+    stream.on("finish", onfinish);
+    if (options.error !== false) stream.on("error", onerror);
+    stream.on("close", onclose);
+
+    const closed = Boolean(w?.closed || r?.closed);
+    if (closed) {
+      nextTick(onclose);
+    } else if (w?.errorEmitted || r?.errorEmitted) {
+      if (!willEmitClose) nextTick(onclose);
+    } else if (!readable && (!willEmitClose || stream.readable) && writableFinished) {
+      nextTick(onclose);
+    } else if (!writable && (!willEmitClose || stream.writable) && readableFinished) {
+      nextTick(onclose);
+    }
+
+    const cleanup = () => {
+      callback = nop;
+      stream.removeListener("end", onend);
+      stream.removeListener("finish", onfinish);
+      stream.removeListener("error", onerror);
+      stream.removeListener("close", onclose);
+    };
+
+    const signal = options.signal;
+    if (signal && !called) {
+      const abort = () => {
+        const end = callback;
+        cleanup();
+        end.call(stream, abortError(signal));
+      };
+      if (signal.aborted) {
+        nextTick(abort);
+      } else {
+        signal.addEventListener("abort", abort, { once: true });
+        const end = callback;
+        callback = function (...args) {
+          signal.removeEventListener("abort", abort);
+          end.apply(stream, args);
+        };
+      }
+    }
+    return cleanup;
+  }
+
+  function finished(stream, options, callback) {
+    return eos(stream, options, callback);
+  }
+
+  // Pipe the streams into each other and call `callback` once the last one is
+  // done or one of them failed, the others are then destroyed. The first
+  // item may be an iterable, the next ones functions receiving the previous
+  // source as an async iterable.
+  function pipelineImpl(streams, callback, options) {
+    if (streams.length === 1 && Array.isArray(streams[0])) streams = streams[0];
+    if (streams.length < 2) {
+      throw codeError(
+        TypeError,
+        "ERR_MISSING_ARGS",
+        'The "streams" argument must be specified'
+      );
+    }
+    const signal = options?.signal;
+    const destroys = [];
+    let error;
+    let value;
+    let finishCount = 0;
+
+    function finish(err) {
+      finishImpl(err, --finishCount === 0);
+    }
+
+    function finishImpl(err, final) {
+      if (err && (!error || error.code === "ERR_STREAM_PREMATURE_CLOSE")) {
+        error = err;
+      }
+      if (!error && !final) return;
+      while (destroys.length) destroys.shift()(error);
+      signal?.removeEventListener("abort", abort);
+      if (final) nextTick(callback, error, value);
+    }
+
+    function abort() {
+      finishImpl(abortError(signal), false);
+    }
+    if (signal?.aborted) {
+      nextTick(abort);
+    } else {
+      signal?.addEventListener("abort", abort, { once: true });
+    }
+
+    let previous;
+    let last;
+    for (let index = 0; index < streams.length; index++) {
+      let stream = streams[index];
+      const isLast = index === streams.length - 1;
+      let piped = true;
+      if (typeof stream === "function") {
+        const result = stream(index === 0 ? { signal } : previous, { signal });
+        piped = false;
+        if (isLast && !isIterable(result) && !isStream(result)) {
+          // The last function returns a promise.
+          finishCount++;
+          Promise.resolve(result).then((result) => {
+            value = result;
+            finish();
+          }, finish);
+          last = result;
+          continue;
+        }
+        stream = isStream(result) ? result : Readable.from(result);
+      } else if (!isStream(stream)) {
+        if (index === 0 && isIterable(stream)) {
+          stream = Readable.from(stream);
+          piped = false;
+        } else {
+          throw codeError(
+            TypeError,
+            "ERR_INVALID_ARG_TYPE",
+            `The "streams[${index}]" argument must be of type Stream, Iterable, AsyncIterable or Function`
+          );
+        }
+      }
+      finishCount++;
+      const reading = !isLast && isReadableStream(stream);
+      const writing = index > 0 && isWritableStream(stream);
+      let streamFinished = false;
+      eos(stream, { readable: reading, writable: writing }, (err) => {
+        streamFinished = !err;
+        finish(err);
+      });
+      destroys.push((err) => {
+        if (!streamFinished) {
+          streamFinished = true;
+          stream.destroy(err || errors.destroyed("pipe"));
+        }
+      });
+      if (previous && piped) {
+        previous.pipe(stream, { end: options?.end !== false || !isLast });
+      }
+      previous = stream;
+      last = stream;
+    }
+    return last;
+  }
+
+  function popCallback(streams) {
+    const callback = streams.pop();
+    if (typeof callback !== "function") {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "streams[stream.length - 1]" argument must be of type function'
+      );
+    }
+    return callback;
+  }
+
+  function pipeline(...streams) {
+    const callback = popCallback(streams);
+    return pipelineImpl(streams, callback);
+  }
+
+  function isOptions(value) {
+    return Boolean(
+      value &&
+        typeof value === "object" &&
+        !isStream(value) &&
+        !isIterable(value)
+    );
+  }
+
+  const promises = {
+    pipeline(...streams) {
+      const options = isOptions(streams[streams.length - 1]) ? streams.pop() : undefined;
+      return new Promise((resolve, reject) => {
+        pipelineImpl(
+          streams,
+          (error, value) => (error ? reject(error) : resolve(value)),
+          options
+        );
+      });
+    },
+    finished(stream, options) {
+      return new Promise((resolve, reject) => {
+        eos(stream, options, (error) => (error ? reject(error) : resolve()));
+      });
+    },
+  };
+
+  // Conversions to and from the WHATWG streams.
+
+  // The premature close of a node stream aborts its web stream.
+  function webError(error) {
+    if (error?.code !== "ERR_STREAM_PREMATURE_CLOSE") return error;
+    const abort = abortError();
+    abort.cause = error;
+    return abort;
+  }
+
+  Readable.toWeb = function (streamReadable, options) {
+    if (!isReadableStream(streamReadable)) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "streamReadable" argument must be an instance of Readable'
+      );
+    }
+    const highWaterMark = streamReadable.readableHighWaterMark;
+    const strategy =
+      options?.strategy ??
+      (streamReadable.readableObjectMode
+        ? new web.CountQueuingStrategy({ highWaterMark })
+        : { highWaterMark, size: (chunk) => chunk.length });
+    let controller;
+    function onData(chunk) {
+      controller.enqueue(chunk);
+      if (controller.desiredSize <= 0) streamReadable.pause();
+    }
+    const cleanup = eos(streamReadable, (error) => {
+      cleanup();
+      streamReadable.removeListener("data", onData);
+      error = webError(error);
+      if (error) {
+        controller.error(error);
+      } else {
+        controller.close();
+      }
+    });
+    streamReadable.pause();
+    streamReadable.on("data", onData);
+    return new web.ReadableStream(
+      {
+        start(c) {
+          controller = c;
+        },
+        pull() {
+          streamReadable.resume();
+        },
+        cancel(reason) {
+          streamReadable.destroy(reason);
+        },
+      },
+      strategy
+    );
+  };
+
+  Readable.fromWeb = function (readableStream, options = {}) {
+    if (!(readableStream instanceof web.ReadableStream)) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "readableStream" argument must be an instance of ReadableStream'
+      );
+    }
+    const { highWaterMark, encoding, objectMode = false, signal } = options;
+    const reader = readableStream.getReader();
+    let closed = false;
+    const readable = new Readable({
+      objectMode,
+      highWaterMark,
+      encoding,
+      signal,
+      read() {
+        reader.read().then(
+          ({ value, done }) => readable.push(done ? null : value),
+          (error) => readable.destroy(error)
+        );
+      },
+      destroy(error, callback) {
+        if (closed) {
+          callback(error);
+          return;
+        }
+        const done = () => callback(error);
+        reader.cancel(error).then(done, done);
+      },
+    });
+    reader.closed.then(
+      () => {
+        closed = true;
+      },
+      (error) => {
+        closed = true;
+        readable.destroy(error);
+      }
+    );
+    return readable;
+  };
+
+  Writable.toWeb = function (streamWritable) {
+    if (!isWritableStream(streamWritable)) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "streamWritable" argument must be an instance of Writable'
+      );
+    }
+    const highWaterMark = streamWritable.writableHighWaterMark;
+    const strategy = streamWritable.writableObjectMode
+      ? new web.CountQueuingStrategy({ highWaterMark })
+      : { highWaterMark };
+    let controller;
+    // Settled on 'drain' while the stream has too much data, and once the
+    // stream is finished after `close()`.
+    let backpressure;
+    let closed;
+    function onDrain() {
+      backpressure?.resolve();
+    }
+    const cleanup = eos(streamWritable, (error) => {
+      cleanup();
+      streamWritable.removeListener("drain", onDrain);
+      // The errors are given to the web stream.
+      streamWritable.on("error", nop);
+      error = webError(error);
+      if (error) {
+        backpressure?.reject(error);
+        closed?.reject(error);
+        controller.error(error);
+      } else if (closed) {
+        closed.resolve();
+      } else {
+        controller.error(abortError());
+      }
+    });
+    streamWritable.on("drain", onDrain);
+    return new web.WritableStream(
+      {
+        start(c) {
+          controller = c;
+        },
+        write(chunk) {
+          if (streamWritable.writableNeedDrain || !streamWritable.write(chunk)) {
+            return new Promise((resolve, reject) => {
+              backpressure = { resolve, reject };
+            }).finally(() => {
+              backpressure = undefined;
+            });
+          }
+        },
+        abort(reason) {
+          streamWritable.destroy(reason);
+        },
+        close() {
+          if (closed || streamWritable.writableEnded) return;
+          return new Promise((resolve, reject) => {
+            closed = { resolve, reject };
+            streamWritable.end();
+          });
+        },
+      },
+      strategy
+    );
+  };
+
+  Writable.fromWeb = function (writableStream, options = {}) {
+    if (!(writableStream instanceof web.WritableStream)) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "writableStream" argument must be an instance of WritableStream'
+      );
+    }
+    const { highWaterMark, decodeStrings = true, objectMode = false, signal } = options;
+    const writer = writableStream.getWriter();
+    let closed = false;
+    const writable = new Writable({
+      highWaterMark,
+      objectMode,
+      decodeStrings,
+      signal,
+      write(chunk, encoding, callback) {
+        writer.ready.then(() => writer.write(chunk)).then(() => callback(), callback);
+      },
+      writev(chunks, callback) {
+        writer.ready
+          .then(() => Promise.all(chunks.map(({ chunk }) => writer.write(chunk))))
+          .then(() => callback(), callback);
+      },
+      final(callback) {
+        if (closed) {
+          callback();
+          return;
+        }
+        writer.close().then(() => callback(), callback);
+      },
+      destroy(error, callback) {
+        if (closed) {
+          callback(error);
+          return;
+        }
+        const done = () => callback(error);
+        (error ? writer.abort(error) : writer.close()).then(done, done);
+      },
+    });
+    writer.closed.then(
+      () => {
+        closed = true;
+        if (!writable.writableEnded) writable.destroy(errors.prematureClose());
+      },
+      (error) => {
+        closed = true;
+        writable.destroy(error);
+      }
+    );
+    return writable;
+  };
+
+  Object.assign(Stream, {
+    Stream,
+    Readable,
+    Writable,
+    Duplex,
+    Transform,
+    PassThrough,
+    pipeline,
+    finished,
+    addAbortSignal,
+    promises,
+    getDefaultHighWaterMark(objectMode) {
+      return objectMode ? defaultObjectHighWaterMark : defaultHighWaterMark;
+    },
+    setDefaultHighWaterMark(objectMode, value) {
+      if (!Number.isInteger(value) || value < 0) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_VALUE",
+          `The argument 'value' is invalid. Received ${value}`
+        );
+      }
+      if (!objectMode) defaultHighWaterMark = value;
+    },
+    isReadable(stream) {
+      return isReadableStream(stream) && stream.readable;
+    },
+    isErrored(stream) {
+      return Boolean(stream?._readableState?.errored || stream?._writableState?.errored);
+    },
+    isDisturbed(stream) {
+      return Boolean(stream?._readableState?.dataEmitted || stream?._readableState?.destroyed);
+    },
+  });
+
+  return Stream;
+});
+//# sourceURL=node:internal/stream
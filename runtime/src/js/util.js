@@ -0,0 +1,689 @@
+// The functions of `node:util` written in javascript: promisify,
+// callbackify, inherits, deprecate, isDeepStrictEqual and parseArgs.
+// Evaluated once by util.rs, the script is a function called with the native
+// module, whose `types` are used by the comparisons, the shared helpers of
+// internals.rs and the arguments given to the script, parsed by default by
+// parseArgs.
+(function (util, internals, argv) {
+  "use strict";
+
+  const { types } = util;
+  const { codeError, nextTick, warn } = internals;
+
+  function validateFunction(value, name) {
+    if (typeof value !== "function") {
+      const subject = name.endsWith(" argument") ? name : `"${name}" argument`;
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        `The ${subject} must be of type function`
+      );
+    }
+  }
+
+  // Copy the own properties of `original` to `fn`, with the descriptors
+  // given by `update`.
+  function copyProperties(fn, original, update = () => {}) {
+    const descriptors = Object.getOwnPropertyDescriptors(original);
+    update(descriptors);
+    for (const descriptor of Object.values(descriptors)) {
+      Object.setPrototypeOf(descriptor, null);
+    }
+    return Object.defineProperties(fn, descriptors);
+  }
+
+  // promisify and callbackify.
+
+  const kCustomPromisified = Symbol.for("nodejs.util.promisify.custom");
+
+  function promisify(original) {
+    validateFunction(original, "original");
+    if (original[kCustomPromisified]) {
+      const fn = original[kCustomPromisified];
+      validateFunction(fn, "util.promisify.custom");
+      return Object.defineProperty(fn, kCustomPromisified, {
+        value: fn,
+        enumerable: false,
+        writable: false,
+        configurable: true,
+      });
+    }
+
+    function fn(...args) {
+      return new Promise((resolve, reject) => {
+        args.push((error, value) => {
+          if (error) reject(error);
+          else resolve(value);
+        });
+        Reflect.apply(original, this, args);
+      });
+    }
+
+    Object.setPrototypeOf(fn, Object.getPrototypeOf(original));
+    Object.defineProperty(fn, kCustomPromisified, {
+      value: fn,
+      enumerable: false,
+      writable: false,
+      configurable: true,
+    });
+    return copyProperties(fn, original);
+  }
+
+  promisify.custom = kCustomPromisified;
+
+  function callbackifyOnRejected(reason, callback) {
+    // A falsy reason would be taken for a success.
+    if (!reason) {
+      const error = codeError(
+        Error,
+        "ERR_FALSY_VALUE_REJECTION",
+        "Promise was rejected with falsy value"
+      );
+      error.reason = reason;
+      reason = error;
+    }
+    return callback(reason);
+  }
+
+  function callbackify(original) {
+    validateFunction(original, "original");
+
+    function callbackified(...args) {
+      const maybeCallback = args.pop();
+      validateFunction(maybeCallback, "last argument");
+      const callback = maybeCallback.bind(this);
+      Reflect.apply(original, this, args).then(
+        (value) => nextTick(callback, null, value),
+        (reason) => nextTick(callbackifyOnRejected, reason, callback)
+      );
+    }
+
+    return copyProperties(callbackified, original, (descriptors) => {
+      if (typeof descriptors.length.value === "number") descriptors.length.value++;
+      if (typeof descriptors.name.value === "string") descriptors.name.value += "Callbackified";
+    });
+  }
+
+  // inherits and deprecate.
+
+  function inherits(ctor, superCtor) {
+    if (ctor === undefined || ctor === null) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "ctor" argument must be of type function'
+      );
+    }
+    if (superCtor === undefined || superCtor === null) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "superCtor" argument must be of type function'
+      );
+    }
+    if (superCtor.prototype === undefined) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "superCtor.prototype" property must be of type object'
+      );
+    }
+    Object.defineProperty(ctor, "super_", {
+      value: superCtor,
+      writable: true,
+      configurable: true,
+    });
+    Object.setPrototypeOf(ctor.prototype, superCtor.prototype);
+  }
+
+  // The codes already warned, a code is only warned once.
+  const codesWarned = new Set();
+
+  function deprecate(fn, message, code) {
+    if (code !== undefined && typeof code !== "string") {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "code" argument must be of type string'
+      );
+    }
+    let warned = false;
+
+    function deprecated(...args) {
+      if (!warned) {
+        warned = true;
+        if (code === undefined) {
+          warn("DeprecationWarning", message);
+        } else if (!codesWarned.has(code)) {
+          codesWarned.add(code);
+          warn(`[${code}] DeprecationWarning`, message);
+        }
+      }
+      if (new.target) return Reflect.construct(fn, args, new.target);
+      return Reflect.apply(fn, this, args);
+    }
+
+    // The wrapper keeps the prototype chain of fn.
+    Object.setPrototypeOf(deprecated, fn);
+    if (fn.prototype) deprecated.prototype = fn.prototype;
+    return deprecated;
+  }
+
+  // isDeepStrictEqual, the comparison of assert.deepStrictEqual: same
+  // prototypes, same type, same own enumerable properties (symbols too) and
+  // equal contents for the builtins, compared recursively. The cycles are
+  // equal if they loop at the same depth.
+
+  const kNoIterator = 0;
+  const kIsArray = 1;
+  const kIsSet = 2;
+  const kIsMap = 3;
+
+  const hasOwn = (object, key) => Object.prototype.hasOwnProperty.call(object, key);
+  const isEnumerable = (object, key) => Object.prototype.propertyIsEnumerable.call(object, key);
+  const tagOf = (value) => Object.prototype.toString.call(value);
+
+  function isArrayIndex(key) {
+    if (typeof key !== "string") return false;
+    const index = Number(key);
+    return String(index >>> 0) === key && index !== 2 ** 32 - 1;
+  }
+
+  // The own enumerable keys which aren't indexes, symbols included.
+  function nonIndexKeys(value) {
+    return Reflect.ownKeys(value).filter((key) => !isArrayIndex(key) && isEnumerable(value, key));
+  }
+
+  function isError(value) {
+    return types.isNativeError(value) || value instanceof Error;
+  }
+
+  function bytesOf(view) {
+    return new Uint8Array(view.buffer ?? view, view.byteOffset ?? 0, view.byteLength);
+  }
+
+  function equalBytes(a, b) {
+    if (a.byteLength !== b.byteLength) return false;
+    const bytesA = bytesOf(a);
+    const bytesB = bytesOf(b);
+    return bytesA.every((byte, index) => byte === bytesB[index]);
+  }
+
+  // The float arrays compare their elements: NaN differs from itself and 0
+  // equals -0.
+  function equalFloats(a, b) {
+    if (a.byteLength !== b.byteLength) return false;
+    return a.every((element, index) => element === b[index]);
+  }
+
+  function equalBoxedPrimitives(a, b) {
+    for (const [check, Type] of [
+      [types.isNumberObject, Number],
+      [types.isStringObject, String],
+      [types.isBooleanObject, Boolean],
+      [types.isBigIntObject, BigInt],
+      [types.isSymbolObject, Symbol],
+    ]) {
+      if (check(a)) {
+        return check(b) && Object.is(Type.prototype.valueOf.call(a), Type.prototype.valueOf.call(b));
+      }
+    }
+    return false;
+  }
+
+  function innerDeepEqual(a, b, memos) {
+    if (a === b) return a !== 0 || Object.is(a, b);
+    if (typeof a !== "object") {
+      return typeof a === "number" && Number.isNaN(a) && Number.isNaN(b);
+    }
+    if (typeof b !== "object" || a === null || b === null) return false;
+    if (Object.getPrototypeOf(a) !== Object.getPrototypeOf(b)) return false;
+    const tag = tagOf(a);
+    if (tag !== tagOf(b)) return false;
+
+    if (Array.isArray(a)) {
+      if (!Array.isArray(b) || a.length !== b.length) return false;
+      const keysA = nonIndexKeys(a);
+      if (keysA.length !== nonIndexKeys(b).length) return false;
+      return keyCheck(a, b, memos, kIsArray, keysA);
+    }
+    if (tag === "[object Object]") {
+      return keyCheck(a, b, memos, kNoIterator);
+    }
+    if (types.isDate(a)) {
+      if (!types.isDate(b) || a.getTime() !== b.getTime()) return false;
+    } else if (types.isRegExp(a)) {
+      if (
+        !types.isRegExp(b) ||
+        a.source !== b.source ||
+        a.flags !== b.flags ||
+        a.lastIndex !== b.lastIndex
+      ) {
+        return false;
+      }
+    } else if (isError(a)) {
+      if (!isError(b) || a.message !== b.message || a.name !== b.name) return false;
+    } else if (ArrayBuffer.isView(a)) {
+      const floats = types.isFloat32Array(a) || types.isFloat64Array(a);
+      if (floats ? !equalFloats(a, b) : !equalBytes(a, b)) return false;
+      const keysA = nonIndexKeys(a);
+      if (keysA.length !== nonIndexKeys(b).length) return false;
+      return keyCheck(a, b, memos, kNoIterator, keysA);
+    } else if (types.isSet(a)) {
+      if (!types.isSet(b) || a.size !== b.size) return false;
+      return keyCheck(a, b, memos, kIsSet);
+    } else if (types.isMap(a)) {
+      if (!types.isMap(b) || a.size !== b.size) return false;
+      return keyCheck(a, b, memos, kIsMap);
+    } else if (types.isAnyArrayBuffer(a)) {
+      if (!types.isAnyArrayBuffer(b) || !equalBytes(a, b)) return false;
+    } else if (types.isBoxedPrimitive(a)) {
+      if (!equalBoxedPrimitives(a, b)) return false;
+    } else if (
+      Array.isArray(b) ||
+      ArrayBuffer.isView(b) ||
+      types.isSet(b) ||
+      types.isMap(b) ||
+      types.isDate(b) ||
+      types.isRegExp(b) ||
+      types.isAnyArrayBuffer(b) ||
+      types.isBoxedPrimitive(b) ||
+      isError(b)
+    ) {
+      return false;
+    }
+    return keyCheck(a, b, memos, kNoIterator);
+  }
+
+  function keyCheck(a, b, memos, iterationType, keysA) {
+    if (keysA === undefined) {
+      keysA = Object.keys(a);
+      if (keysA.length !== Object.keys(b).length) return false;
+      if (!keysA.every((key) => hasOwn(b, key))) return false;
+      // The enumerable symbols.
+      const symbolsA = Object.getOwnPropertySymbols(a).filter((key) => isEnumerable(a, key));
+      if (!symbolsA.every((key) => isEnumerable(b, key))) return false;
+      const symbolsB = Object.getOwnPropertySymbols(b).filter((key) => isEnumerable(b, key));
+      if (symbolsA.length !== symbolsB.length) return false;
+      keysA.push(...symbolsA);
+    }
+    if (
+      keysA.length === 0 &&
+      (iterationType === kNoIterator ||
+        (iterationType === kIsArray && a.length === 0) ||
+        a.size === 0)
+    ) {
+      return true;
+    }
+
+    // The objects being compared, with their depth.
+    if (memos === undefined) {
+      memos = { a: new Map(), b: new Map(), position: 0 };
+    } else {
+      const memoA = memos.a.get(a);
+      if (memoA !== undefined) {
+        const memoB = memos.b.get(b);
+        if (memoB !== undefined) return memoA === memoB;
+      }
+      memos.position++;
+    }
+    memos.a.set(a, memos.position);
+    memos.b.set(b, memos.position);
+    const equal = objectEquiv(a, b, keysA, memos, iterationType);
+    memos.a.delete(a);
+    memos.b.delete(b);
+    return equal;
+  }
+
+  function objectEquiv(a, b, keys, memos, iterationType) {
+    if (iterationType === kIsSet) {
+      if (!setEquiv(a, b, memos)) return false;
+    } else if (iterationType === kIsMap) {
+      if (!mapEquiv(a, b, memos)) return false;
+    } else if (iterationType === kIsArray) {
+      for (let index = 0; index < a.length; index++) {
+        if (hasOwn(a, index)) {
+          if (!hasOwn(b, index) || !innerDeepEqual(a[index], b[index], memos)) return false;
+        } else if (hasOwn(b, index)) {
+          return false;
+        } else {
+          // A hole: the remaining keys are compared as the ones of objects.
+          const keysA = Object.keys(a);
+          for (; index < keysA.length; index++) {
+            const key = keysA[index];
+            if (!hasOwn(b, key) || !innerDeepEqual(a[key], b[key], memos)) return false;
+          }
+          return keysA.length === Object.keys(b).length;
+        }
+      }
+    }
+    return keys.every((key) => innerDeepEqual(a[key], b[key], memos));
+  }
+
+  // The primitives are found with `has`, the objects are compared with each
+  // of the remaining ones.
+  function setEquiv(a, b, memos) {
+    let objects = null;
+    for (const value of a) {
+      if (typeof value === "object" && value !== null) {
+        objects ??= new Set();
+        objects.add(value);
+      } else if (!b.has(value)) {
+        return false;
+      }
+    }
+    if (objects === null) return true;
+    for (const value of b) {
+      if (typeof value === "object" && value !== null) {
+        const found = [...objects].find((object) => innerDeepEqual(value, object, memos));
+        if (found === undefined) return false;
+        objects.delete(found);
+      }
+    }
+    return objects.size === 0;
+  }
+
+  function mapEquiv(a, b, memos) {
+    let objects = null;
+    for (const [key, value] of a) {
+      if (typeof key === "object" && key !== null) {
+        objects ??= new Set();
+        objects.add(key);
+      } else if (!b.has(key) || !innerDeepEqual(value, b.get(key), memos)) {
+        return false;
+      }
+    }
+    if (objects === null) return true;
+    for (const [key, value] of b) {
+      if (typeof key === "object" && key !== null) {
+        const found = [...objects].find(
+          (object) =>
+            innerDeepEqual(key, object, memos) && innerDeepEqual(value, a.get(object), memos)
+        );
+        if (found === undefined) return false;
+        objects.delete(found);
+      }
+    }
+    return objects.size === 0;
+  }
+
+  function isDeepStrictEqual(a, b) {
+    return innerDeepEqual(a, b);
+  }
+
+  // parseArgs: the arguments are split in tokens (options, positionals and
+  // the `--` terminator), checked in strict mode, then stored in `values`
+  // and `positionals`, with the defaults of the missing options.
+
+  // The "argument" of parseArgs, or the "property" of its options.
+  function invalidArgType(name, expected) {
+    const subject = name.includes(".") ? `"${name}" property` : `"${name}" argument`;
+    return codeError(TypeError, "ERR_INVALID_ARG_TYPE", `The ${subject} must be ${expected}`);
+  }
+
+  function validateType(value, name, type) {
+    if (typeof value !== type) throw invalidArgType(name, `of type ${type}`);
+  }
+
+  function validateArrayOf(value, name, type) {
+    if (!Array.isArray(value)) throw invalidArgType(name, "an instance of Array");
+    value.forEach((element, index) => validateType(element, `${name}[${index}]`, type));
+  }
+
+  function validateObject(value, name) {
+    if (value === null || typeof value !== "object" || Array.isArray(value)) {
+      throw invalidArgType(name, "of type object");
+    }
+  }
+
+  const getOwn = (object, key) => (hasOwn(object, key) ? object[key] : undefined);
+
+  // A property of the configuration of an option.
+  const optionGetOwn = (options, name, key) =>
+    hasOwn(options, name) ? getOwn(options[name], key) : undefined;
+
+  // The long name of a short option, itself if it's unknown.
+  function findLongOption(short, options) {
+    const entry = Object.entries(options).find(
+      ([, option]) => getOwn(option, "short") === short
+    );
+    return entry?.[0] ?? short;
+  }
+
+  const isOptionLikeValue = (value) => value != null && value.length > 1 && value[0] === "-";
+  const isLoneShortOption = (arg) => arg.length === 2 && arg[0] === "-" && arg[1] !== "-";
+  const isLongOption = (arg) => arg.length > 2 && arg.startsWith("--");
+
+  // `-abc`, `-fFILE` when f takes a string.
+  const isShortOptions = (arg) => arg.length > 2 && arg[0] === "-" && arg[1] !== "-";
+
+  const isStringOption = (options, short) =>
+    optionGetOwn(options, findLongOption(short, options), "type") === "string";
+
+  function argsToTokens(args, options) {
+    const tokens = [];
+    let index = -1;
+    // The arguments of an expanded group share its index.
+    let groupCount = 0;
+    const remaining = args.slice();
+    while (remaining.length > 0) {
+      const arg = remaining.shift();
+      const next = remaining[0];
+      if (groupCount > 0) groupCount--;
+      else index++;
+
+      if (arg === "--") {
+        tokens.push({ kind: "option-terminator", index });
+        for (const value of remaining) tokens.push({ kind: "positional", index: ++index, value });
+        break;
+      }
+
+      if (isLoneShortOption(arg) || (isLongOption(arg) && !arg.includes("=", 3))) {
+        const name = arg.length === 2 ? findLongOption(arg[1], options) : arg.slice(2);
+        let value;
+        let inlineValue;
+        if (optionGetOwn(options, name, "type") === "string" && next != null) {
+          value = remaining.shift();
+          inlineValue = false;
+        }
+        tokens.push({ kind: "option", name, rawName: arg, index, value, inlineValue });
+        if (value != null) index++;
+        continue;
+      }
+
+      if (isShortOptions(arg) && !isStringOption(options, arg[1])) {
+        // `-abc` is `-a -b -c`, and `-abfFILE` is `-a -b -fFILE`.
+        const expanded = [];
+        for (let position = 1; position < arg.length; position++) {
+          const short = arg[position];
+          if (!isStringOption(options, short) || position === arg.length - 1) {
+            expanded.push(`-${short}`);
+          } else {
+            expanded.push(`-${arg.slice(position)}`);
+            break;
+          }
+        }
+        remaining.unshift(...expanded);
+        groupCount = expanded.length;
+        continue;
+      }
+
+      if (isShortOptions(arg)) {
+        const name = findLongOption(arg[1], options);
+        const rawName = `-${arg[1]}`;
+        const value = arg.slice(2);
+        tokens.push({ kind: "option", name, rawName, index, value, inlineValue: true });
+        continue;
+      }
+
+      if (isLongOption(arg)) {
+        const equal = arg.indexOf("=");
+        const name = arg.slice(2, equal);
+        const value = arg.slice(equal + 1);
+        const rawName = `--${name}`;
+        tokens.push({ kind: "option", name, rawName, index, value, inlineValue: true });
+        continue;
+      }
+
+      tokens.push({ kind: "positional", index, value: arg });
+    }
+    return tokens;
+  }
+
+  function unknownOption(rawName, allowPositionals) {
+    const suggestion = allowPositionals
+      ? ". To specify a positional argument starting with a '-', place it at the end of " +
+        `the command after '--', as in '-- ${JSON.stringify(rawName)}`
+      : "";
+    return codeError(
+      TypeError,
+      "ERR_PARSE_ARGS_UNKNOWN_OPTION",
+      `Unknown option '${rawName}'${suggestion}`
+    );
+  }
+
+  function invalidOptionValue(message) {
+    return codeError(TypeError, "ERR_PARSE_ARGS_INVALID_OPTION_VALUE", message);
+  }
+
+  // The strict checks of an option: known, with a value if it's a string,
+  // without one if it's a boolean.
+  function checkOptionUsage(config, token) {
+    let name = token.name;
+    if (!hasOwn(config.options, name)) {
+      // `--no-name` negates the boolean option `name`.
+      const negated = name.slice(3);
+      if (
+        !config.allowNegative ||
+        !name.startsWith("no-") ||
+        optionGetOwn(config.options, negated, "type") !== "boolean"
+      ) {
+        throw unknownOption(token.rawName, config.allowPositionals);
+      }
+      name = negated;
+    }
+    const short = optionGetOwn(config.options, name, "short");
+    const shortAndLong = `${short ? `-${short}, ` : ""}--${name}`;
+    const type = optionGetOwn(config.options, name, "type");
+    if (type === "string" && typeof token.value !== "string") {
+      throw invalidOptionValue(`Option '${shortAndLong} <value>' argument missing`);
+    }
+    if (type === "boolean" && token.value != null) {
+      throw invalidOptionValue(`Option '${shortAndLong}' does not take an argument`);
+    }
+  }
+
+  // A value starting with a dash is taken for a forgotten value, unless it
+  // is given inline.
+  function checkOptionLikeValue(token) {
+    if (token.inlineValue || !isOptionLikeValue(token.value)) return;
+    const example = token.rawName.startsWith("--")
+      ? `'${token.rawName}=-XYZ'`
+      : `'--${token.name}=-XYZ' or '${token.rawName}-XYZ'`;
+    throw invalidOptionValue(
+      `Option '${token.rawName}' argument is ambiguous.\n` +
+        `Did you forget to specify the option argument for '${token.rawName}'?\n` +
+        `To specify an option argument starting with a dash use ${example}.`
+    );
+  }
+
+  function storeOption(name, value, config, values) {
+    let newValue = value ?? true;
+    if (config.allowNegative && name.startsWith("no-") && value === undefined) {
+      name = name.slice(3);
+      newValue = false;
+    }
+    if (name === "__proto__") return;
+    if (optionGetOwn(config.options, name, "multiple")) {
+      if (values[name]) values[name].push(newValue);
+      else values[name] = [newValue];
+    } else {
+      values[name] = newValue;
+    }
+  }
+
+  function validateOptions(options) {
+    validateObject(options, "options");
+    for (const [name, option] of Object.entries(options)) {
+      validateObject(option, `options.${name}`);
+      const type = getOwn(option, "type");
+      if (type !== "string" && type !== "boolean") {
+        throw invalidArgType(`options.${name}.type`, "('string|boolean')");
+      }
+      if (hasOwn(option, "short")) {
+        validateType(option.short, `options.${name}.short`, "string");
+        if (option.short.length !== 1) {
+          throw codeError(
+            TypeError,
+            "ERR_INVALID_ARG_VALUE",
+            `The property 'options.${name}.short' must be a single character`
+          );
+        }
+      }
+      const multiple = getOwn(option, "multiple");
+      if (hasOwn(option, "multiple")) validateType(multiple, `options.${name}.multiple`, "boolean");
+      const defaultValue = getOwn(option, "default");
+      if (defaultValue !== undefined) {
+        if (multiple) validateArrayOf(defaultValue, `options.${name}.default`, type);
+        else validateType(defaultValue, `options.${name}.default`, type);
+      }
+    }
+  }
+
+  function parseArgs(config = {}) {
+    const args = getOwn(config, "args") ?? argv.slice();
+    const strict = getOwn(config, "strict") ?? true;
+    const allowPositionals = getOwn(config, "allowPositionals") ?? !strict;
+    const returnTokens = getOwn(config, "tokens") ?? false;
+    const allowNegative = getOwn(config, "allowNegative") ?? false;
+    const options = getOwn(config, "options") ?? { __proto__: null };
+
+    if (!Array.isArray(args)) throw invalidArgType("args", "an instance of Array");
+    validateType(strict, "strict", "boolean");
+    validateType(allowPositionals, "allowPositionals", "boolean");
+    validateType(returnTokens, "tokens", "boolean");
+    validateType(allowNegative, "allowNegative", "boolean");
+    validateOptions(options);
+
+    const parseConfig = { options, allowPositionals, allowNegative };
+    const tokens = argsToTokens(args, options);
+    const result = { values: { __proto__: null }, positionals: [] };
+    if (returnTokens) result.tokens = tokens;
+    for (const token of tokens) {
+      if (token.kind === "option") {
+        if (strict) {
+          checkOptionUsage(parseConfig, token);
+          checkOptionLikeValue(token);
+        }
+        storeOption(token.name, token.value, parseConfig, result.values);
+      } else if (token.kind === "positional") {
+        if (!allowPositionals) {
+          throw codeError(
+            TypeError,
+            "ERR_PARSE_ARGS_UNEXPECTED_POSITIONAL",
+            `Unexpected argument '${token.value}'. This command does not take positional arguments`
+          );
+        }
+        result.positionals.push(token.value);
+      }
+    }
+
+    for (const [name, option] of Object.entries(options)) {
+      const defaultValue = getOwn(option, "default");
+      if (defaultValue !== undefined && result.values[name] === undefined && name !== "__proto__") {
+        result.values[name] = defaultValue;
+      }
+    }
+    return result;
+  }
+
+  Object.assign(util, {
+    promisify,
+    callbackify,
+    inherits,
+    deprecate,
+    isDeepStrictEqual,
+    parseArgs,
+  });
+});
+//# sourceURL=node:internal/util
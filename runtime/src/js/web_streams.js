@@ -0,0 +1,2288 @@
+// The WHATWG streams: ReadableStream, WritableStream, TransformStream, their
+// readers, writers and controllers, the queuing strategies and
+// TextEncoderStream/TextDecoderStream, with TextEncoder and TextDecoder.
+// Evaluated once by web_streams.rs, the script is a function called with the
+// `node:events` module, the native encoding binding of stream.rs and the
+// shared helpers of internals.rs. It returns the classes, which are globals
+// and the `node:stream/web` module.
+//
+// The algorithms follow the specification (https://streams.spec.whatwg.org)
+// and keep its names, the state of each object is under `kState`. Byte
+// streams (`type: 'bytes'`) aren't supported.
+(function (EventEmitter, binding, internals) {
+  "use strict";
+
+  const kState = Symbol("kState");
+  const kCloseSentinel = Symbol("kCloseSentinel");
+  // Steps of the controllers called by their streams.
+  const kPull = Symbol("kPull");
+  const kCancel = Symbol("kCancel");
+  const kAbort = Symbol("kAbort");
+  const kError = Symbol("kError");
+  const { codeError, abortError } = internals;
+
+  function nop() {}
+
+  function invalidState(message) {
+    return codeError(TypeError, "ERR_INVALID_STATE", `Invalid state: ${message}`);
+  }
+
+  function invalidThis(name) {
+    return codeError(TypeError, "ERR_INVALID_THIS", `Value of "this" must be of type ${name}`);
+  }
+
+  function illegalConstructor() {
+    return codeError(TypeError, "ERR_ILLEGAL_CONSTRUCTOR", "Illegal constructor");
+  }
+
+  // Get the state of an object checking its class, as the `this` of the
+  // methods and getters may be anything.
+  function slots(object, Class) {
+    if (typeof object !== "object" || object === null || !(kState in object)) {
+      throw invalidThis(Class.name);
+    }
+    const state = object[kState];
+    if (state.brand !== Class) throw invalidThis(Class.name);
+    return state;
+  }
+
+  function isBranded(object, Class) {
+    return (
+      typeof object === "object" &&
+      object !== null &&
+      kState in object &&
+      object[kState].brand === Class
+    );
+  }
+
+  // A promise with its resolving functions, `settled` once one was called.
+  function deferred() {
+    const result = { settled: false };
+    result.promise = new Promise((resolve, reject) => {
+      result.resolve = (value) => {
+        result.settled = true;
+        resolve(value);
+      };
+      result.reject = (error) => {
+        result.settled = true;
+        reject(error);
+      };
+    });
+    return result;
+  }
+
+  function resolved(value) {
+    const result = deferred();
+    result.resolve(value);
+    return result;
+  }
+
+  // A rejected promise nobody has to wait for.
+  function rejectedHandled(error) {
+    const result = deferred();
+    result.promise.then(undefined, nop);
+    result.reject(error);
+    return result;
+  }
+
+  function rejectHandled(result, error) {
+    result.promise.then(undefined, nop);
+    result.reject(error);
+  }
+
+  // Call an optional method of an underlying source, sink or transformer
+  // and give a promise of its result.
+  function promiseCall(method, object, ...args) {
+    if (method === undefined) return Promise.resolve();
+    try {
+      return Promise.resolve(method.apply(object, args));
+    } catch (error) {
+      return Promise.reject(error);
+    }
+  }
+
+  function checkMethod(object, name, context) {
+    const method = object?.[name];
+    if (method !== undefined && typeof method !== "function") {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        `The "${context}.${name}" property must be of type function`
+      );
+    }
+    return method;
+  }
+
+  // Queuing strategies.
+
+  function extractHighWaterMark(strategy, defaultHighWaterMark) {
+    const highWaterMark = strategy?.highWaterMark;
+    if (highWaterMark === undefined) return defaultHighWaterMark;
+    const value = Number(highWaterMark);
+    if (Number.isNaN(value) || value < 0) {
+      throw codeError(
+        RangeError,
+        "ERR_INVALID_ARG_VALUE",
+        `The property 'strategy.highWaterMark' is invalid. Received ${String(highWaterMark)}`
+      );
+    }
+    return value;
+  }
+
+  function extractSizeAlgorithm(strategy) {
+    const size = checkMethod(strategy, "size", "strategy");
+    if (size === undefined) return () => 1;
+    return (chunk) => size(chunk);
+  }
+
+  function checkStrategyInit(init, name) {
+    if (typeof init !== "object" || init === null || init.highWaterMark === undefined) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        `The "init.highWaterMark" property must be of type number`
+      );
+    }
+    return { brand: name, highWaterMark: Number(init.highWaterMark) };
+  }
+
+  const byteLengthSize = function size(chunk) {
+    return chunk.byteLength;
+  };
+
+  const countSize = function size() {
+    return 1;
+  };
+
+  class ByteLengthQueuingStrategy {
+    constructor(init) {
+      this[kState] = checkStrategyInit(init, ByteLengthQueuingStrategy);
+    }
+
+    get highWaterMark() {
+      return slots(this, ByteLengthQueuingStrategy).highWaterMark;
+    }
+
+    get size() {
+      slots(this, ByteLengthQueuingStrategy);
+      return byteLengthSize;
+    }
+  }
+
+  class CountQueuingStrategy {
+    constructor(init) {
+      this[kState] = checkStrategyInit(init, CountQueuingStrategy);
+    }
+
+    get highWaterMark() {
+      return slots(this, CountQueuingStrategy).highWaterMark;
+    }
+
+    get size() {
+      slots(this, CountQueuingStrategy);
+      return countSize;
+    }
+  }
+
+  // The queue with sizes of the controllers.
+
+  function enqueueValueWithSize(state, value, size) {
+    if (typeof size !== "number" || Number.isNaN(size) || size < 0 || size === Infinity) {
+      throw codeError(
+        RangeError,
+        "ERR_INVALID_ARG_VALUE",
+        `The argument 'size' is invalid. Received ${String(size)}`
+      );
+    }
+    state.queue.push({ value, size });
+    state.queueTotalSize += size;
+  }
+
+  function dequeueValue(state) {
+    const { value, size } = state.queue.shift();
+    state.queueTotalSize = Math.max(0, state.queueTotalSize - size);
+    return value;
+  }
+
+  function resetQueue(state) {
+    state.queue = [];
+    state.queueTotalSize = 0;
+  }
+
+  // ReadableStream.
+
+  class ReadableStream {
+    constructor(underlyingSource = {}, strategy = {}) {
+      if (typeof underlyingSource !== "object" || underlyingSource === null) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "source" argument must be of type object'
+        );
+      }
+      initializeReadableStream(this);
+      if (underlyingSource.type !== undefined) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_VALUE",
+          `The argument 'source.type' is invalid. Received ${String(underlyingSource.type)}`
+        );
+      }
+      const sizeAlgorithm = extractSizeAlgorithm(strategy);
+      const highWaterMark = extractHighWaterMark(strategy, 1);
+      const start = checkMethod(underlyingSource, "start", "source");
+      const pull = checkMethod(underlyingSource, "pull", "source");
+      const cancel = checkMethod(underlyingSource, "cancel", "source");
+      const controller = Object.create(ReadableStreamDefaultController.prototype);
+      setUpReadableStreamDefaultController(
+        this,
+        controller,
+        () => start?.call(underlyingSource, controller),
+        () => promiseCall(pull, underlyingSource, controller),
+        (reason) => promiseCall(cancel, underlyingSource, reason),
+        highWaterMark,
+        sizeAlgorithm
+      );
+    }
+
+    get locked() {
+      return isReadableStreamLocked(slots(this, ReadableStream));
+    }
+
+    cancel(reason) {
+      if (!isBranded(this, ReadableStream)) return Promise.reject(invalidThis("ReadableStream"));
+      const state = this[kState];
+      if (isReadableStreamLocked(state)) {
+        return Promise.reject(invalidState("ReadableStream is locked"));
+      }
+      return readableStreamCancel(this, reason);
+    }
+
+    getReader(options = {}) {
+      slots(this, ReadableStream);
+      const mode = options?.mode;
+      if (mode === undefined) return new ReadableStreamDefaultReader(this);
+      if (String(mode) === "byob") {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_VALUE",
+          "The argument 'options.mode' is invalid. Byte streams are not supported"
+        );
+      }
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_VALUE",
+        `The argument 'options.mode' is invalid. Received ${String(mode)}`
+      );
+    }
+
+    pipeThrough(transform, options = {}) {
+      const state = slots(this, ReadableStream);
+      if (!isBranded(transform?.readable, ReadableStream)) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "transform.readable" property must be an instance of ReadableStream'
+        );
+      }
+      if (!isBranded(transform?.writable, WritableStream)) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "transform.writable" property must be an instance of WritableStream'
+        );
+      }
+      const { preventClose, preventAbort, preventCancel, signal } = pipeOptions(options);
+      if (isReadableStreamLocked(state)) throw invalidState("The ReadableStream is locked");
+      if (transform.writable[kState].writer !== undefined) {
+        throw invalidState("The WritableStream is locked");
+      }
+      const promise = readableStreamPipeTo(
+        this,
+        transform.writable,
+        preventClose,
+        preventAbort,
+        preventCancel,
+        signal
+      );
+      promise.then(undefined, nop);
+      return transform.readable;
+    }
+
+    pipeTo(destination, options = {}) {
+      try {
+        const state = slots(this, ReadableStream);
+        if (!isBranded(destination, WritableStream)) {
+          throw codeError(
+            TypeError,
+            "ERR_INVALID_ARG_TYPE",
+            'The "destination" argument must be an instance of WritableStream'
+          );
+        }
+        const { preventClose, preventAbort, preventCancel, signal } = pipeOptions(options);
+        if (isReadableStreamLocked(state)) throw invalidState("The ReadableStream is locked");
+        if (destination[kState].writer !== undefined) {
+          throw invalidState("The WritableStream is locked");
+        }
+        return readableStreamPipeTo(
+          this,
+          destination,
+          preventClose,
+          preventAbort,
+          preventCancel,
+          signal
+        );
+      } catch (error) {
+        return Promise.reject(error);
+      }
+    }
+
+    tee() {
+      slots(this, ReadableStream);
+      return readableStreamDefaultTee(this);
+    }
+
+    values(options = {}) {
+      slots(this, ReadableStream);
+      return readableStreamValues(this, Boolean(options?.preventCancel));
+    }
+
+    [Symbol.asyncIterator](options) {
+      return this.values(options);
+    }
+
+    // Create a stream from an iterable or an async iterable.
+    static from(iterable) {
+      return readableStreamFromIterable(iterable);
+    }
+  }
+
+  function initializeReadableStream(stream) {
+    stream[kState] = {
+      brand: ReadableStream,
+      state: "readable",
+      reader: undefined,
+      storedError: undefined,
+      disturbed: false,
+      controller: undefined,
+    };
+  }
+
+  function createReadableStream(
+    startAlgorithm,
+    pullAlgorithm,
+    cancelAlgorithm,
+    highWaterMark = 1,
+    sizeAlgorithm = () => 1
+  ) {
+    const stream = Object.create(ReadableStream.prototype);
+    initializeReadableStream(stream);
+    const controller = Object.create(ReadableStreamDefaultController.prototype);
+    setUpReadableStreamDefaultController(
+      stream,
+      controller,
+      startAlgorithm,
+      pullAlgorithm,
+      cancelAlgorithm,
+      highWaterMark,
+      sizeAlgorithm
+    );
+    return stream;
+  }
+
+  function isReadableStreamLocked(state) {
+    return state.reader !== undefined;
+  }
+
+  function readableStreamCancel(stream, reason) {
+    const state = stream[kState];
+    state.disturbed = true;
+    if (state.state === "closed") return Promise.resolve();
+    if (state.state === "errored") return Promise.reject(state.storedError);
+    readableStreamClose(stream);
+    return state.controller[kCancel](reason).then(nop);
+  }
+
+  function readableStreamClose(stream) {
+    const state = stream[kState];
+    state.state = "closed";
+    const reader = state.reader;
+    if (reader === undefined) return;
+    const readerState = reader[kState];
+    readerState.closed.resolve();
+    for (const request of readerState.readRequests.splice(0)) request.close();
+  }
+
+  function readableStreamError(stream, error) {
+    const state = stream[kState];
+    state.state = "errored";
+    state.storedError = error;
+    const reader = state.reader;
+    if (reader === undefined) return;
+    const readerState = reader[kState];
+    rejectHandled(readerState.closed, error);
+    for (const request of readerState.readRequests.splice(0)) request.error(error);
+  }
+
+  function readableStreamFulfillReadRequest(stream, chunk, done) {
+    const request = stream[kState].reader[kState].readRequests.shift();
+    if (done) {
+      request.close();
+    } else {
+      request.chunk(chunk);
+    }
+  }
+
+  function readableStreamGetNumReadRequests(stream) {
+    return stream[kState].reader[kState].readRequests.length;
+  }
+
+  // ReadableStreamDefaultReader.
+
+  class ReadableStreamDefaultReader {
+    constructor(stream) {
+      if (!isBranded(stream, ReadableStream)) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "stream" argument must be an instance of ReadableStream'
+        );
+      }
+      if (isReadableStreamLocked(stream[kState])) {
+        throw invalidState("ReadableStream is locked");
+      }
+      this[kState] = {
+        brand: ReadableStreamDefaultReader,
+        stream,
+        closed: deferred(),
+        readRequests: [],
+      };
+      readableStreamReaderGenericInitialize(this, stream);
+    }
+
+    get closed() {
+      return slots(this, ReadableStreamDefaultReader).closed.promise;
+    }
+
+    cancel(reason) {
+      if (!isBranded(this, ReadableStreamDefaultReader)) {
+        return Promise.reject(invalidThis("ReadableStreamDefaultReader"));
+      }
+      const stream = this[kState].stream;
+      if (stream === undefined) {
+        return Promise.reject(invalidState("The reader is not attached to a stream"));
+      }
+      return readableStreamCancel(stream, reason);
+    }
+
+    read() {
+      if (!isBranded(this, ReadableStreamDefaultReader)) {
+        return Promise.reject(invalidThis("ReadableStreamDefaultReader"));
+      }
+      if (this[kState].stream === undefined) {
+        return Promise.reject(invalidState("The reader is not attached to a stream"));
+      }
+      const result = deferred();
+      readableStreamDefaultReaderRead(this, {
+        chunk: (value) => result.resolve({ value, done: false }),
+        close: () => result.resolve({ value: undefined, done: true }),
+        error: (error) => result.reject(error),
+      });
+      return result.promise;
+    }
+
+    releaseLock() {
+      const state = slots(this, ReadableStreamDefaultReader);
+      if (state.stream === undefined) return;
+      readableStreamDefaultReaderRelease(this);
+    }
+  }
+
+  function readableStreamReaderGenericInitialize(reader, stream) {
+    const state = stream[kState];
+    const readerState = reader[kState];
+    state.reader = reader;
+    if (state.state === "closed") {
+      readerState.closed.resolve();
+    } else if (state.state === "errored") {
+      rejectHandled(readerState.closed, state.storedError);
+    }
+  }
+
+  function readableStreamDefaultReaderRead(reader, request) {
+    const stream = reader[kState].stream;
+    const state = stream[kState];
+    state.disturbed = true;
+    if (state.state === "closed") {
+      request.close();
+    } else if (state.state === "errored") {
+      request.error(state.storedError);
+    } else {
+      state.controller[kPull](request);
+    }
+  }
+
+  function readableStreamDefaultReaderRelease(reader) {
+    const readerState = reader[kState];
+    const stream = readerState.stream;
+    const error = invalidState("Releasing reader");
+    if (stream[kState].state === "readable") {
+      rejectHandled(readerState.closed, error);
+    } else {
+      readerState.closed = rejectedHandled(error);
+    }
+    stream[kState].reader = undefined;
+    readerState.stream = undefined;
+    for (const request of readerState.readRequests.splice(0)) request.error(error);
+  }
+
+  // ReadableStreamDefaultController.
+
+  class ReadableStreamDefaultController {
+    constructor() {
+      throw illegalConstructor();
+    }
+
+    get desiredSize() {
+      return readableStreamDefaultControllerGetDesiredSize(
+        slots(this, ReadableStreamDefaultController)
+      );
+    }
+
+    close() {
+      const state = slots(this, ReadableStreamDefaultController);
+      if (!readableStreamDefaultControllerCanCloseOrEnqueue(state)) {
+        throw invalidState("Controller is already closed");
+      }
+      readableStreamDefaultControllerClose(this);
+    }
+
+    enqueue(chunk) {
+      const state = slots(this, ReadableStreamDefaultController);
+      if (!readableStreamDefaultControllerCanCloseOrEnqueue(state)) {
+        throw invalidState("Controller is already closed");
+      }
+      readableStreamDefaultControllerEnqueue(this, chunk);
+    }
+
+    error(error) {
+      slots(this, ReadableStreamDefaultController);
+      readableStreamDefaultControllerError(this, error);
+    }
+
+    [kCancel](reason) {
+      const state = this[kState];
+      resetQueue(state);
+      const result = state.cancelAlgorithm(reason);
+      readableStreamDefaultControllerClearAlgorithms(state);
+      return result;
+    }
+
+    [kPull](request) {
+      const state = this[kState];
+      const stream = state.stream;
+      if (state.queue.length > 0) {
+        const chunk = dequeueValue(state);
+        if (state.closeRequested && state.queue.length === 0) {
+          readableStreamDefaultControllerClearAlgorithms(state);
+          readableStreamClose(stream);
+        } else {
+          readableStreamDefaultControllerCallPullIfNeeded(this);
+        }
+        request.chunk(chunk);
+      } else {
+        stream[kState].reader[kState].readRequests.push(request);
+        readableStreamDefaultControllerCallPullIfNeeded(this);
+      }
+    }
+  }
+
+  function setUpReadableStreamDefaultController(
+    stream,
+    controller,
+    startAlgorithm,
+    pullAlgorithm,
+    cancelAlgorithm,
+    highWaterMark,
+    sizeAlgorithm
+  ) {
+    const state = {
+      brand: ReadableStreamDefaultController,
+      stream,
+      queue: [],
+      queueTotalSize: 0,
+      started: false,
+      closeRequested: false,
+      pullAgain: false,
+      pulling: false,
+      strategySizeAlgorithm: sizeAlgorithm,
+      strategyHWM: highWaterMark,
+      pullAlgorithm,
+      cancelAlgorithm,
+    };
+    controller[kState] = state;
+    stream[kState].controller = controller;
+    Promise.resolve(startAlgorithm()).then(
+      () => {
+        state.started = true;
+        readableStreamDefaultControllerCallPullIfNeeded(controller);
+      },
+      (error) => readableStreamDefaultControllerError(controller, error)
+    );
+  }
+
+  function readableStreamDefaultControllerCallPullIfNeeded(controller) {
+    const state = controller[kState];
+    if (!readableStreamDefaultControllerShouldCallPull(state)) return;
+    if (state.pulling) {
+      state.pullAgain = true;
+      return;
+    }
+    state.pulling = true;
+    state.pullAlgorithm().then(
+      () => {
+        state.pulling = false;
+        if (state.pullAgain) {
+          state.pullAgain = false;
+          readableStreamDefaultControllerCallPullIfNeeded(controller);
+        }
+      },
+      (error) => readableStreamDefaultControllerError(controller, error)
+    );
+  }
+
+  function readableStreamDefaultControllerShouldCallPull(state) {
+    if (!readableStreamDefaultControllerCanCloseOrEnqueue(state) || !state.started) {
+      return false;
+    }
+    const stream = state.stream;
+    if (
+      isReadableStreamLocked(stream[kState]) &&
+      readableStreamGetNumReadRequests(stream) > 0
+    ) {
+      return true;
+    }
+    return readableStreamDefaultControllerGetDesiredSize(state) > 0;
+  }
+
+  function readableStreamDefaultControllerClearAlgorithms(state) {
+    state.pullAlgorithm = undefined;
+    state.cancelAlgorithm = undefined;
+    state.strategySizeAlgorithm = undefined;
+  }
+
+  function readableStreamDefaultControllerClose(controller) {
+    const state = controller[kState];
+    if (!readableStreamDefaultControllerCanCloseOrEnqueue(state)) return;
+    state.closeRequested = true;
+    if (state.queue.length === 0) {
+      readableStreamDefaultControllerClearAlgorithms(state);
+      readableStreamClose(state.stream);
+    }
+  }
+
+  function readableStreamDefaultControllerEnqueue(controller, chunk) {
+    const state = controller[kState];
+    if (!readableStreamDefaultControllerCanCloseOrEnqueue(state)) return;
+    const stream = state.stream;
+    if (
+      isReadableStreamLocked(stream[kState]) &&
+      readableStreamGetNumReadRequests(stream) > 0
+    ) {
+      readableStreamFulfillReadRequest(stream, chunk, false);
+    } else {
+      try {
+        enqueueValueWithSize(state, chunk, state.strategySizeAlgorithm(chunk));
+      } catch (error) {
+        readableStreamDefaultControllerError(controller, error);
+        throw error;
+      }
+    }
+    readableStreamDefaultControllerCallPullIfNeeded(controller);
+  }
+
+  function readableStreamDefaultControllerError(controller, error) {
+    const state = controller[kState];
+    if (state.stream[kState].state !== "readable") return;
+    resetQueue(state);
+    readableStreamDefaultControllerClearAlgorithms(state);
+    readableStreamError(state.stream, error);
+  }
+
+  function readableStreamDefaultControllerGetDesiredSize(state) {
+    const streamState = state.stream[kState].state;
+    if (streamState === "errored") return null;
+    if (streamState === "closed") return 0;
+    return state.strategyHWM - state.queueTotalSize;
+  }
+
+  function readableStreamDefaultControllerHasBackpressure(controller) {
+    return !readableStreamDefaultControllerShouldCallPull(controller[kState]);
+  }
+
+  function readableStreamDefaultControllerCanCloseOrEnqueue(state) {
+    return !state.closeRequested && state.stream[kState].state === "readable";
+  }
+
+  // Tee, async iteration and `ReadableStream.from`.
+
+  function readableStreamDefaultTee(stream) {
+    const reader = new ReadableStreamDefaultReader(stream);
+    let reading = false;
+    let readAgain = false;
+    let canceled1 = false;
+    let canceled2 = false;
+    let reason1;
+    let reason2;
+    const cancelPromise = deferred();
+
+    function pull() {
+      if (reading) {
+        readAgain = true;
+        return Promise.resolve();
+      }
+      reading = true;
+      readableStreamDefaultReaderRead(reader, {
+        chunk(chunk) {
+          // As in the specification, the chunk is given in a microtask so a
+          // rejected read may be seen first.
+          Promise.resolve().then(() => {
+            readAgain = false;
+            if (!canceled1) readableStreamDefaultControllerEnqueue(branch1[kState].controller, chunk);
+            if (!canceled2) readableStreamDefaultControllerEnqueue(branch2[kState].controller, chunk);
+            reading = false;
+            if (readAgain) pull();
+          });
+        },
+        close() {
+          reading = false;
+          if (!canceled1) readableStreamDefaultControllerClose(branch1[kState].controller);
+          if (!canceled2) readableStreamDefaultControllerClose(branch2[kState].controller);
+          if (!canceled1 || !canceled2) cancelPromise.resolve();
+        },
+        error() {
+          reading = false;
+        },
+      });
+      return Promise.resolve();
+    }
+
+    function cancel1(reason) {
+      canceled1 = true;
+      reason1 = reason;
+      if (canceled2) cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+      return cancelPromise.promise;
+    }
+
+    function cancel2(reason) {
+      canceled2 = true;
+      reason2 = reason;
+      if (canceled1) cancelPromise.resolve(readableStreamCancel(stream, [reason1, reason2]));
+      return cancelPromise.promise;
+    }
+
+    const branch1 = createReadableStream(nop, pull, cancel1);
+    const branch2 = createReadableStream(nop, pull, cancel2);
+    reader[kState].closed.promise.then(undefined, (error) => {
+      readableStreamDefaultControllerError(branch1[kState].controller, error);
+      readableStreamDefaultControllerError(branch2[kState].controller, error);
+      if (!canceled1 || !canceled2) cancelPromise.resolve();
+    });
+    return [branch1, branch2];
+  }
+
+  const AsyncIteratorPrototype = Object.getPrototypeOf(
+    Object.getPrototypeOf(async function* () {}).prototype
+  );
+
+  function readableStreamValues(stream, preventCancel) {
+    const reader = new ReadableStreamDefaultReader(stream);
+    let ongoing;
+    let finished = false;
+
+    function nextSteps() {
+      if (finished) return Promise.resolve({ value: undefined, done: true });
+      const result = deferred();
+      readableStreamDefaultReaderRead(reader, {
+        chunk: (value) => result.resolve({ value, done: false }),
+        close() {
+          finished = true;
+          readableStreamDefaultReaderRelease(reader);
+          result.resolve({ value: undefined, done: true });
+        },
+        error(error) {
+          finished = true;
+          readableStreamDefaultReaderRelease(reader);
+          result.reject(error);
+        },
+      });
+      return result.promise;
+    }
+
+    function returnSteps(value) {
+      if (finished) return Promise.resolve({ value, done: true });
+      finished = true;
+      if (!preventCancel) {
+        const result = readableStreamCancel(stream, value);
+        readableStreamDefaultReaderRelease(reader);
+        return result.then(() => ({ value, done: true }));
+      }
+      readableStreamDefaultReaderRelease(reader);
+      return Promise.resolve({ value, done: true });
+    }
+
+    const iterator = Object.create(AsyncIteratorPrototype);
+    iterator.next = function () {
+      ongoing = ongoing ? ongoing.then(nextSteps, nextSteps) : nextSteps();
+      return ongoing;
+    };
+    iterator.return = function (value) {
+      const steps = () => returnSteps(value);
+      ongoing = ongoing ? ongoing.then(steps, steps) : steps();
+      return ongoing;
+    };
+    return iterator;
+  }
+
+  function readableStreamFromIterable(iterable) {
+    let iterator;
+    if (iterable != null && typeof iterable[Symbol.asyncIterator] === "function") {
+      iterator = iterable[Symbol.asyncIterator]();
+    } else if (iterable != null && typeof iterable[Symbol.iterator] === "function") {
+      const syncIterator = iterable[Symbol.iterator]();
+      iterator = {
+        next: () => Promise.resolve(syncIterator.next()).then(unwrapResult),
+        return: (value) =>
+          Promise.resolve(
+            typeof syncIterator.return === "function"
+              ? syncIterator.return(value)
+              : { value, done: true }
+          ).then(unwrapResult),
+      };
+    } else {
+      throw codeError(
+        TypeError,
+        "ERR_ARG_NOT_ITERABLE",
+        "asyncIterable must be iterable"
+      );
+    }
+    const stream = createReadableStream(
+      nop,
+      () =>
+        Promise.resolve(iterator.next()).then((result) => {
+          if (typeof result !== "object" || result === null) {
+            throw codeError(
+              TypeError,
+              "ERR_INVALID_STATE",
+              "Invalid state: The promise returned by the iterator.next() method must fulfill with an object"
+            );
+          }
+          const controller = stream[kState].controller;
+          if (result.done) {
+            readableStreamDefaultControllerClose(controller);
+          } else {
+            readableStreamDefaultControllerEnqueue(controller, result.value);
+          }
+        }),
+      (reason) => {
+        if (typeof iterator.return !== "function") return Promise.resolve();
+        return Promise.resolve(iterator.return(reason)).then((result) => {
+          if (typeof result !== "object" || result === null) {
+            throw codeError(
+              TypeError,
+              "ERR_INVALID_STATE",
+              "Invalid state: The promise returned by the iterator.return() method must fulfill with an object"
+            );
+          }
+        });
+      },
+      0
+    );
+    return stream;
+
+    // The values of a sync iterator may be promises.
+    function unwrapResult(result) {
+      return Promise.resolve(result.value).then((value) => ({ value, done: result.done }));
+    }
+  }
+
+  // Pipes.
+
+  function pipeOptions(options) {
+    if (typeof options !== "object" || options === null) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "options" argument must be of type object'
+      );
+    }
+    const signal = options.signal;
+    if (
+      signal !== undefined &&
+      (signal === null || typeof signal !== "object" || !("aborted" in signal))
+    ) {
+      throw codeError(
+        TypeError,
+        "ERR_INVALID_ARG_TYPE",
+        'The "options.signal" property must be an instance of AbortSignal'
+      );
+    }
+    return {
+      preventClose: Boolean(options.preventClose),
+      preventAbort: Boolean(options.preventAbort),
+      preventCancel: Boolean(options.preventCancel),
+      signal,
+    };
+  }
+
+  function readableStreamPipeTo(source, dest, preventClose, preventAbort, preventCancel, signal) {
+    const reader = new ReadableStreamDefaultReader(source);
+    const writer = new WritableStreamDefaultWriter(dest);
+    const sourceState = source[kState];
+    const destState = dest[kState];
+    sourceState.disturbed = true;
+    let shuttingDown = false;
+    let currentWrite = Promise.resolve();
+    const result = deferred();
+    let abortAlgorithm;
+
+    if (signal !== undefined) {
+      abortAlgorithm = () => {
+        const error = signal.reason !== undefined ? signal.reason : abortError();
+        const actions = [];
+        if (!preventAbort) {
+          actions.push(() =>
+            destState.state === "writable" ? writableStreamAbort(dest, error) : Promise.resolve()
+          );
+        }
+        if (!preventCancel) {
+          actions.push(() =>
+            sourceState.state === "readable"
+              ? readableStreamCancel(source, error)
+              : Promise.resolve()
+          );
+        }
+        shutdownWithAction(() => Promise.all(actions.map((action) => action())), true, error);
+      };
+      if (signal.aborted) {
+        abortAlgorithm();
+        return result.promise;
+      }
+      signal.addEventListener("abort", abortAlgorithm, { once: true });
+    }
+
+    function pipeStep() {
+      if (shuttingDown) return Promise.resolve(true);
+      return writer[kState].ready.promise.then(() => {
+        if (shuttingDown) return true;
+        const step = deferred();
+        readableStreamDefaultReaderRead(reader, {
+          chunk(chunk) {
+            currentWrite = writableStreamDefaultWriterWrite(writer, chunk).then(undefined, nop);
+            step.resolve(false);
+          },
+          close: () => step.resolve(true),
+          error: (error) => step.reject(error),
+        });
+        return step.promise;
+      });
+    }
+
+    function pipeLoop() {
+      pipeStep().then((done) => {
+        if (!done) pipeLoop();
+      }, nop);
+    }
+
+    function isOrBecomesErrored(state, promise, action) {
+      if (state.state === "errored") {
+        action(state.storedError);
+      } else {
+        promise.then(undefined, action);
+      }
+    }
+
+    function isOrBecomesClosed(state, promise, action) {
+      if (state.state === "closed") {
+        action();
+      } else {
+        promise.then(action, nop);
+      }
+    }
+
+    function waitForWritesToFinish() {
+      const oldCurrentWrite = currentWrite;
+      return currentWrite.then(() =>
+        oldCurrentWrite !== currentWrite ? waitForWritesToFinish() : undefined
+      );
+    }
+
+    function shutdownWithAction(action, originalIsError, originalError) {
+      if (shuttingDown) return;
+      shuttingDown = true;
+      const doTheRest = () => {
+        action().then(
+          () => finalize(originalIsError, originalError),
+          (error) => finalize(true, error)
+        );
+      };
+      if (destState.state === "writable" && !writableStreamCloseQueuedOrInFlight(destState)) {
+        waitForWritesToFinish().then(doTheRest);
+      } else {
+        doTheRest();
+      }
+    }
+
+    function shutdown(isError, error) {
+      if (shuttingDown) return;
+      shuttingDown = true;
+      if (destState.state === "writable" && !writableStreamCloseQueuedOrInFlight(destState)) {
+        waitForWritesToFinish().then(() => finalize(isError, error));
+      } else {
+        finalize(isError, error);
+      }
+    }
+
+    function finalize(isError, error) {
+      writableStreamDefaultWriterRelease(writer);
+      readableStreamDefaultReaderRelease(reader);
+      if (signal !== undefined) signal.removeEventListener("abort", abortAlgorithm);
+      if (isError) {
+        result.reject(error);
+      } else {
+        result.resolve();
+      }
+    }
+
+    isOrBecomesErrored(sourceState, reader[kState].closed.promise, (error) => {
+      if (!preventAbort) {
+        shutdownWithAction(() => writableStreamAbort(dest, error), true, error);
+      } else {
+        shutdown(true, error);
+      }
+    });
+    isOrBecomesErrored(destState, writer[kState].closed.promise, (error) => {
+      if (!preventCancel) {
+        shutdownWithAction(() => readableStreamCancel(source, error), true, error);
+      } else {
+        shutdown(true, error);
+      }
+    });
+    isOrBecomesClosed(sourceState, reader[kState].closed.promise, () => {
+      if (!preventClose) {
+        shutdownWithAction(() => writableStreamDefaultWriterCloseWithErrorPropagation(writer));
+      } else {
+        shutdown();
+      }
+    });
+    if (writableStreamCloseQueuedOrInFlight(destState) || destState.state === "closed") {
+      const error = new TypeError(
+        "the destination writable stream closed before all data could be piped to it"
+      );
+      if (!preventCancel) {
+        shutdownWithAction(() => readableStreamCancel(source, error), true, error);
+      } else {
+        shutdown(true, error);
+      }
+    }
+    pipeLoop();
+    return result.promise;
+  }
+
+  // WritableStream.
+
+  class WritableStream {
+    constructor(underlyingSink = {}, strategy = {}) {
+      if (typeof underlyingSink !== "object" || underlyingSink === null) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "sink" argument must be of type object'
+        );
+      }
+      if (underlyingSink.type !== undefined) {
+        throw codeError(
+          RangeError,
+          "ERR_INVALID_ARG_VALUE",
+          `The argument 'sink.type' is invalid. Received ${String(underlyingSink.type)}`
+        );
+      }
+      initializeWritableStream(this);
+      const sizeAlgorithm = extractSizeAlgorithm(strategy);
+      const highWaterMark = extractHighWaterMark(strategy, 1);
+      const start = checkMethod(underlyingSink, "start", "sink");
+      const write = checkMethod(underlyingSink, "write", "sink");
+      const close = checkMethod(underlyingSink, "close", "sink");
+      const abort = checkMethod(underlyingSink, "abort", "sink");
+      const controller = Object.create(WritableStreamDefaultController.prototype);
+      setUpWritableStreamDefaultController(
+        this,
+        controller,
+        () => start?.call(underlyingSink, controller),
+        (chunk) => promiseCall(write, underlyingSink, chunk, controller),
+        () => promiseCall(close, underlyingSink),
+        (reason) => promiseCall(abort, underlyingSink, reason),
+        highWaterMark,
+        sizeAlgorithm
+      );
+    }
+
+    get locked() {
+      return slots(this, WritableStream).writer !== undefined;
+    }
+
+    abort(reason) {
+      if (!isBranded(this, WritableStream)) return Promise.reject(invalidThis("WritableStream"));
+      if (this[kState].writer !== undefined) {
+        return Promise.reject(invalidState("WritableStream is locked"));
+      }
+      return writableStreamAbort(this, reason);
+    }
+
+    close() {
+      if (!isBranded(this, WritableStream)) return Promise.reject(invalidThis("WritableStream"));
+      const state = this[kState];
+      if (state.writer !== undefined) {
+        return Promise.reject(invalidState("WritableStream is locked"));
+      }
+      if (writableStreamCloseQueuedOrInFlight(state)) {
+        return Promise.reject(invalidState("Failure closing WritableStream"));
+      }
+      return writableStreamClose(this);
+    }
+
+    getWriter() {
+      slots(this, WritableStream);
+      return new WritableStreamDefaultWriter(this);
+    }
+  }
+
+  function initializeWritableStream(stream) {
+    stream[kState] = {
+      brand: WritableStream,
+      state: "writable",
+      storedError: undefined,
+      writer: undefined,
+      controller: undefined,
+      inFlightWriteRequest: undefined,
+      closeRequest: undefined,
+      inFlightCloseRequest: undefined,
+      pendingAbortRequest: undefined,
+      writeRequests: [],
+      backpressure: false,
+    };
+  }
+
+  function createWritableStream(
+    startAlgorithm,
+    writeAlgorithm,
+    closeAlgorithm,
+    abortAlgorithm,
+    highWaterMark,
+    sizeAlgorithm
+  ) {
+    const stream = Object.create(WritableStream.prototype);
+    initializeWritableStream(stream);
+    const controller = Object.create(WritableStreamDefaultController.prototype);
+    setUpWritableStreamDefaultController(
+      stream,
+      controller,
+      startAlgorithm,
+      writeAlgorithm,
+      closeAlgorithm,
+      abortAlgorithm,
+      highWaterMark,
+      sizeAlgorithm
+    );
+    return stream;
+  }
+
+  function writableStreamAbort(stream, reason) {
+    const state = stream[kState];
+    if (state.state === "closed" || state.state === "errored") return Promise.resolve();
+    state.controller[kState].abort(reason);
+    // The abort signal may have changed the state.
+    if (state.state === "closed" || state.state === "errored") return Promise.resolve();
+    if (state.pendingAbortRequest !== undefined) return state.pendingAbortRequest.result.promise;
+    const wasAlreadyErroring = state.state === "erroring";
+    if (wasAlreadyErroring) reason = undefined;
+    const result = deferred();
+    state.pendingAbortRequest = { result, reason, wasAlreadyErroring };
+    if (!wasAlreadyErroring) writableStreamStartErroring(stream, reason);
+    return result.promise;
+  }
+
+  function writableStreamClose(stream) {
+    const state = stream[kState];
+    if (state.state === "closed" || state.state === "errored") {
+      return Promise.reject(invalidState("WritableStream is closed"));
+    }
+    const result = deferred();
+    state.closeRequest = result;
+    if (state.writer !== undefined && state.backpressure && state.state === "writable") {
+      state.writer[kState].ready.resolve();
+    }
+    writableStreamDefaultControllerClose(state.controller);
+    return result.promise;
+  }
+
+  function writableStreamAddWriteRequest(stream) {
+    const result = deferred();
+    stream[kState].writeRequests.push(result);
+    return result.promise;
+  }
+
+  function writableStreamDealWithRejection(stream, error) {
+    if (stream[kState].state === "writable") {
+      writableStreamStartErroring(stream, error);
+    } else {
+      writableStreamFinishErroring(stream);
+    }
+  }
+
+  function writableStreamStartErroring(stream, reason) {
+    const state = stream[kState];
+    state.state = "erroring";
+    state.storedError = reason;
+    if (state.writer !== undefined) {
+      writableStreamDefaultWriterEnsureReadyPromiseRejected(state.writer, reason);
+    }
+    if (!writableStreamHasOperationMarkedInFlight(state) && state.controller[kState].started) {
+      writableStreamFinishErroring(stream);
+    }
+  }
+
+  function writableStreamFinishErroring(stream) {
+    const state = stream[kState];
+    state.state = "errored";
+    state.controller[kError]();
+    const storedError = state.storedError;
+    for (const request of state.writeRequests.splice(0)) request.reject(storedError);
+    const abortRequest = state.pendingAbortRequest;
+    if (abortRequest === undefined) {
+      writableStreamRejectCloseAndClosedPromiseIfNeeded(state);
+      return;
+    }
+    state.pendingAbortRequest = undefined;
+    if (abortRequest.wasAlreadyErroring) {
+      abortRequest.result.reject(storedError);
+      writableStreamRejectCloseAndClosedPromiseIfNeeded(state);
+      return;
+    }
+    state.controller[kAbort](abortRequest.reason).then(
+      () => {
+        abortRequest.result.resolve();
+        writableStreamRejectCloseAndClosedPromiseIfNeeded(state);
+      },
+      (error) => {
+        abortRequest.result.reject(error);
+        writableStreamRejectCloseAndClosedPromiseIfNeeded(state);
+      }
+    );
+  }
+
+  function writableStreamFinishInFlightWrite(state) {
+    state.inFlightWriteRequest.resolve();
+    state.inFlightWriteRequest = undefined;
+  }
+
+  function writableStreamFinishInFlightWriteWithError(stream, error) {
+    const state = stream[kState];
+    state.inFlightWriteRequest.reject(error);
+    state.inFlightWriteRequest = undefined;
+    writableStreamDealWithRejection(stream, error);
+  }
+
+  function writableStreamFinishInFlightClose(state) {
+    state.inFlightCloseRequest.resolve();
+    state.inFlightCloseRequest = undefined;
+    if (state.state === "erroring") {
+      state.storedError = undefined;
+      if (state.pendingAbortRequest !== undefined) {
+        state.pendingAbortRequest.result.resolve();
+        state.pendingAbortRequest = undefined;
+      }
+    }
+    state.state = "closed";
+    if (state.writer !== undefined) state.writer[kState].closed.resolve();
+  }
+
+  function writableStreamFinishInFlightCloseWithError(stream, error) {
+    const state = stream[kState];
+    state.inFlightCloseRequest.reject(error);
+    state.inFlightCloseRequest = undefined;
+    if (state.pendingAbortRequest !== undefined) {
+      state.pendingAbortRequest.result.reject(error);
+      state.pendingAbortRequest = undefined;
+    }
+    writableStreamDealWithRejection(stream, error);
+  }
+
+  function writableStreamCloseQueuedOrInFlight(state) {
+    return state.closeRequest !== undefined || state.inFlightCloseRequest !== undefined;
+  }
+
+  function writableStreamHasOperationMarkedInFlight(state) {
+    return state.inFlightWriteRequest !== undefined || state.inFlightCloseRequest !== undefined;
+  }
+
+  function writableStreamRejectCloseAndClosedPromiseIfNeeded(state) {
+    if (state.closeRequest !== undefined) {
+      state.closeRequest.reject(state.storedError);
+      state.closeRequest = undefined;
+    }
+    if (state.writer !== undefined) rejectHandled(state.writer[kState].closed, state.storedError);
+  }
+
+  function writableStreamUpdateBackpressure(state, backpressure) {
+    const writer = state.writer;
+    if (writer !== undefined && backpressure !== state.backpressure) {
+      if (backpressure) {
+        writer[kState].ready = deferred();
+      } else {
+        writer[kState].ready.resolve();
+      }
+    }
+    state.backpressure = backpressure;
+  }
+
+  // WritableStreamDefaultWriter.
+
+  class WritableStreamDefaultWriter {
+    constructor(stream) {
+      if (!isBranded(stream, WritableStream)) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "stream" argument must be an instance of WritableStream'
+        );
+      }
+      const state = stream[kState];
+      if (state.writer !== undefined) throw invalidState("WritableStream is locked");
+      const writerState = {
+        brand: WritableStreamDefaultWriter,
+        stream,
+        ready: undefined,
+        closed: undefined,
+      };
+      this[kState] = writerState;
+      state.writer = this;
+      if (state.state === "writable") {
+        writerState.ready =
+          !writableStreamCloseQueuedOrInFlight(state) && state.backpressure
+            ? deferred()
+            : resolved();
+        writerState.closed = deferred();
+      } else if (state.state === "erroring") {
+        writerState.ready = rejectedHandled(state.storedError);
+        writerState.closed = deferred();
+      } else if (state.state === "closed") {
+        writerState.ready = resolved();
+        writerState.closed = resolved();
+      } else {
+        writerState.ready = rejectedHandled(state.storedError);
+        writerState.closed = rejectedHandled(state.storedError);
+      }
+    }
+
+    get closed() {
+      return slots(this, WritableStreamDefaultWriter).closed.promise;
+    }
+
+    get desiredSize() {
+      const stream = slots(this, WritableStreamDefaultWriter).stream;
+      if (stream === undefined) throw invalidState("Writer is not bound to a WritableStream");
+      const state = stream[kState];
+      if (state.state === "errored" || state.state === "erroring") return null;
+      if (state.state === "closed") return 0;
+      return writableStreamDefaultControllerGetDesiredSize(state.controller[kState]);
+    }
+
+    get ready() {
+      return slots(this, WritableStreamDefaultWriter).ready.promise;
+    }
+
+    abort(reason) {
+      if (!isBranded(this, WritableStreamDefaultWriter)) {
+        return Promise.reject(invalidThis("WritableStreamDefaultWriter"));
+      }
+      const stream = this[kState].stream;
+      if (stream === undefined) {
+        return Promise.reject(invalidState("Writer is not bound to a WritableStream"));
+      }
+      return writableStreamAbort(stream, reason);
+    }
+
+    close() {
+      if (!isBranded(this, WritableStreamDefaultWriter)) {
+        return Promise.reject(invalidThis("WritableStreamDefaultWriter"));
+      }
+      const stream = this[kState].stream;
+      if (stream === undefined) {
+        return Promise.reject(invalidState("Writer is not bound to a WritableStream"));
+      }
+      if (writableStreamCloseQueuedOrInFlight(stream[kState])) {
+        return Promise.reject(invalidState("Failure to close WritableStream"));
+      }
+      return writableStreamClose(stream);
+    }
+
+    releaseLock() {
+      const state = slots(this, WritableStreamDefaultWriter);
+      if (state.stream === undefined) return;
+      writableStreamDefaultWriterRelease(this);
+    }
+
+    write(chunk) {
+      if (!isBranded(this, WritableStreamDefaultWriter)) {
+        return Promise.reject(invalidThis("WritableStreamDefaultWriter"));
+      }
+      if (this[kState].stream === undefined) {
+        return Promise.reject(invalidState("Writer is not bound to a WritableStream"));
+      }
+      return writableStreamDefaultWriterWrite(this, chunk);
+    }
+  }
+
+  function writableStreamDefaultWriterCloseWithErrorPropagation(writer) {
+    const stream = writer[kState].stream;
+    const state = stream[kState];
+    if (writableStreamCloseQueuedOrInFlight(state) || state.state === "closed") {
+      return Promise.resolve();
+    }
+    if (state.state === "errored") return Promise.reject(state.storedError);
+    return writableStreamClose(stream);
+  }
+
+  function writableStreamDefaultWriterEnsureClosedPromiseRejected(writer, error) {
+    const state = writer[kState];
+    if (state.closed.settled) {
+      state.closed = rejectedHandled(error);
+    } else {
+      rejectHandled(state.closed, error);
+    }
+  }
+
+  function writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, error) {
+    const state = writer[kState];
+    if (state.ready.settled) {
+      state.ready = rejectedHandled(error);
+    } else {
+      rejectHandled(state.ready, error);
+    }
+  }
+
+  function writableStreamDefaultWriterRelease(writer) {
+    const state = writer[kState];
+    const error = invalidState("Writer has been released");
+    writableStreamDefaultWriterEnsureReadyPromiseRejected(writer, error);
+    writableStreamDefaultWriterEnsureClosedPromiseRejected(writer, error);
+    state.stream[kState].writer = undefined;
+    state.stream = undefined;
+  }
+
+  function writableStreamDefaultWriterWrite(writer, chunk) {
+    const stream = writer[kState].stream;
+    const state = stream[kState];
+    const controller = state.controller;
+    const chunkSize = writableStreamDefaultControllerGetChunkSize(controller, chunk);
+    if (stream !== writer[kState].stream) {
+      return Promise.reject(invalidState("Writer has been released"));
+    }
+    if (state.state === "errored") return Promise.reject(state.storedError);
+    if (writableStreamCloseQueuedOrInFlight(state) || state.state === "closed") {
+      return Promise.reject(invalidState("WritableStream is closed"));
+    }
+    if (state.state === "erroring") return Promise.reject(state.storedError);
+    const result = writableStreamAddWriteRequest(stream);
+    writableStreamDefaultControllerWrite(controller, chunk, chunkSize);
+    return result;
+  }
+
+  // WritableStreamDefaultController.
+
+  // The `signal` of a WritableStreamDefaultController, aborted with the
+  // stream.
+  function createAbortSignal() {
+    const signal = new EventEmitter.EventTarget();
+    let aborted = false;
+    let reason;
+    Object.defineProperties(signal, {
+      aborted: { get: () => aborted, enumerable: true },
+      reason: { get: () => reason, enumerable: true },
+      throwIfAborted: {
+        value() {
+          if (aborted) throw reason;
+        },
+      },
+      [Symbol.toStringTag]: { value: "AbortSignal" },
+    });
+    return {
+      signal,
+      abort(value) {
+        if (aborted) return;
+        aborted = true;
+        reason = value !== undefined ? value : abortError();
+        signal.dispatchEvent(new EventEmitter.Event("abort"));
+      },
+    };
+  }
+
+  class WritableStreamDefaultController {
+    constructor() {
+      throw illegalConstructor();
+    }
+
+    get signal() {
+      return slots(this, WritableStreamDefaultController).abortController.signal;
+    }
+
+    error(error) {
+      const state = slots(this, WritableStreamDefaultController);
+      if (state.stream[kState].state !== "writable") return;
+      writableStreamDefaultControllerError(this, error);
+    }
+
+    [kAbort](reason) {
+      const state = this[kState];
+      const result = state.abortAlgorithm(reason);
+      writableStreamDefaultControllerClearAlgorithms(state);
+      return result;
+    }
+
+    [kError]() {
+      resetQueue(this[kState]);
+    }
+  }
+
+  function setUpWritableStreamDefaultController(
+    stream,
+    controller,
+    startAlgorithm,
+    writeAlgorithm,
+    closeAlgorithm,
+    abortAlgorithm,
+    highWaterMark,
+    sizeAlgorithm
+  ) {
+    const state = {
+      brand: WritableStreamDefaultController,
+      stream,
+      queue: [],
+      queueTotalSize: 0,
+      abortController: createAbortSignal(),
+      abort(reason) {
+        this.abortController.abort(reason);
+      },
+      started: false,
+      strategySizeAlgorithm: sizeAlgorithm,
+      strategyHWM: highWaterMark,
+      writeAlgorithm,
+      closeAlgorithm,
+      abortAlgorithm,
+    };
+    controller[kState] = state;
+    stream[kState].controller = controller;
+    writableStreamUpdateBackpressure(
+      stream[kState],
+      writableStreamDefaultControllerGetDesiredSize(state) <= 0
+    );
+    Promise.resolve(startAlgorithm()).then(
+      () => {
+        state.started = true;
+        writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+      },
+      (error) => {
+        state.started = true;
+        writableStreamDealWithRejection(stream, error);
+      }
+    );
+  }
+
+  function writableStreamDefaultControllerClearAlgorithms(state) {
+    state.writeAlgorithm = undefined;
+    state.closeAlgorithm = undefined;
+    state.abortAlgorithm = undefined;
+    state.strategySizeAlgorithm = undefined;
+  }
+
+  function writableStreamDefaultControllerClose(controller) {
+    enqueueValueWithSize(controller[kState], kCloseSentinel, 0);
+    writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+  }
+
+  function writableStreamDefaultControllerGetChunkSize(controller, chunk) {
+    const state = controller[kState];
+    if (state.strategySizeAlgorithm === undefined) return 1;
+    try {
+      return state.strategySizeAlgorithm(chunk);
+    } catch (error) {
+      writableStreamDefaultControllerErrorIfNeeded(controller, error);
+      return 1;
+    }
+  }
+
+  function writableStreamDefaultControllerGetDesiredSize(state) {
+    return state.strategyHWM - state.queueTotalSize;
+  }
+
+  function writableStreamDefaultControllerWrite(controller, chunk, chunkSize) {
+    const state = controller[kState];
+    try {
+      enqueueValueWithSize(state, chunk, chunkSize);
+    } catch (error) {
+      writableStreamDefaultControllerErrorIfNeeded(controller, error);
+      return;
+    }
+    const streamState = state.stream[kState];
+    if (!writableStreamCloseQueuedOrInFlight(streamState) && streamState.state === "writable") {
+      writableStreamUpdateBackpressure(
+        streamState,
+        writableStreamDefaultControllerGetDesiredSize(state) <= 0
+      );
+    }
+    writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+  }
+
+  function writableStreamDefaultControllerAdvanceQueueIfNeeded(controller) {
+    const state = controller[kState];
+    const stream = state.stream;
+    const streamState = stream[kState];
+    if (!state.started || streamState.inFlightWriteRequest !== undefined) return;
+    if (streamState.state === "erroring") {
+      writableStreamFinishErroring(stream);
+      return;
+    }
+    if (state.queue.length === 0) return;
+    const value = state.queue[0].value;
+    if (value === kCloseSentinel) {
+      writableStreamDefaultControllerProcessClose(controller);
+    } else {
+      writableStreamDefaultControllerProcessWrite(controller, value);
+    }
+  }
+
+  function writableStreamDefaultControllerErrorIfNeeded(controller, error) {
+    if (controller[kState].stream[kState].state === "writable") {
+      writableStreamDefaultControllerError(controller, error);
+    }
+  }
+
+  function writableStreamDefaultControllerProcessClose(controller) {
+    const state = controller[kState];
+    const stream = state.stream;
+    const streamState = stream[kState];
+    streamState.inFlightCloseRequest = streamState.closeRequest;
+    streamState.closeRequest = undefined;
+    dequeueValue(state);
+    const closeAlgorithm = state.closeAlgorithm;
+    writableStreamDefaultControllerClearAlgorithms(state);
+    closeAlgorithm().then(
+      () => writableStreamFinishInFlightClose(streamState),
+      (error) => writableStreamFinishInFlightCloseWithError(stream, error)
+    );
+  }
+
+  function writableStreamDefaultControllerProcessWrite(controller, chunk) {
+    const state = controller[kState];
+    const stream = state.stream;
+    const streamState = stream[kState];
+    streamState.inFlightWriteRequest = streamState.writeRequests.shift();
+    state.writeAlgorithm(chunk).then(
+      () => {
+        writableStreamFinishInFlightWrite(streamState);
+        dequeueValue(state);
+        if (
+          !writableStreamCloseQueuedOrInFlight(streamState) &&
+          streamState.state === "writable"
+        ) {
+          writableStreamUpdateBackpressure(
+            streamState,
+            writableStreamDefaultControllerGetDesiredSize(state) <= 0
+          );
+        }
+        writableStreamDefaultControllerAdvanceQueueIfNeeded(controller);
+      },
+      (error) => {
+        if (streamState.state === "writable") writableStreamDefaultControllerClearAlgorithms(state);
+        writableStreamFinishInFlightWriteWithError(stream, error);
+      }
+    );
+  }
+
+  function writableStreamDefaultControllerError(controller, error) {
+    const state = controller[kState];
+    writableStreamDefaultControllerClearAlgorithms(state);
+    writableStreamStartErroring(state.stream, error);
+  }
+
+  // TransformStream.
+
+  class TransformStream {
+    constructor(transformer = {}, writableStrategy = {}, readableStrategy = {}) {
+      if (typeof transformer !== "object" || transformer === null) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "transformer" argument must be of type object'
+        );
+      }
+      if (transformer.readableType !== undefined) {
+        throw codeError(
+          RangeError,
+          "ERR_INVALID_ARG_VALUE",
+          `The argument 'transformer.readableType' is invalid. Received ${String(transformer.readableType)}`
+        );
+      }
+      if (transformer.writableType !== undefined) {
+        throw codeError(
+          RangeError,
+          "ERR_INVALID_ARG_VALUE",
+          `The argument 'transformer.writableType' is invalid. Received ${String(transformer.writableType)}`
+        );
+      }
+      const readableHighWaterMark = extractHighWaterMark(readableStrategy, 0);
+      const readableSizeAlgorithm = extractSizeAlgorithm(readableStrategy);
+      const writableHighWaterMark = extractHighWaterMark(writableStrategy, 1);
+      const writableSizeAlgorithm = extractSizeAlgorithm(writableStrategy);
+      const start = checkMethod(transformer, "start", "transformer");
+      const startPromise = deferred();
+      initializeTransformStream(
+        this,
+        startPromise.promise,
+        writableHighWaterMark,
+        writableSizeAlgorithm,
+        readableHighWaterMark,
+        readableSizeAlgorithm
+      );
+      setUpTransformStreamDefaultControllerFromTransformer(this, transformer);
+      try {
+        startPromise.resolve(start?.call(transformer, this[kState].controller));
+      } catch (error) {
+        startPromise.reject(error);
+        throw error;
+      }
+    }
+
+    get readable() {
+      return slots(this, TransformStream).readable;
+    }
+
+    get writable() {
+      return slots(this, TransformStream).writable;
+    }
+  }
+
+  function initializeTransformStream(
+    stream,
+    startPromise,
+    writableHighWaterMark,
+    writableSizeAlgorithm,
+    readableHighWaterMark,
+    readableSizeAlgorithm
+  ) {
+    const state = {
+      brand: TransformStream,
+      readable: undefined,
+      writable: undefined,
+      backpressure: undefined,
+      backpressureChangePromise: undefined,
+      controller: undefined,
+    };
+    stream[kState] = state;
+    const startAlgorithm = () => startPromise;
+    state.writable = createWritableStream(
+      startAlgorithm,
+      (chunk) => transformStreamDefaultSinkWriteAlgorithm(stream, chunk),
+      () => transformStreamDefaultSinkCloseAlgorithm(stream),
+      (reason) => transformStreamDefaultSinkAbortAlgorithm(stream, reason),
+      writableHighWaterMark,
+      writableSizeAlgorithm
+    );
+    state.readable = createReadableStream(
+      startAlgorithm,
+      () => transformStreamDefaultSourcePullAlgorithm(stream),
+      (reason) => transformStreamDefaultSourceCancelAlgorithm(stream, reason),
+      readableHighWaterMark,
+      readableSizeAlgorithm
+    );
+    transformStreamSetBackpressure(state, true);
+  }
+
+  function transformStreamError(stream, error) {
+    readableStreamDefaultControllerError(stream[kState].readable[kState].controller, error);
+    transformStreamErrorWritableAndUnblockWrite(stream, error);
+  }
+
+  function transformStreamErrorWritableAndUnblockWrite(stream, error) {
+    const state = stream[kState];
+    transformStreamDefaultControllerClearAlgorithms(state.controller[kState]);
+    writableStreamDefaultControllerErrorIfNeeded(state.writable[kState].controller, error);
+    transformStreamUnblockWrite(state);
+  }
+
+  function transformStreamUnblockWrite(state) {
+    if (state.backpressure) transformStreamSetBackpressure(state, false);
+  }
+
+  function transformStreamSetBackpressure(state, backpressure) {
+    if (state.backpressureChangePromise !== undefined) state.backpressureChangePromise.resolve();
+    state.backpressureChangePromise = deferred();
+    state.backpressure = backpressure;
+  }
+
+  // TransformStreamDefaultController.
+
+  class TransformStreamDefaultController {
+    constructor() {
+      throw illegalConstructor();
+    }
+
+    get desiredSize() {
+      const stream = slots(this, TransformStreamDefaultController).stream;
+      return readableStreamDefaultControllerGetDesiredSize(
+        stream[kState].readable[kState].controller[kState]
+      );
+    }
+
+    enqueue(chunk) {
+      slots(this, TransformStreamDefaultController);
+      transformStreamDefaultControllerEnqueue(this, chunk);
+    }
+
+    error(error) {
+      transformStreamError(slots(this, TransformStreamDefaultController).stream, error);
+    }
+
+    terminate() {
+      slots(this, TransformStreamDefaultController);
+      transformStreamDefaultControllerTerminate(this);
+    }
+  }
+
+  function setUpTransformStreamDefaultControllerFromTransformer(stream, transformer) {
+    const controller = Object.create(TransformStreamDefaultController.prototype);
+    const transform = checkMethod(transformer, "transform", "transformer");
+    const flush = checkMethod(transformer, "flush", "transformer");
+    const cancel = checkMethod(transformer, "cancel", "transformer");
+    let transformAlgorithm = (chunk) => {
+      try {
+        transformStreamDefaultControllerEnqueue(controller, chunk);
+        return Promise.resolve();
+      } catch (error) {
+        return Promise.reject(error);
+      }
+    };
+    if (transform !== undefined) {
+      transformAlgorithm = (chunk) => promiseCall(transform, transformer, chunk, controller);
+    }
+    controller[kState] = {
+      brand: TransformStreamDefaultController,
+      stream,
+      transformAlgorithm,
+      flushAlgorithm: () => promiseCall(flush, transformer, controller),
+      cancelAlgorithm: (reason) => promiseCall(cancel, transformer, reason),
+      finishPromise: undefined,
+    };
+    stream[kState].controller = controller;
+  }
+
+  function transformStreamDefaultControllerClearAlgorithms(state) {
+    state.transformAlgorithm = undefined;
+    state.flushAlgorithm = undefined;
+    state.cancelAlgorithm = undefined;
+  }
+
+  function transformStreamDefaultControllerEnqueue(controller, chunk) {
+    const stream = controller[kState].stream;
+    const state = stream[kState];
+    const readableController = state.readable[kState].controller;
+    if (!readableStreamDefaultControllerCanCloseOrEnqueue(readableController[kState])) {
+      throw invalidState("Unable to enqueue");
+    }
+    try {
+      readableStreamDefaultControllerEnqueue(readableController, chunk);
+    } catch (error) {
+      transformStreamErrorWritableAndUnblockWrite(stream, error);
+      throw state.readable[kState].storedError;
+    }
+    const backpressure = readableStreamDefaultControllerHasBackpressure(readableController);
+    if (backpressure !== state.backpressure) transformStreamSetBackpressure(state, true);
+  }
+
+  function transformStreamDefaultControllerPerformTransform(controller, chunk) {
+    return controller[kState].transformAlgorithm(chunk).then(undefined, (error) => {
+      transformStreamError(controller[kState].stream, error);
+      throw error;
+    });
+  }
+
+  function transformStreamDefaultControllerTerminate(controller) {
+    const stream = controller[kState].stream;
+    readableStreamDefaultControllerClose(stream[kState].readable[kState].controller);
+    transformStreamErrorWritableAndUnblockWrite(stream, invalidState("TransformStream terminated"));
+  }
+
+  function transformStreamDefaultSinkWriteAlgorithm(stream, chunk) {
+    const state = stream[kState];
+    const controller = state.controller;
+    if (state.backpressure) {
+      return state.backpressureChangePromise.promise.then(() => {
+        const writable = state.writable[kState];
+        if (writable.state === "erroring") throw writable.storedError;
+        return transformStreamDefaultControllerPerformTransform(controller, chunk);
+      });
+    }
+    return transformStreamDefaultControllerPerformTransform(controller, chunk);
+  }
+
+  function transformStreamDefaultSinkAbortAlgorithm(stream, reason) {
+    const controllerState = stream[kState].controller[kState];
+    if (controllerState.finishPromise !== undefined) return controllerState.finishPromise.promise;
+    const readable = stream[kState].readable;
+    controllerState.finishPromise = deferred();
+    const cancelPromise = controllerState.cancelAlgorithm(reason);
+    transformStreamDefaultControllerClearAlgorithms(controllerState);
+    cancelPromise.then(
+      () => {
+        if (readable[kState].state === "errored") {
+          controllerState.finishPromise.reject(readable[kState].storedError);
+        } else {
+          readableStreamDefaultControllerError(readable[kState].controller, reason);
+          controllerState.finishPromise.resolve();
+        }
+      },
+      (error) => {
+        readableStreamDefaultControllerError(readable[kState].controller, error);
+        controllerState.finishPromise.reject(error);
+      }
+    );
+    return controllerState.finishPromise.promise;
+  }
+
+  function transformStreamDefaultSinkCloseAlgorithm(stream) {
+    const controllerState = stream[kState].controller[kState];
+    if (controllerState.finishPromise !== undefined) return controllerState.finishPromise.promise;
+    const readable = stream[kState].readable;
+    controllerState.finishPromise = deferred();
+    const flushPromise = controllerState.flushAlgorithm();
+    transformStreamDefaultControllerClearAlgorithms(controllerState);
+    flushPromise.then(
+      () => {
+        if (readable[kState].state === "errored") {
+          controllerState.finishPromise.reject(readable[kState].storedError);
+        } else {
+          readableStreamDefaultControllerClose(readable[kState].controller);
+          controllerState.finishPromise.resolve();
+        }
+      },
+      (error) => {
+        readableStreamDefaultControllerError(readable[kState].controller, error);
+        controllerState.finishPromise.reject(error);
+      }
+    );
+    return controllerState.finishPromise.promise;
+  }
+
+  function transformStreamDefaultSourcePullAlgorithm(stream) {
+    const state = stream[kState];
+    transformStreamSetBackpressure(state, false);
+    return state.backpressureChangePromise.promise;
+  }
+
+  function transformStreamDefaultSourceCancelAlgorithm(stream, reason) {
+    const state = stream[kState];
+    const controllerState = state.controller[kState];
+    if (controllerState.finishPromise !== undefined) return controllerState.finishPromise.promise;
+    const writable = state.writable;
+    controllerState.finishPromise = deferred();
+    const cancelPromise = controllerState.cancelAlgorithm(reason);
+    transformStreamDefaultControllerClearAlgorithms(controllerState);
+    cancelPromise.then(
+      () => {
+        if (writable[kState].state === "errored") {
+          controllerState.finishPromise.reject(writable[kState].storedError);
+        } else {
+          writableStreamDefaultControllerErrorIfNeeded(writable[kState].controller, reason);
+          transformStreamUnblockWrite(state);
+          controllerState.finishPromise.resolve();
+        }
+      },
+      (error) => {
+        writableStreamDefaultControllerErrorIfNeeded(writable[kState].controller, error);
+        transformStreamUnblockWrite(state);
+        controllerState.finishPromise.reject(error);
+      }
+    );
+    return controllerState.finishPromise.promise;
+  }
+
+  // TextEncoder, TextDecoder and their streams.
+
+  class TextEncoder {
+    constructor() {
+      this[kState] = { brand: TextEncoder };
+    }
+
+    get encoding() {
+      slots(this, TextEncoder);
+      return "utf-8";
+    }
+
+    encode(input = "") {
+      slots(this, TextEncoder);
+      return binding.encode(String(input), "utf8");
+    }
+
+    // Encode as many characters of `source` as fit in `destination`.
+    encodeInto(source, destination) {
+      slots(this, TextEncoder);
+      if (!(destination instanceof Uint8Array)) {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "dest" argument must be an instance of Uint8Array'
+        );
+      }
+      const string = String(source);
+      let read = 0;
+      let written = 0;
+      while (read < string.length) {
+        let code = string.codePointAt(read);
+        const units = code > 0xffff ? 2 : 1;
+        // A lone surrogate is replaced.
+        if (code >= 0xd800 && code <= 0xdfff) code = 0xfffd;
+        const size = code < 0x80 ? 1 : code < 0x800 ? 2 : code < 0x10000 ? 3 : 4;
+        if (written + size > destination.length) break;
+        if (size === 1) {
+          destination[written] = code;
+        } else {
+          destination[written] = [0, 0, 0xc0, 0xe0, 0xf0][size] | (code >> (6 * (size - 1)));
+          for (let index = 1; index < size; index++) {
+            destination[written + index] = 0x80 | ((code >> (6 * (size - 1 - index))) & 0x3f);
+          }
+        }
+        read += units;
+        written += size;
+      }
+      return { read, written };
+    }
+  }
+
+  class TextEncoderStream {
+    constructor() {
+      // A high surrogate ending a chunk waits for the next one.
+      let pending = "";
+      const transform = new TransformStream({
+        transform(chunk, controller) {
+          let string = pending + String(chunk);
+          pending = "";
+          const last = string.charCodeAt(string.length - 1);
+          if (last >= 0xd800 && last <= 0xdbff) {
+            pending = string.slice(-1);
+            string = string.slice(0, -1);
+          }
+          if (string.length > 0) controller.enqueue(binding.encode(string, "utf8"));
+        },
+        flush(controller) {
+          // The replacement character.
+          if (pending.length > 0) controller.enqueue(new Uint8Array([0xef, 0xbf, 0xbd]));
+        },
+      });
+      this[kState] = { brand: TextEncoderStream, transform };
+    }
+
+    get encoding() {
+      slots(this, TextEncoderStream);
+      return "utf-8";
+    }
+
+    get readable() {
+      return slots(this, TextEncoderStream).transform.readable;
+    }
+
+    get writable() {
+      return slots(this, TextEncoderStream).transform.writable;
+    }
+  }
+
+  // The WHATWG labels of the supported encodings.
+  const decoderEncodings = {
+    "utf-8": "utf-8",
+    utf8: "utf-8",
+    "unicode-1-1-utf-8": "utf-8",
+    "utf-16le": "utf-16le",
+    "utf-16": "utf-16le",
+  };
+
+  function decoderEncoding(label) {
+    const encoding = decoderEncodings[String(label).trim().toLowerCase()];
+    if (encoding === undefined) {
+      throw codeError(
+        RangeError,
+        "ERR_ENCODING_NOT_SUPPORTED",
+        `The "${String(label)}" encoding is not supported`
+      );
+    }
+    return encoding;
+  }
+
+  // A decoder keeping the bytes of a character split between the calls made
+  // with `stream`, until the last one. The BOM is removed from the start of
+  // the text, unless `ignoreBOM`.
+  function createDecoder(encoding, fatal, ignoreBOM) {
+    const nativeEncoding = encoding === "utf-8" ? "utf8" : "utf16le";
+    let pending = new Uint8Array(0);
+    let bomSeen = ignoreBOM;
+
+    return function decode(input, stream) {
+      let bytes = input;
+      if (pending.length > 0) {
+        bytes = new Uint8Array(pending.length + input.length);
+        bytes.set(pending);
+        bytes.set(input, pending.length);
+      }
+      const length = stream ? binding.completeLength(bytes, nativeEncoding) : bytes.length;
+      pending = bytes.slice(length);
+      bytes = bytes.subarray(0, length);
+      let text = binding.decode(bytes, nativeEncoding);
+      // The lossy decoding replaces the invalid sequences, they are found
+      // back by encoding the text again.
+      if (fatal && text.includes("\ufffd")) {
+        const encoded = binding.encode(text, nativeEncoding);
+        if (
+          encoded.length !== bytes.length ||
+          encoded.some((byte, index) => byte !== bytes[index])
+        ) {
+          throw codeError(
+            TypeError,
+            "ERR_ENCODING_INVALID_ENCODED_DATA",
+            `The encoded data was not valid for encoding ${encoding}`
+          );
+        }
+      }
+      if (!bomSeen && text.length > 0) {
+        bomSeen = true;
+        if (text.charCodeAt(0) === 0xfeff) text = text.slice(1);
+      }
+      if (!stream) bomSeen = ignoreBOM;
+      return text;
+    };
+  }
+
+  class TextDecoder {
+    constructor(label = "utf-8", options = {}) {
+      const encoding = decoderEncoding(label);
+      const fatal = Boolean(options?.fatal);
+      const ignoreBOM = Boolean(options?.ignoreBOM);
+      const decode = createDecoder(encoding, fatal, ignoreBOM);
+      this[kState] = { brand: TextDecoder, decode, encoding, fatal, ignoreBOM };
+    }
+
+    get encoding() {
+      return slots(this, TextDecoder).encoding;
+    }
+
+    get fatal() {
+      return slots(this, TextDecoder).fatal;
+    }
+
+    get ignoreBOM() {
+      return slots(this, TextDecoder).ignoreBOM;
+    }
+
+    decode(input = new Uint8Array(0), options = {}) {
+      const state = slots(this, TextDecoder);
+      let bytes;
+      if (input instanceof ArrayBuffer) {
+        bytes = new Uint8Array(input);
+      } else if (ArrayBuffer.isView(input)) {
+        bytes = new Uint8Array(input.buffer, input.byteOffset, input.byteLength);
+      } else {
+        throw codeError(
+          TypeError,
+          "ERR_INVALID_ARG_TYPE",
+          'The "input" argument must be an instance of SharedArrayBuffer, ArrayBuffer or ArrayBufferView.'
+        );
+      }
+      return state.decode(bytes, Boolean(options?.stream));
+    }
+  }
+
+  function toBytes(chunk) {
+    if (chunk instanceof ArrayBuffer) return new Uint8Array(chunk);
+    if (ArrayBuffer.isView(chunk)) {
+      return new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+    }
+    throw codeError(
+      TypeError,
+      "ERR_INVALID_ARG_TYPE",
+      'The "input" argument must be an instance of ArrayBuffer or ArrayBufferView'
+    );
+  }
+
+  class TextDecoderStream {
+    constructor(label = "utf-8", options = {}) {
+      const encoding = decoderEncoding(label);
+      const fatal = Boolean(options?.fatal);
+      const ignoreBOM = Boolean(options?.ignoreBOM);
+      const decode = createDecoder(encoding, fatal, ignoreBOM);
+      const transform = new TransformStream({
+        transform(chunk, controller) {
+          const text = decode(toBytes(chunk), true);
+          if (text.length > 0) controller.enqueue(text);
+        },
+        flush(controller) {
+          const text = decode(new Uint8Array(0), false);
+          if (text.length > 0) controller.enqueue(text);
+        },
+      });
+      this[kState] = { brand: TextDecoderStream, transform, encoding, fatal, ignoreBOM };
+    }
+
+    get encoding() {
+      return slots(this, TextDecoderStream).encoding;
+    }
+
+    get fatal() {
+      return slots(this, TextDecoderStream).fatal;
+    }
+
+    get ignoreBOM() {
+      return slots(this, TextDecoderStream).ignoreBOM;
+    }
+
+    get readable() {
+      return slots(this, TextDecoderStream).transform.readable;
+    }
+
+    get writable() {
+      return slots(this, TextDecoderStream).transform.writable;
+    }
+  }
+
+  const classes = {
+    ReadableStream,
+    ReadableStreamDefaultReader,
+    ReadableStreamDefaultController,
+    WritableStream,
+    WritableStreamDefaultWriter,
+    WritableStreamDefaultController,
+    TransformStream,
+    TransformStreamDefaultController,
+    ByteLengthQueuingStrategy,
+    CountQueuingStrategy,
+    TextEncoder,
+    TextDecoder,
+    TextEncoderStream,
+    TextDecoderStream,
+  };
+  for (const [name, Class] of Object.entries(classes)) {
+    Object.defineProperty(Class.prototype, Symbol.toStringTag, {
+      value: name,
+      configurable: true,
+    });
+  }
+  return classes;
+});
+//# sourceURL=node:internal/web_streams
@@ -0,0 +1,164 @@
+//! Small helpers to reach the javascript builtins from Rust.
+//!
+//! The C API of JavaScriptCore doesn't expose everything (symbols as property
+//! names, binding, constructors...), these helpers go through the builtins
+//! themselves (`Reflect`, `Function.prototype.bind`...).
+//!
+//! The builtins are captured once at startup by `js/primordials.js`, before
+//! any script runs, so the scripts replacing them don't change the runtime.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+
+/// Get the builtins captured at startup, keyed by their dotted path. The
+/// script is evaluated on the first call, see `init`.
+fn primordials(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let primordials = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    primordials
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/primordials.js"), 1)
+                .expect("cannot evaluate the primordials")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(context, None, &[context.get_global_object().into()])
+                .expect("cannot capture the primordials")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Capture the builtins, called before the evaluation of the main script.
+pub fn init(context: &JSContext) {
+    primordials(context);
+}
+
+/// Get a builtin following its dotted path, like `Reflect.construct` or
+/// `Symbol.asyncIterator`. The unknown paths give undefined.
+pub fn global_property(context: &JSContext, path: &str) -> Result<JSValue, JSValue> {
+    primordials(context).get_property(context, path)
+}
+
+/// Call the builtin function at `path` with `this` set to undefined.
+pub fn call_global(
+    context: &JSContext,
+    path: &str,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    global_property(context, path)?
+        .to_object(context)?
+        .call_as_function(context, None, arguments)
+}
+
+/// Call `object[method](...arguments)`.
+pub fn call_method(
+    context: &JSContext,
+    object: &JSObject,
+    method: &str,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    object
+        .get_property(context, method)?
+        .to_object(context)?
+        .call_as_function(context, Some(object), arguments)
+}
+
+/// Get the elements of an array, or of an iterable through `Array.from`.
+pub fn array_values(context: &JSContext, iterable: &JSValue) -> Result<Vec<JSValue>, JSValue> {
+    let array = call_global(context, "Array.from", &[iterable.clone()])?.to_object(context)?;
+    let length = array.get_property(context, "length")?.to_number(context)? as usize;
+    (0..length)
+        .map(|index| array.get_property(context, &index.to_string()))
+        .collect()
+}
+
+/// `Object.prototype.hasOwnProperty.call(object, key)`.
+pub fn has_own_property(context: &JSContext, object: &JSValue, key: &str) -> Result<bool, JSValue> {
+    let object = object.to_object(context)?;
+    Ok(global_property(context, "Object.prototype.hasOwnProperty")?
+        .to_object(context)?
+        .call_as_function(context, Some(&object), &[JSValue::string(context, key)])?
+        .to_bool(context))
+}
+
+/// Set `object[Symbol[symbol]] = value`. Symbols can't be used as property
+/// names directly, so it goes through `Reflect.set`.
+pub fn set_symbol_property(
+    context: &JSContext,
+    object: JSValue,
+    symbol: &str,
+    value: JSValue,
+) -> Result<(), JSValue> {
+    let symbol = global_property(context, &format!("Symbol.{symbol}"))?;
+    call_global(context, "Reflect.set", &[object, symbol, value])?;
+    Ok(())
+}
+
+/// Bind a native callback to `this`, through `Function.prototype.bind`. It's
+/// the way to give a state to a callback: the state lives in the private data
+/// of `this`.
+pub fn bind(context: &JSContext, callback: JSValue, this: JSValue) -> JSValue {
+    let function = callback.to_object(context).unwrap();
+    function
+        .get_property(context, "bind")
+        .unwrap()
+        .to_object(context)
+        .unwrap()
+        .call_as_function(context, Some(&function), &[this])
+        .unwrap()
+}
+
+/// Call `new constructor(...arguments)` through `Reflect.construct`, for the
+/// builtin constructor at `path`.
+pub fn construct(
+    context: &JSContext,
+    constructor: &str,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    construct_with(context, global_property(context, constructor)?, arguments)
+}
+
+/// Same as `construct` for a constructor that isn't a global, like the
+/// classes of the javascript modules.
+pub fn construct_with(
+    context: &JSContext,
+    constructor: JSValue,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let arguments = JSObject::new_array(context, arguments)?;
+    call_global(
+        context,
+        "Reflect.construct",
+        &[constructor, arguments.into()],
+    )
+}
+
+/// Create a `Date` from a number of milliseconds since the epoch.
+pub fn new_date(context: &JSContext, milliseconds: f64) -> Result<JSValue, JSValue> {
+    construct(context, "Date", &[JSValue::number(context, milliseconds)])
+}
+
+/// Build the `{ value, done }` object of an iterator.
+pub fn iterator_result(context: &JSContext, value: Option<JSValue>) -> JSValue {
+    let mut result = JSObject::<JSObjectGeneric>::new(context);
+    let done = value.is_none();
+    result
+        .set_property(
+            context,
+            "value",
+            value.unwrap_or_else(|| JSValue::undefined(context)),
+        )
+        .unwrap();
+    result
+        .set_property(context, "done", JSValue::boolean(context, done))
+        .unwrap();
+    result.into()
+}
+
+/// Compare two values with the `Object.is` semantic, used to find a listener
+/// to remove.
+pub fn same_value(context: &JSContext, a: JSValue, b: JSValue) -> bool {
+    call_global(context, "Object.is", &[a, b]).map_or(false, |same| same.to_bool(context))
+}
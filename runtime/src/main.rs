@@ -3,14 +3,35 @@ use rusty_jsc::JSContext;
 use std::fs::read_to_string;
 use tokio::sync::oneshot::channel;
 
+mod colors;
 mod console;
+mod encoding;
+mod errors;
 mod event_loop;
+mod events;
 mod fs;
+mod fs_callback;
+mod fs_cp;
+mod fs_dir;
+mod fs_dirent;
 mod fs_promise;
+mod fs_read_stream;
+mod fs_stats;
+mod fs_sync;
+mod fs_watch;
 mod fs_write_stream;
+mod inspect;
+mod internals;
+mod js_utils;
 mod modules;
+mod options;
+mod process;
+mod stdio;
+mod stream;
 mod timeout_api;
+mod types;
 mod util;
+mod web_streams;
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
 async fn main() {
@@ -28,6 +49,8 @@ async fn main() {
         // block any asynchronous calls from event loop during main evaluation.
         let _ = get_hold().lock().await;
         if let Err(err) = context.evaluate_script(&script, 1) {
+            // Printed after the queued writes of the script.
+            stdio::flush();
             println!("{}", err.to_js_string(&context).unwrap());
         }
     }
@@ -35,4 +58,6 @@ async fn main() {
     let (sender, receiver) = channel();
     event_loop::append(event_loop::Action::Stop(sender));
     receiver.await.unwrap();
+    // The writes on the pipes are done before exiting.
+    stdio::flush();
 }
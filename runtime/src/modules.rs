@@ -6,7 +6,9 @@ use maybe_static::{maybe_static, maybe_static_unsafe};
 use rusty_jsc::{JSClass, JSContext, JSObject, JSValue};
 use rusty_jsc_macros::callback;
 
-use crate::{console, timeout_api};
+use crate::{
+    console, errors::code_error, events, js_utils, process, stream, timeout_api, util, web_streams,
+};
 
 /*
 
@@ -139,18 +141,56 @@ fn require(
                 .to_string_utf8()
                 .unwrap();
 
-            if path == "node:fs/promises" {
+            if path == "node:fs/promises" || path == "fs/promises" {
                 return Ok(crate::fs_promise::fs_promise(&context).into());
             }
 
-            if path == "node:fs" {
+            if path == "node:fs" || path == "fs" {
                 return Ok(crate::fs::fs(&context).into());
             }
 
+            if path == "node:events" || path == "events" {
+                return Ok(events::events(&context).into());
+            }
+
+            if path == "node:stream/web" || path == "stream/web" {
+                return Ok(web_streams::web_streams(&context).into());
+            }
+
+            if path == "node:stream/promises" || path == "stream/promises" {
+                return Ok(stream::stream_promises(&context).into());
+            }
+
+            if path == "node:stream" || path == "stream" {
+                return Ok(stream::stream(&context).into());
+            }
+
+            if path == "node:process" || path == "process" {
+                return Ok(process::process(&context).into());
+            }
+
+            if path == "node:util" || path == "util" {
+                return Ok(util::util(&context).into());
+            }
+
             // TODO, modify the path in order to behave like node. For example, fs is
             // internal (I guess), if you can look for a foreign library in the node_modules
             // file or in the global folder. The path can change but point to the same
             // file depending of the caller position. etc.
+            //
+            // The cache key is the real path of the file, so `./a.js`, `a.js` or a
+            // symbolic link to it are loaded only once.
+            let path = match std::fs::canonicalize(&path) {
+                Ok(real_path) => real_path.to_string_lossy().into_owned(),
+                Err(_) => {
+                    return Err(code_error(
+                        &context,
+                        "Error",
+                        "MODULE_NOT_FOUND",
+                        &format!("Cannot find module '{path}'"),
+                    ))
+                }
+            };
             if let Some(global_object) = global_objects.get(&path) {
                 let module: JSObject = global_object
                     .get_property(&context, "module")
@@ -221,8 +261,12 @@ fn internal_init(context: &mut JSContext) {
         .unwrap();
 
     // init all basics
+    js_utils::init(context);
+    process::init(context);
     console::init(context);
     timeout_api::init(context);
+    events::init(context);
+    web_streams::init(context);
 }
 
 pub fn init(context: &mut JSContext) {
@@ -0,0 +1,185 @@
+//! Helpers to read the arguments and the `options` objects given to the
+//! javascript API calls.
+//!
+//! Most of NodeJS functions accept an optional object as last parameter, or a
+//! string when the only option that makes sense is the encoding. These helpers
+//! read them defensively: an undefined property is considered as missing.
+
+use rusty_jsc::{JSContext, JSValue};
+
+use crate::errors::{invalid_arg_type, invalid_arg_value};
+
+/// Get the property `name` of an optional options object. Returns None if
+/// `options` is not an object or if the property is undefined or null.
+pub fn get_option(context: &JSContext, options: Option<&JSValue>, name: &str) -> Option<JSValue> {
+    let options = options?;
+    if !options.is_object(context) {
+        return None;
+    }
+    let value = options
+        .to_object(context)
+        .unwrap()
+        .get_property(context, name)
+        .unwrap();
+    if value.is_undefined(context) || value.is_null(context) {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Read a boolean option, falsy values are considered as false.
+pub fn get_bool_option(context: &JSContext, options: Option<&JSValue>, name: &str) -> Option<bool> {
+    get_option(context, options, name).map(|value| value.to_bool(context))
+}
+
+/// Read a numeric option. Returns an `ERR_INVALID_ARG_TYPE` if the option
+/// is set but isn't a number.
+pub fn get_number_option(
+    context: &JSContext,
+    options: Option<&JSValue>,
+    name: &str,
+) -> Result<Option<f64>, JSValue> {
+    match get_option(context, options, name) {
+        Some(value) if value.is_number(context) => Ok(Some(value.to_number(context).unwrap())),
+        Some(_) => Err(invalid_arg_type(
+            context,
+            &format!("options.{name}"),
+            "of type number",
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Read a string option. Returns an `ERR_INVALID_ARG_TYPE` if the option is
+/// set but isn't a string.
+pub fn get_string_option(
+    context: &JSContext,
+    options: Option<&JSValue>,
+    name: &str,
+) -> Result<Option<String>, JSValue> {
+    match get_option(context, options, name) {
+        Some(value) if value.is_string(context) => {
+            Ok(Some(value.to_js_string(context).unwrap().to_string()))
+        }
+        Some(_) => Err(invalid_arg_type(
+            context,
+            &format!("options.{name}"),
+            "of type string",
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Read the encoding of an API that accepts `options` as an object or as a
+/// string, like `readdir(path, 'utf8')`.
+pub fn get_encoding(
+    context: &JSContext,
+    options: Option<&JSValue>,
+) -> Result<Option<String>, JSValue> {
+    match options {
+        Some(value) if value.is_string(context) => {
+            Ok(Some(value.to_js_string(context).unwrap().to_string()))
+        }
+        _ => get_string_option(context, options, "encoding"),
+    }
+}
+
+/// Read a mandatory path argument at `index`.
+pub fn path_argument(
+    context: &JSContext,
+    arguments: &[JSValue],
+    index: usize,
+    name: &str,
+) -> Result<String, JSValue> {
+    match arguments.get(index) {
+        Some(value) if value.is_string(context) => {
+            let path = value.to_js_string(context).unwrap().to_string();
+            if path.contains('\0') {
+                return Err(invalid_arg_value(
+                    context,
+                    name,
+                    "must be a string without null bytes",
+                ));
+            }
+            Ok(path)
+        }
+        _ => Err(invalid_arg_type(context, name, "of type string")),
+    }
+}
+
+/// Parse a file mode given as a number or as an octal string (`'755'`).
+/// Returns `default` if the mode is missing.
+pub fn parse_mode(
+    context: &JSContext,
+    value: Option<&JSValue>,
+    default: u32,
+) -> Result<u32, JSValue> {
+    let value = match value {
+        Some(value) if !value.is_undefined(context) && !value.is_null(context) => value,
+        _ => return Ok(default),
+    };
+    let mode = if value.is_number(context) {
+        let mode = value.to_number(context).unwrap();
+        if mode.fract() != 0.0 || !(0.0..=u32::MAX as f64).contains(&mode) {
+            None
+        } else {
+            Some(mode as u32)
+        }
+    } else if value.is_string(context) {
+        u32::from_str_radix(&value.to_js_string(context).unwrap().to_string(), 8).ok()
+    } else {
+        return Err(invalid_arg_type(context, "mode", "of type number"));
+    };
+    mode.ok_or_else(|| {
+        invalid_arg_value(
+            context,
+            "mode",
+            "must be a 32-bit unsigned integer or an octal string",
+        )
+    })
+}
+
+/// Parse the `flags` of `open`, given as a string (`'r'`, `'w+'`, `'ax'`...)
+/// or as a number combining the `O_*` constants. Returns the `default`
+/// flags if it's missing.
+pub fn parse_flags(
+    context: &JSContext,
+    value: Option<&JSValue>,
+    default: &str,
+) -> Result<libc::c_int, JSValue> {
+    let flags = match value {
+        Some(value) if value.is_number(context) => {
+            return Ok(value.to_number(context).unwrap() as libc::c_int)
+        }
+        Some(value) if value.is_string(context) => value.to_js_string(context).unwrap().to_string(),
+        Some(value) if !value.is_undefined(context) && !value.is_null(context) => {
+            return Err(invalid_arg_value(context, "flags", "is invalid"))
+        }
+        _ => default.to_string(),
+    };
+    use libc::{O_APPEND, O_CREAT, O_EXCL, O_RDONLY, O_RDWR, O_SYNC, O_TRUNC, O_WRONLY};
+    Ok(match flags.as_str() {
+        "r" => O_RDONLY,
+        "rs" | "sr" => O_RDONLY | O_SYNC,
+        "r+" => O_RDWR,
+        "rs+" | "sr+" => O_RDWR | O_SYNC,
+        "w" => O_TRUNC | O_CREAT | O_WRONLY,
+        "wx" | "xw" => O_TRUNC | O_CREAT | O_WRONLY | O_EXCL,
+        "w+" => O_TRUNC | O_CREAT | O_RDWR,
+        "wx+" | "xw+" => O_TRUNC | O_CREAT | O_RDWR | O_EXCL,
+        "a" => O_APPEND | O_CREAT | O_WRONLY,
+        "ax" | "xa" => O_APPEND | O_CREAT | O_WRONLY | O_EXCL,
+        "as" | "sa" => O_APPEND | O_CREAT | O_WRONLY | O_SYNC,
+        "a+" => O_APPEND | O_CREAT | O_RDWR,
+        "ax+" | "xa+" => O_APPEND | O_CREAT | O_RDWR | O_EXCL,
+        "as+" | "sa+" => O_APPEND | O_CREAT | O_RDWR | O_SYNC,
+        _ => {
+            return Err(invalid_arg_value(
+                context,
+                "flags",
+                &format!("is invalid. Received '{flags}'"),
+            ))
+        }
+    })
+}
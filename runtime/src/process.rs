@@ -0,0 +1,39 @@
+//! The `process` global, which is also the `node:process` module. It gives
+//! the standard outputs, `process.stdout` and `process.stderr`, see the
+//! stdio module.
+//!
+//! The object is shared by the main script and the required files.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+
+use crate::stdio::{stderr, stdout};
+
+/// Get the `process` object, created on the first call.
+pub fn process(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let process = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    process
+        .get_or_insert_with(|| {
+            let mut process = JSObject::<JSObjectGeneric>::new(context);
+            process
+                .set_property(context, "stdout", stdout(context).into())
+                .unwrap();
+            process
+                .set_property(context, "stderr", stderr(context).into())
+                .unwrap();
+            let process: JSValue = process.into();
+            process.into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Define the global `process`.
+pub fn init(context: &mut JSContext) {
+    let process = process(context);
+    let global = &mut context.get_global_object();
+    global
+        .set_property(context, "process", process.into())
+        .unwrap();
+}
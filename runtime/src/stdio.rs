@@ -0,0 +1,290 @@
+//! The standard outputs of the process: `process.stdout` and
+//! `process.stderr`, the outputs of the global console.
+//!
+//! They are Writables of `node:stream`, written in javascript
+//! (`js/stdio.js`) on top of the native writes of this module. As NodeJS on
+//! POSIX, the writes on a terminal or a file are synchronous, the ones on a
+//! pipe or a socket are asynchronous: they are queued and done in order by a
+//! writer thread, so a slow reader doesn't block the javascript thread.
+//!
+//! The queue is shared by both outputs and a synchronous write waits for it
+//! to be empty, so the writes reach the outputs in the order they were made,
+//! even when stdout and stderr are the same pipe. The queue is also emptied
+//! before the process exits, see `flush`.
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    mem::{ManuallyDrop, MaybeUninit},
+    os::fd::FromRawFd,
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+};
+
+use maybe_static::{maybe_static, maybe_static_unsafe};
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+use tokio::sync::oneshot;
+
+use crate::{
+    colors::{color_depth, is_tty},
+    encoding::{data_argument, Encoding},
+    errors::{invalid_arg_type, system_error},
+    event_loop::{self, get_hold, Action},
+    js_utils::construct_with,
+    stream::stream,
+};
+
+/// A write waiting in the queue.
+struct QueuedWrite {
+    fd: i32,
+    bytes: Vec<u8>,
+    done: oneshot::Sender<io::Result<()>>,
+}
+
+/// The queue of the asynchronous writes, and the number of writes not done
+/// yet with its condition variable, notified when it goes down.
+struct Queue {
+    sender: Sender<QueuedWrite>,
+    pending: Arc<(Mutex<usize>, Condvar)>,
+}
+
+/// Get the queue, its writer thread is started on the first call.
+fn queue() -> &'static Queue {
+    maybe_static!(Queue, || {
+        let (sender, receiver) = mpsc::channel::<QueuedWrite>();
+        let pending = Arc::new((Mutex::new(0), Condvar::new()));
+        let counter = pending.clone();
+        thread::spawn(move || {
+            for write in receiver {
+                let _ = write.done.send(write_all(write.fd, &write.bytes));
+                let (count, drained) = &*counter;
+                *count.lock().unwrap() -= 1;
+                drained.notify_all();
+            }
+        });
+        Queue { sender, pending }
+    })
+}
+
+/// Write all the bytes on a file descriptor of the process, waiting for it
+/// to be writable if it's non-blocking.
+fn write_all(fd: i32, mut bytes: &[u8]) -> io::Result<()> {
+    // The file descriptor belongs to the process, it isn't closed.
+    let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    while !bytes.is_empty() {
+        match file.write(bytes) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => bytes = &bytes[written..],
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                let mut poll = libc::pollfd {
+                    fd,
+                    events: libc::POLLOUT,
+                    revents: 0,
+                };
+                unsafe { libc::poll(&mut poll, 1, -1) };
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+/// Wait for the queued writes to be done. Called before a synchronous write
+/// and before the process exits.
+pub fn flush() {
+    let (count, drained) = &*queue().pending;
+    let mut count = count.lock().unwrap();
+    while *count > 0 {
+        count = drained.wait(count).unwrap();
+    }
+}
+
+/// An asynchronous write waiting for the writer, see `exec_stdio_write`.
+pub struct StdioWriteAction {
+    done: oneshot::Receiver<io::Result<()>>,
+    /// Called once the write is done, with the error if it failed.
+    callback: JSObject<JSProtected>,
+}
+
+/// Wait for a queued write, and call back the stream.
+pub async fn exec_stdio_write(action: StdioWriteAction) {
+    let result = action.done.await;
+    let _hold = get_hold().lock().await;
+    let context = action.callback.context();
+    let arguments = match result {
+        Ok(Err(error)) => vec![system_error(&context, &error, "write", None, None)],
+        _ => vec![],
+    };
+    let _ = action.callback.call_as_function(&context, None, &arguments);
+}
+
+/// The `fd` and `chunk` arguments of the writes, the chunk is a string in
+/// the given encoding or an `ArrayBufferView`.
+fn write_arguments(context: &JSContext, arguments: &[JSValue]) -> Result<(i32, Vec<u8>), JSValue> {
+    let fd = match arguments.first() {
+        Some(fd) if fd.is_number(context) => fd.to_number(context)? as i32,
+        _ => return Err(invalid_arg_type(context, "fd", "of type number")),
+    };
+    let encoding = match arguments.get(2) {
+        Some(encoding) if encoding.is_string(context) => {
+            Encoding::parse(context, &encoding.to_js_string(context)?.to_string())?
+        }
+        _ => None,
+    };
+    Ok((fd, data_argument(context, arguments.get(1), encoding)?))
+}
+
+#[callback]
+/// `binding.writeSync(fd, chunk, encoding)`, once the queued writes are
+/// done. Throws the error of the write.
+fn write_sync(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let (fd, bytes) = write_arguments(&context, arguments)?;
+    flush();
+    write_all(fd, &bytes).map_err(|error| system_error(&context, &error, "write", None, None))?;
+    Ok(JSValue::undefined(&context))
+}
+
+#[callback]
+/// `binding.write(fd, chunk, encoding, callback)`, queue a write and return
+/// its number of bytes. `callback` is called on the event loop once the
+/// write is done, with the error if it failed.
+fn write(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let (fd, bytes) = write_arguments(&context, arguments)?;
+    let callback = match arguments.get(3) {
+        Some(callback) if callback.is_object(&context) => {
+            callback.clone().into_protected_object(&context)
+        }
+        _ => return Err(invalid_arg_type(&context, "callback", "of type function")),
+    };
+    let length = bytes.len();
+    let (done, receiver) = oneshot::channel();
+    let queue = queue();
+    *queue.pending.0.lock().unwrap() += 1;
+    queue
+        .sender
+        .send(QueuedWrite { fd, bytes, done })
+        .expect("the writer of the standard outputs stopped");
+    event_loop::append(Action::StdioWrite(StdioWriteAction {
+        done: receiver,
+        callback,
+    }));
+    Ok(JSValue::number(&context, length as f64))
+}
+
+#[callback]
+/// `binding.colorDepth()`, see `colors::color_depth`.
+fn color_depth_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    _arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    Ok(JSValue::number(&context, color_depth() as f64))
+}
+
+/// The type of an output, as NodeJS names it: 'tty' for a terminal, 'pipe'
+/// for a pipe or a socket and 'fs' for the rest, the files and the devices.
+fn output_type(fd: i32) -> &'static str {
+    if is_tty(fd) {
+        return "tty";
+    }
+    let mut stat = MaybeUninit::<libc::stat>::uninit();
+    if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } == 0 {
+        let format = unsafe { stat.assume_init() }.st_mode & libc::S_IFMT;
+        if format == libc::S_IFIFO || format == libc::S_IFSOCK {
+            return "pipe";
+        }
+    }
+    "fs"
+}
+
+/// Get the class of the standard outputs. The script is evaluated on the
+/// first call.
+fn stdio_class(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let class = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    class
+        .get_or_insert_with(|| {
+            let mut binding = JSObject::<JSObjectGeneric>::new(context);
+            macro_rules! method {
+                ($( $name: literal => $callback: ident ),*) => {
+                    $(
+                    binding
+                        .set_property(context, $name, JSValue::callback(context, Some($callback)))
+                        .unwrap();
+                    )*
+                };
+            }
+            method!(
+                "write" => write,
+                "writeSync" => write_sync,
+                "colorDepth" => color_depth_callback
+            );
+            context
+                .evaluate_script(include_str!("js/stdio.js"), 1)
+                .expect("cannot evaluate the standard outputs")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(context, None, &[stream(context).into(), binding.into()])
+                .expect("cannot create the class of the standard outputs")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Create the Writable of an output of the process.
+fn output(context: &JSContext, fd: i32) -> JSObject {
+    construct_with(
+        context,
+        stdio_class(context).into(),
+        &[
+            JSValue::number(context, fd as f64),
+            JSValue::string(context, output_type(fd)),
+        ],
+    )
+    .expect("cannot create a standard output")
+    .to_object(context)
+    .unwrap()
+}
+
+/// Get `process.stdout`, created on the first call.
+pub fn stdout(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let stdout = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    stdout
+        .get_or_insert_with(|| {
+            let stdout: JSValue = output(context, libc::STDOUT_FILENO).into();
+            stdout.into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Get `process.stderr`, created on the first call.
+pub fn stderr(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let stderr = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    stderr
+        .get_or_insert_with(|| {
+            let stderr: JSValue = output(context, libc::STDERR_FILENO).into();
+            stderr.into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
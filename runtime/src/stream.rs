@@ -0,0 +1,172 @@
+//! The `node:stream` and `node:stream/promises` modules: `Readable`,
+//! `Writable`, `Duplex`, `Transform`, `PassThrough`, `pipeline` and
+//! `finished`.
+//!
+//! As `node:events`, the module is written in javascript (`js/stream.js`) so
+//! user classes can extend the streams, and evaluated once. The conversions
+//! between strings and bytes are given to the script by a native binding,
+//! built on `encoding`, and the helpers shared with the other scripts by
+//! `internals`. The file streams of `fs` extend these classes, and
+//! `Readable.toWeb`/`fromWeb` convert them to and from the WHATWG streams of
+//! `web_streams`.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{
+    encoding::{bytes_to_value, is_array_buffer_view, view_bytes, Encoding},
+    errors::invalid_arg_type,
+    events::events,
+    internals::internals,
+    web_streams::web_streams,
+};
+
+/// Read the encoding argument of a binding call, `'buffer'` means UTF-8.
+fn encoding_argument(context: &JSContext, value: Option<&JSValue>) -> Result<Encoding, JSValue> {
+    match value {
+        Some(value) if value.is_string(context) => {
+            let name = value.to_js_string(context)?.to_string();
+            Ok(Encoding::parse(context, &name)?.unwrap_or(Encoding::Utf8))
+        }
+        _ => Ok(Encoding::Utf8),
+    }
+}
+
+/// Read the bytes argument of a binding call.
+fn bytes_argument(context: &JSContext, value: Option<&JSValue>) -> Result<Vec<u8>, JSValue> {
+    match value {
+        Some(value) if is_array_buffer_view(context, value) => Ok(view_bytes(context, value)),
+        _ => Err(invalid_arg_type(
+            context,
+            "chunk",
+            "an instance of Buffer, TypedArray, or DataView",
+        )),
+    }
+}
+
+#[callback]
+/// `binding.encode(string, encoding)`, gives an `Uint8Array`.
+fn encode(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let string = match arguments.first() {
+        Some(string) => string.to_js_string(&context)?.to_string(),
+        None => String::new(),
+    };
+    let encoding = encoding_argument(&context, arguments.get(1))?;
+    Ok(bytes_to_value(&context, encoding.encode(&string), None))
+}
+
+#[callback]
+/// `binding.decode(bytes, encoding)`, gives a string.
+fn decode(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let bytes = bytes_argument(&context, arguments.first())?;
+    let encoding = encoding_argument(&context, arguments.get(1))?;
+    Ok(JSValue::string(&context, encoding.decode(&bytes)))
+}
+
+#[callback]
+/// `binding.completeLength(bytes, encoding)`, the number of bytes that can
+/// be decoded without splitting a character, see `Encoding::complete_len`.
+fn complete_length(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let bytes = bytes_argument(&context, arguments.first())?;
+    let encoding = encoding_argument(&context, arguments.get(1))?;
+    Ok(JSValue::number(
+        &context,
+        encoding.complete_len(&bytes) as f64,
+    ))
+}
+
+#[callback]
+/// `binding.normalize(encoding)`, the NodeJS name of an encoding. Throws
+/// for an unknown encoding.
+fn normalize(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let name = match arguments.first() {
+        Some(name) if name.is_string(&context) => name.to_js_string(&context)?.to_string(),
+        _ => return Err(invalid_arg_type(&context, "encoding", "of type string")),
+    };
+    let normalized = match Encoding::parse(&context, &name)? {
+        Some(encoding) => encoding.name(),
+        None => "buffer",
+    };
+    Ok(JSValue::string(&context, normalized))
+}
+
+/// The native functions given to the script, also used by the text streams
+/// of `web_streams`.
+pub fn binding(context: &JSContext) -> JSValue {
+    let mut binding = JSObject::<JSObjectGeneric>::new(context);
+    macro_rules! method {
+        ($( $name: literal => $callback: ident ),*) => {
+            $(
+            binding
+                .set_property(context, $name, JSValue::callback(context, Some($callback)))
+                .unwrap();
+            )*
+        };
+    }
+    method!(
+        "encode" => encode,
+        "decode" => decode,
+        "completeLength" => complete_length,
+        "normalize" => normalize
+    );
+    binding.into()
+}
+
+/// Get the `Stream` constructor, which is also the module. The script is
+/// evaluated on the first call.
+pub fn stream(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let module = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    module
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/stream.js"), 1)
+                .expect("cannot evaluate the stream module")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(
+                    context,
+                    None,
+                    &[
+                        events(context).into(),
+                        binding(context),
+                        web_streams(context).into(),
+                        internals(context).into(),
+                    ],
+                )
+                .expect("cannot initialize the stream module")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Get the `node:stream/promises` module.
+pub fn stream_promises(context: &JSContext) -> JSObject {
+    stream(context)
+        .get_property(context, "promises")
+        .unwrap()
+        .to_object(context)
+        .unwrap()
+}
@@ -5,7 +5,10 @@ use rusty_jsc::{JSContext, JSObject, JSProtected, JSValue};
 use rusty_jsc_macros::callback;
 use tokio::sync::oneshot::{Receiver, Sender};
 
-use crate::event_loop::{self, get_hold, Action};
+use crate::{
+    errors::print_uncaught,
+    event_loop::{self, get_hold, Action},
+};
 
 #[derive(Default)]
 struct TimeoutCancelers {
@@ -85,12 +88,13 @@ pub async fn exec_timeout(action: TimeoutAction) {
         _ = tokio::time::sleep(action.time) => {},
         _ = action.cancel_receiver => return,
     }
-    let _ = get_hold().lock().await;
+    let _hold = get_hold().lock().await;
     get_timeout_cancelers().remove(action.index);
-    action
-        .callback
-        .call_as_function(&action.callback.context(), None, &[])
-        .unwrap();
+    let context = action.callback.context();
+    // An error thrown by the callback is uncaught, as in `events::emit`.
+    if let Err(err) = action.callback.call_as_function(&context, None, &[]) {
+        print_uncaught(&context, &err);
+    }
 }
 
 pub fn init(context: &mut JSContext) {
@@ -0,0 +1,218 @@
+//! `util.types`: the checks of the builtin types, like `isDate` or
+//! `isUint8Array`.
+//!
+//! The C API of JavaScriptCore doesn't expose the internal type of the
+//! objects. It is checked with a builtin method which throws if its receiver
+//! isn't of the type, like `Map.prototype.has` for the maps, or read from
+//! `Object.prototype.toString` for the types without such method (the
+//! iterators, the promises, the arguments...).
+//!
+//! The proxies can't be told apart from their target, `isProxy` is always
+//! false, as `isExternal`, `isKeyObject` and `isCryptoKey` since there are no
+//! externals or crypto module.
+
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSValue};
+use rusty_jsc_macros::callback;
+
+use crate::{
+    inspect::{apply, builtin_getter, to_string, type_tag},
+    js_utils::{call_global, global_property, same_value},
+};
+
+/// Whether `value` is an object accepted as receiver by the builtin method
+/// at `path`.
+fn checked(context: &JSContext, value: &JSValue, path: &str) -> bool {
+    value.is_object(context) && apply(context, path, value, &[]).is_ok()
+}
+
+/// Whether `value` is an object accepted as receiver by the getter `key` of
+/// the builtin prototype at `path`.
+fn checked_getter(context: &JSContext, value: &JSValue, path: &str, key: &str) -> bool {
+    value.is_object(context)
+        && builtin_getter(context, path, JSValue::string(context, key), value).is_ok()
+}
+
+/// Whether `value` is an object whose `Object.prototype.toString` tag is
+/// one of `tags`.
+fn tagged(context: &JSContext, value: &JSValue, tags: &[&str]) -> Result<bool, JSValue> {
+    Ok(value.is_object(context) && tags.contains(&type_tag(context, value)?.as_str()))
+}
+
+/// The class of a typed array, like `Uint8Array`, None for the other values:
+/// the getter of the `Symbol.toStringTag` of the typed arrays gives it.
+fn typed_array_class(context: &JSContext, value: &JSValue) -> Result<Option<String>, JSValue> {
+    if !value.is_object(context) {
+        return Ok(None);
+    }
+    let class = builtin_getter(
+        context,
+        "TypedArray.prototype",
+        global_property(context, "Symbol.toStringTag")?,
+        value,
+    )?;
+    if !class.is_string(context) {
+        return Ok(None);
+    }
+    Ok(Some(to_string(context, &class)?))
+}
+
+fn is_typed_array_of(context: &JSContext, value: &JSValue, class: &str) -> Result<bool, JSValue> {
+    Ok(typed_array_class(context, value)?.as_deref() == Some(class))
+}
+
+fn is_function(context: &JSContext, value: &JSValue, tags: &[&str]) -> Result<bool, JSValue> {
+    Ok(tagged(context, value, tags)? && checked(context, value, "Function.prototype.toString"))
+}
+
+fn is_array_buffer(context: &JSContext, value: &JSValue) -> bool {
+    checked_getter(context, value, "ArrayBuffer.prototype", "byteLength")
+}
+
+/// SharedArrayBuffer is only defined when JavaScriptCore enables it.
+fn is_shared_array_buffer(context: &JSContext, value: &JSValue) -> Result<bool, JSValue> {
+    Ok(
+        !global_property(context, "SharedArrayBuffer")?.is_undefined(context)
+            && checked_getter(context, value, "SharedArrayBuffer.prototype", "byteLength"),
+    )
+}
+
+fn is_boxed_primitive(context: &JSContext, value: &JSValue) -> bool {
+    ["Number", "String", "Boolean", "BigInt", "Symbol"]
+        .iter()
+        .any(|type_name| checked(context, value, &format!("{type_name}.prototype.valueOf")))
+}
+
+/// Define the callbacks of the checks, given the value as `$value`, and
+/// `types` making the `util.types` object.
+macro_rules! predicates {
+    ($(
+        $name: literal => $callback: ident |$context: ident, $value: ident| $check: expr
+    ),* $(,)?) => {
+        $(
+        #[callback]
+        fn $callback(
+            context: JSContext,
+            _function: JSObject,
+            _this: JSObject,
+            arguments: &[JSValue],
+        ) -> Result<JSValue, JSValue> {
+            let undefined = JSValue::undefined(&context);
+            let $value = arguments.first().unwrap_or(&undefined);
+            let $context = &context;
+            let result: bool = $check;
+            Ok(JSValue::boolean(&context, result))
+        }
+        )*
+
+        /// Create the `util.types` object.
+        pub fn types(context: &JSContext) -> JSObject {
+            let mut types = JSObject::<JSObjectGeneric>::new(context);
+            $(
+            types
+                .set_property(context, $name, JSValue::callback(context, Some($callback)))
+                .unwrap();
+            )*
+            types.into()
+        }
+    };
+}
+
+predicates!(
+    "isExternal" => is_external |_context, _value| false,
+    "isDate" => is_date |context, value| checked(context, value, "Date.prototype.getTime"),
+    "isArgumentsObject" => is_arguments_object |context, value| {
+        tagged(context, value, &["Arguments"])?
+    },
+    "isBigIntObject" => is_big_int_object |context, value| {
+        checked(context, value, "BigInt.prototype.valueOf")
+    },
+    "isBooleanObject" => is_boolean_object |context, value| {
+        checked(context, value, "Boolean.prototype.valueOf")
+    },
+    "isNumberObject" => is_number_object |context, value| {
+        checked(context, value, "Number.prototype.valueOf")
+    },
+    "isStringObject" => is_string_object |context, value| {
+        checked(context, value, "String.prototype.valueOf")
+    },
+    "isSymbolObject" => is_symbol_object |context, value| {
+        checked(context, value, "Symbol.prototype.valueOf")
+    },
+    "isNativeError" => is_native_error |context, value| tagged(context, value, &["Error"])?,
+    // The getter of `source` only accepts the regular expressions, and their
+    // prototype.
+    "isRegExp" => is_reg_exp |context, value| {
+        checked_getter(context, value, "RegExp.prototype", "source")
+            && !same_value(context, value.clone(), global_property(context, "RegExp.prototype")?)
+    },
+    "isAsyncFunction" => is_async_function |context, value| {
+        is_function(context, value, &["AsyncFunction", "AsyncGeneratorFunction"])?
+    },
+    "isGeneratorFunction" => is_generator_function |context, value| {
+        is_function(context, value, &["GeneratorFunction", "AsyncGeneratorFunction"])?
+    },
+    "isGeneratorObject" => is_generator_object |context, value| {
+        tagged(context, value, &["Generator", "AsyncGenerator"])?
+    },
+    "isPromise" => is_promise |context, value| tagged(context, value, &["Promise"])?,
+    "isMap" => is_map |context, value| checked(context, value, "Map.prototype.has"),
+    "isSet" => is_set |context, value| checked(context, value, "Set.prototype.has"),
+    "isMapIterator" => is_map_iterator |context, value| tagged(context, value, &["Map Iterator"])?,
+    "isSetIterator" => is_set_iterator |context, value| tagged(context, value, &["Set Iterator"])?,
+    "isWeakMap" => is_weak_map |context, value| checked(context, value, "WeakMap.prototype.has"),
+    "isWeakSet" => is_weak_set |context, value| checked(context, value, "WeakSet.prototype.has"),
+    "isArrayBuffer" => is_array_buffer_callback |context, value| is_array_buffer(context, value),
+    "isDataView" => is_data_view |context, value| {
+        checked_getter(context, value, "DataView.prototype", "byteLength")
+    },
+    "isSharedArrayBuffer" => is_shared_array_buffer_callback |context, value| {
+        is_shared_array_buffer(context, value)?
+    },
+    "isProxy" => is_proxy |_context, _value| false,
+    "isModuleNamespaceObject" => is_module_namespace_object |context, value| {
+        tagged(context, value, &["Module"])?
+    },
+    "isAnyArrayBuffer" => is_any_array_buffer |context, value| {
+        is_array_buffer(context, value) || is_shared_array_buffer(context, value)?
+    },
+    "isBoxedPrimitive" => is_boxed_primitive_callback |context, value| {
+        is_boxed_primitive(context, value)
+    },
+    "isArrayBufferView" => is_array_buffer_view |context, value| {
+        call_global(context, "ArrayBuffer.isView", &[value.clone()])?.to_bool(context)
+    },
+    "isTypedArray" => is_typed_array |context, value| typed_array_class(context, value)?.is_some(),
+    "isUint8Array" => is_uint8_array |context, value| {
+        is_typed_array_of(context, value, "Uint8Array")?
+    },
+    "isUint8ClampedArray" => is_uint8_clamped_array |context, value| {
+        is_typed_array_of(context, value, "Uint8ClampedArray")?
+    },
+    "isUint16Array" => is_uint16_array |context, value| {
+        is_typed_array_of(context, value, "Uint16Array")?
+    },
+    "isUint32Array" => is_uint32_array |context, value| {
+        is_typed_array_of(context, value, "Uint32Array")?
+    },
+    "isInt8Array" => is_int8_array |context, value| is_typed_array_of(context, value, "Int8Array")?,
+    "isInt16Array" => is_int16_array |context, value| {
+        is_typed_array_of(context, value, "Int16Array")?
+    },
+    "isInt32Array" => is_int32_array |context, value| {
+        is_typed_array_of(context, value, "Int32Array")?
+    },
+    "isFloat32Array" => is_float32_array |context, value| {
+        is_typed_array_of(context, value, "Float32Array")?
+    },
+    "isFloat64Array" => is_float64_array |context, value| {
+        is_typed_array_of(context, value, "Float64Array")?
+    },
+    "isBigInt64Array" => is_big_int64_array |context, value| {
+        is_typed_array_of(context, value, "BigInt64Array")?
+    },
+    "isBigUint64Array" => is_big_uint64_array |context, value| {
+        is_typed_array_of(context, value, "BigUint64Array")?
+    },
+    "isKeyObject" => is_key_object |_context, _value| false,
+    "isCryptoKey" => is_crypto_key |_context, _value| false,
+);
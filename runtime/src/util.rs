@@ -1,154 +1,536 @@
-use std::collections::VecDeque;
+//! The `node:util` module: `inspect`, see the inspect module, `styleText`,
+//! `types`, see the types module, `TextEncoder` and `TextDecoder` of the web
+//! streams, and `format` and `formatWithOptions`, the printf-like formatting
+//! of the console methods. The other functions, `promisify`, `callbackify`,
+//! `inherits`, `deprecate`, `isDeepStrictEqual` and `parseArgs`, are written
+//! in javascript (`js/util.js`) and added once to the module.
+//!
+//! The first argument is a format string which can contain zero or more
+//! format specifiers. Each specifier is replaced with the converted value
+//! from the corresponding argument:
+//!
+//! - `%s`: String will be used to convert all values except BigInt, Object
+//!   and -0. BigInt values will be represented with an n and Objects that
+//!   have no user defined toString function are inspected with `{ depth: 0 }`.
+//! - `%d`: Number will be used to convert all values except BigInt and Symbol.
+//! - `%i`: parseInt(value, 10) is used for all values except BigInt and Symbol.
+//! - `%f`: parseFloat(value) is used for all values expect Symbol.
+//! - `%j`: JSON. Replaced with the string '[Circular]' if the argument
+//!   contains circular references.
+//! - `%o`: Object, inspected with `{ depth: 4 }`.
+//! - `%O`: Object, inspected with the options.
+//! - `%c`: CSS. This specifier is ignored and will skip any CSS passed in.
+//! - `%%`: single percent sign ('%'). This does not consume an argument.
+//!
+//! A specifier without a corresponding argument isn't replaced. The extra
+//! arguments are appended, separated by spaces: the strings as they are, the
+//! other values inspected. If the first argument isn't a string, all of them
+//! are.
 
+use std::env;
+
+use maybe_static::maybe_static_unsafe;
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_until},
-    error::{Error as NomError, ErrorKind as NomErrorKind, ParseError},
+    bytes::complete::{is_not, tag},
+    character::complete::{char, one_of},
+    combinator::map,
+    multi::many0,
+    sequence::preceded,
     IResult,
 };
+use rusty_jsc::{JSContext, JSObject, JSObjectGeneric, JSProtected, JSValue};
+use rusty_jsc_macros::callback;
 
-use rusty_jsc::{JSContext, JSValue};
+use crate::{
+    colors::{is_tty, should_colorize},
+    errors::{invalid_arg_type, invalid_arg_value},
+    inspect::{
+        color_codes, custom_symbol, format_number, inspect, to_string, type_tag, Compact,
+        InspectOptions,
+    },
+    internals::internals,
+    js_utils::{array_values, call_global, has_own_property},
+    options::get_option,
+    types::types,
+    web_streams::web_streams,
+};
 
-/*
+/// The constructors of the builtin objects, whose `toString` isn't used by
+/// `%s`: the objects are inspected instead.
+const BUILTIN_CONSTRUCTORS: &[&str] = &[
+    "Object",
+    "Function",
+    "Array",
+    "Number",
+    "Boolean",
+    "String",
+    "Symbol",
+    "BigInt",
+    "Date",
+    "RegExp",
+    "Error",
+    "AggregateError",
+    "EvalError",
+    "RangeError",
+    "ReferenceError",
+    "SyntaxError",
+    "TypeError",
+    "URIError",
+    "Promise",
+    "Map",
+    "Set",
+    "WeakMap",
+    "WeakSet",
+    "WeakRef",
+    "ArrayBuffer",
+    "SharedArrayBuffer",
+    "DataView",
+    "Int8Array",
+    "Uint8Array",
+    "Uint8ClampedArray",
+    "Int16Array",
+    "Uint16Array",
+    "Int32Array",
+    "Uint32Array",
+    "Float32Array",
+    "Float64Array",
+    "BigInt64Array",
+    "BigUint64Array",
+];
 
-#[callback]
-fn format(context: JSContext, _function: JSObject, _this: JSObject, arguments: &[JSValue]) {
-    if arguments.is_empty() {
-        return println!();
-    }
-    if arguments[0].is_string(&context) {}
-    for arg in arguments {}
+/// A piece of a format string.
+enum Token<'a> {
+    /// Text kept as it is.
+    Text(&'a str),
+    /// A specifier replaced by the next argument, like `%s`.
+    Specifier(char),
+    /// `%%`, a percent sign.
+    Percent,
 }
 
-The util.format() method returns a formatted string using the first argument as a printf-like format string which can contain zero or more format specifiers. Each specifier is replaced with the converted value from the corresponding argument. Supported specifiers are:
-
-    %s: String will be used to convert all values except BigInt, Object and -0. BigInt values will be represented with an n and Objects that have no user defined toString function are inspected using util.inspect() with options { depth: 0, colors: false, compact: 3 }.
-    %d: Number will be used to convert all values except BigInt and Symbol.
-    %i: parseInt(value, 10) is used for all values except BigInt and Symbol.
-    %f: parseFloat(value) is used for all values expect Symbol.
-    %j: JSON. Replaced with the string '[Circular]' if the argument contains circular references.
-    %o: Object. A string representation of an object with generic JavaScript object formatting. Similar to util.inspect() with options { showHidden: true, showProxy: true }. This will show the full object including non-enumerable properties and proxies.
-    %O: Object. A string representation of an object with generic JavaScript object formatting. Similar to util.inspect() without options. This will show the full object not including non-enumerable properties and proxies.
-    %c: CSS. This specifier is ignored and will skip any CSS passed in.
-    %%: single percent sign ('%'). This does not consume an argument.
-    Returns: <string> The formatted string
-
-
-
- */
-
-//pub type Res<T, U> = IResult<T, U, VerboseError<T>>;
+fn token(input: &str) -> IResult<&str, Token<'_>> {
+    alt((
+        map(is_not("%"), Token::Text),
+        map(tag("%%"), |_| Token::Percent),
+        map(preceded(char('%'), one_of("sdifjoOc")), Token::Specifier),
+        // A percent sign that doesn't start a specifier is kept.
+        map(tag("%"), Token::Text),
+    ))(input)
+}
 
-#[derive(Clone)]
-struct ArgIn<'a> {
-    input: &'a str,
-    arguments: &'a [JSValue],
-    context: &'a JSContext,
+/// Split a format string in tokens. Any input is valid.
+fn tokens(input: &str) -> Vec<Token<'_>> {
+    many0(token)(input)
+        .map(|(_, tokens)| tokens)
+        .unwrap_or_default()
 }
 
-type ResWithArg<'a> = IResult<ArgIn<'a>, VecDeque<String>, nom::error::Error<&'a str>>;
-type Res<'a> = IResult<&'a str, &'a str>;
+/// Whether the `toString` of an object is the one of a builtin class, not a
+/// user defined one.
+fn has_builtin_to_string(context: &JSContext, value: &JSValue) -> Result<bool, JSValue> {
+    let method = value
+        .to_object(context)?
+        .get_property(context, "toString")?;
+    if !method.is_object(context) {
+        return Ok(true);
+    }
+    if has_own_property(context, value, "toString")? {
+        return Ok(false);
+    }
+    // The prototype defining the method.
+    let mut owner = value.clone();
+    loop {
+        owner = call_global(context, "Object.getPrototypeOf", &[owner])?;
+        if owner.is_null(context) {
+            return Ok(true);
+        }
+        if has_own_property(context, &owner, "toString")? {
+            break;
+        }
+    }
+    if !has_own_property(context, &owner, "constructor")? {
+        return Ok(false);
+    }
+    let constructor = owner
+        .to_object(context)?
+        .get_property(context, "constructor")?;
+    if !constructor.is_object(context) {
+        return Ok(false);
+    }
+    let name = constructor
+        .to_object(context)?
+        .get_property(context, "name")?;
+    Ok(BUILTIN_CONSTRUCTORS.contains(&to_string(context, &name)?.as_str()))
+}
 
-fn take_until_percent(mut arg_in: ArgIn) -> ResWithArg {
-    match take_until("%")(arg_in.input) {
-        Ok((rest, out)) => {
-            arg_in.input = rest;
-            let (new_arg_in, mut next_out) = alt_percent_sign(arg_in)?;
-            next_out.push_front(out.to_string());
-            Ok((new_arg_in, next_out))
+/// `JSON.stringify(value)`, '[Circular]' for a circular structure.
+fn stringify(context: &JSContext, value: &JSValue) -> Result<String, JSValue> {
+    match call_global(context, "JSON.stringify", &[value.clone()]) {
+        Ok(json) => to_string(context, &json),
+        Err(error) => {
+            let message = match error.to_object(context) {
+                Ok(error) => to_string(context, &error.get_property(context, "message")?)?,
+                Err(_) => String::new(),
+            };
+            if message.contains("cyclic") || message.contains("circular") {
+                Ok(String::from("[Circular]"))
+            } else {
+                Err(error)
+            }
         }
-        Err(rest) => Err(rest),
     }
 }
 
-impl<'a> ParseError<ArgIn<'a>> for nom::error::Error<&'a str> {
-    fn from_error_kind(arg_in: ArgIn<'a>, kind: nom::error::ErrorKind) -> Self {
-        nom::error::Error {
-            input: arg_in.input,
-            code: kind,
+/// Replace a specifier by its argument.
+fn convert(
+    context: &JSContext,
+    options: &InspectOptions,
+    specifier: char,
+    value: &JSValue,
+) -> Result<String, JSValue> {
+    let tag = type_tag(context, value)?;
+    let number = |function: &str| -> Result<String, JSValue> {
+        match tag.as_str() {
+            "BigInt" => Ok(format!("{}n", to_string(context, value)?)),
+            "Symbol" => Ok(String::from("NaN")),
+            _ => {
+                let number = call_global(context, function, &[value.clone()])?;
+                format_number(context, number.to_number(context)?)
+            }
+        }
+    };
+    match specifier {
+        's' => {
+            if value.is_number(context) {
+                format_number(context, value.to_number(context)?)
+            } else if tag == "BigInt" {
+                Ok(format!("{}n", to_string(context, value)?))
+            } else if !value.is_object(context)
+                || tag.ends_with("Function")
+                || !has_builtin_to_string(context, value)?
+            {
+                to_string(context, value)
+            } else {
+                let options = InspectOptions {
+                    depth: 0.0,
+                    compact: Compact::Levels(3),
+                    colors: false,
+                    ..options.clone()
+                };
+                inspect(context, value, &options)
+            }
+        }
+        'd' => number("Number"),
+        'i' => number("parseInt"),
+        'f' if tag == "Symbol" => Ok(String::from("NaN")),
+        'f' => {
+            let number = call_global(context, "parseFloat", &[value.clone()])?;
+            format_number(context, number.to_number(context)?)
+        }
+        'j' => stringify(context, value),
+        'o' => {
+            let options = InspectOptions {
+                depth: 4.0,
+                show_hidden: true,
+                ..options.clone()
+            };
+            inspect(context, value, &options)
         }
+        'O' => inspect(context, value, options),
+        _ => Ok(String::new()),
     }
+}
 
-    fn append(arg_in: ArgIn<'a>, kind: nom::error::ErrorKind, _: Self) -> Self {
-        nom::error::Error {
-            input: arg_in.input,
-            code: kind,
+/// Format the arguments as `util.formatWithOptions`, the values are
+/// inspected with `options`.
+pub fn format_with_options(
+    context: &JSContext,
+    options: &InspectOptions,
+    arguments: &[JSValue],
+) -> Result<String, JSValue> {
+    let Some((first, rest)) = arguments.split_first() else {
+        return Ok(String::new());
+    };
+    let mut parts = vec![];
+    let mut remaining = arguments;
+    if first.is_string(context) {
+        let format = first.to_js_string(context)?.to_string();
+        if rest.is_empty() {
+            return Ok(format);
+        }
+        let mut rest = rest.iter();
+        let mut text = String::new();
+        for token in tokens(&format) {
+            match token {
+                Token::Text(part) => text.push_str(part),
+                Token::Percent => text.push('%'),
+                Token::Specifier(specifier) => match rest.next() {
+                    Some(value) => text.push_str(&convert(context, options, specifier, value)?),
+                    None => {
+                        text.push('%');
+                        text.push(specifier);
+                    }
+                },
+            }
         }
+        parts.push(text);
+        remaining = rest.as_slice();
     }
+    for value in remaining {
+        if value.is_string(context) {
+            parts.push(value.to_js_string(context)?.to_string());
+        } else {
+            parts.push(inspect(context, value, options)?);
+        }
+    }
+    Ok(parts.join(" "))
 }
 
-fn alt_percent_sign(arg_in: ArgIn) -> ResWithArg {
-    alt((percent_s, percent_d))(arg_in)
-    //%s, %d, %i, %f, %j, %o, %O, %c, %%
+/// Format the arguments as `util.format`.
+pub fn format(context: &JSContext, arguments: &[JSValue]) -> Result<String, JSValue> {
+    format_with_options(context, &InspectOptions::default(), arguments)
 }
 
-fn percent_s(mut arg_in: ArgIn) -> ResWithArg {
-    let tmp: Res = tag("%s")(arg_in.input);
-    let (rest, res) = tmp?;
-    arg_in.input = rest;
-    if arg_in.arguments.is_empty() {
-        // Don't replace %s if arguments is empty
-        Ok((arg_in, VecDeque::from([res.to_string()])))
-    } else {
-        let res = &arg_in.arguments[0];
-        arg_in.arguments = &arg_in.arguments[1..];
-        if res.is_string(arg_in.context) {
-            let res = res.to_js_string(arg_in.context).unwrap().to_string();
-            Ok((arg_in, VecDeque::from([res])))
-        } else {
-            Err(nom::Err::Error(NomError::new(
-                "Parse Error: Unknown JSValue type",
-                NomErrorKind::NoneOf,
-            )))
+#[callback]
+/// `util.format(format[, ...args])`.
+fn format_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    Ok(JSValue::string(&context, format(&context, arguments)?))
+}
+
+#[callback]
+/// `util.formatWithOptions(inspectOptions, format[, ...args])`.
+fn format_with_options_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let options = match arguments.first() {
+        Some(options) if options.is_object(&context) => {
+            InspectOptions::from_object(&context, options)?
         }
-    }
+        _ => {
+            return Err(invalid_arg_type(
+                &context,
+                "inspectOptions",
+                "of type object",
+            ))
+        }
+    };
+    let text = format_with_options(&context, &options, &arguments[1..])?;
+    Ok(JSValue::string(&context, text))
 }
 
-fn percent_d(mut arg_in: ArgIn) -> ResWithArg {
-    let tmp: Res = tag("%d")(arg_in.input);
-    let (rest, res) = tmp?;
-    arg_in.input = rest;
-    if arg_in.arguments.is_empty() {
-        // Don't replace %d if arguments is empty
-        Ok((arg_in, VecDeque::from([res.to_string()])))
-    } else {
-        let res = &arg_in.arguments[0];
-        arg_in.arguments = &arg_in.arguments[1..];
-        if res.is_string(arg_in.context) {
-            let res = res.to_js_string(arg_in.context).unwrap().to_string();
-            Ok((arg_in, VecDeque::from([res])))
-        } else {
-            Err(nom::Err::Error(NomError::new(
-                "Parse Error: Unknown JSValue type",
-                NomErrorKind::NoneOf,
-            )))
+#[callback]
+/// `util.inspect(object[, options])`, or the legacy
+/// `util.inspect(object[, showHidden[, depth]])`.
+fn inspect_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let value = match arguments.first() {
+        Some(value) => value.clone(),
+        None => JSValue::undefined(&context),
+    };
+    let mut options = InspectOptions::default();
+    match arguments.get(2) {
+        Some(depth) if depth.is_null(&context) => options.depth = f64::INFINITY,
+        Some(depth) if depth.is_number(&context) => options.depth = depth.to_number(&context)?,
+        _ => {}
+    }
+    match arguments.get(1) {
+        Some(argument) if argument.is_object(&context) => options.update(&context, argument)?,
+        Some(argument) if type_tag(&context, argument)? == "Boolean" => {
+            options.show_hidden = argument.to_bool(&context);
         }
+        _ => {}
     }
+    Ok(JSValue::string(
+        &context,
+        inspect(&context, &value, &options)?,
+    ))
 }
 
-pub fn format_parser<'a>(
-    context: &'a JSContext,
-    arguments: &'a [JSValue],
-) -> Result<Vec<String>, ()> {
-    let mut arg_in = ArgIn {
-        arguments: &arguments[1..],
+/// Whether a value is a stream: a `node:stream` one, with an `on` method
+/// and a `write` or `pipe` one, or a WHATWG one, with a `getReader` or
+/// `getWriter` method.
+fn is_stream(context: &JSContext, value: &JSValue) -> bool {
+    let has_method = |name: &str| {
+        get_option(context, Some(value), name).map_or(false, |method| method.is_object(context))
+    };
+    (has_method("on") && (has_method("write") || has_method("pipe")))
+        || has_method("getReader")
+        || has_method("getWriter")
+}
+
+/// Get the codes of a color of `util.inspect.colors`, the error lists the
+/// colors if it's unknown.
+fn style_codes(context: &JSContext, format: &JSValue) -> Result<(String, String), JSValue> {
+    if let Some(codes) = color_codes(context, &to_string(context, format)?)? {
+        return Ok(codes);
+    }
+    let colors = util(context)
+        .get_property(context, "inspect")?
+        .to_object(context)?
+        .get_property(context, "colors")?;
+    let names = call_global(context, "Object.keys", &[colors])?;
+    let names = array_values(context, &names)?
+        .iter()
+        .map(|name| Ok(format!("'{}'", to_string(context, name)?)))
+        .collect::<Result<Vec<String>, JSValue>>()?;
+    let received = inspect(context, format, &InspectOptions::default())?;
+    Err(invalid_arg_value(
         context,
-        input: &arguments[0].to_js_string(context).unwrap().to_string(),
+        "format",
+        &format!("must be one of: {}. Received {received}", names.join(", ")),
+    ))
+}
+
+#[callback]
+/// `util.styleText(format, text[, options])`: the text wrapped in the escape
+/// sequences of a color of `util.inspect.colors`, or of several ones given
+/// in an array. The text is kept as it is if the stream, the standard output
+/// by default, isn't colored, unless `validateStream` is false.
+fn style_text_callback(
+    context: JSContext,
+    _function: JSObject,
+    _this: JSObject,
+    arguments: &[JSValue],
+) -> Result<JSValue, JSValue> {
+    let undefined = JSValue::undefined(&context);
+    let format = arguments.first().unwrap_or(&undefined);
+    let text = match arguments.get(1) {
+        Some(text) if text.is_string(&context) => text.to_js_string(&context)?.to_string(),
+        _ => return Err(invalid_arg_type(&context, "text", "of type string")),
+    };
+    let options = arguments.get(2);
+    let validate_stream = match get_option(&context, options, "validateStream") {
+        Some(validate) if type_tag(&context, &validate)? == "Boolean" => validate.to_bool(&context),
+        Some(_) => {
+            return Err(invalid_arg_type(
+                &context,
+                "options.validateStream",
+                "of type boolean",
+            ))
+        }
+        None => true,
+    };
+    let stream = get_option(&context, options, "stream");
+    if let Some(stream) = &stream {
+        if validate_stream && !is_stream(&context, stream) {
+            return Err(invalid_arg_type(
+                &context,
+                "stream",
+                "an instance of ReadableStream, WritableStream, or Stream",
+            ));
+        }
+    }
+    let formats = if call_global(&context, "Array.isArray", &[format.clone()])?.to_bool(&context) {
+        array_values(&context, format)?
+    } else {
+        vec![format.clone()]
     };
-    let mut res = vec![];
+    let mut start = String::new();
+    let mut end = String::new();
+    for format in &formats {
+        let codes = style_codes(&context, format)?;
+        start.push_str(&format!("\x1b[{}m", codes.0));
+        end.insert_str(0, &format!("\x1b[{}m", codes.1));
+    }
+    // Checked after the arguments, as NodeJS.
+    let colored = !validate_stream
+        || match &stream {
+            Some(stream) => should_colorize(
+                get_option(&context, Some(stream), "isTTY")
+                    .map_or(false, |is_tty| is_tty.to_bool(&context)),
+            ),
+            None => should_colorize(is_tty(libc::STDOUT_FILENO)),
+        };
+    if !colored {
+        return Ok(JSValue::string(&context, text));
+    }
+    Ok(JSValue::string(&context, format!("{start}{text}{end}")))
+}
 
-    loop {
-        match take_until_percent(arg_in) {
-            Ok((new_arg_in, out)) => {
-                arg_in = new_arg_in;
-                res.append(&mut out.into());
+/// The arguments given to the script, after `needjs script.js`.
+fn script_arguments(context: &JSContext) -> JSValue {
+    let arguments: Vec<JSValue> = env::args()
+        .skip(2)
+        .map(|argument| JSValue::string(context, argument))
+        .collect();
+    JSObject::new_array(context, &arguments).unwrap().into()
+}
+
+/// Get the `node:util` module, created on the first call.
+pub fn util(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let module = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    module
+        .get_or_insert_with(|| {
+            let mut util = JSObject::<JSObjectGeneric>::new(context);
+            macro_rules! method {
+                ($( $name: literal => $callback: ident ),*) => {
+                    $(
+                    util
+                        .set_property(context, $name, JSValue::callback(context, Some($callback)))
+                        .unwrap();
+                    )*
+                };
             }
-            Err(nom::Err::Error(NomError { input, code: _ })) => {
-                res.push(input.to_string());
-                break;
+            method!(
+                "format" => format_callback,
+                "formatWithOptions" => format_with_options_callback,
+                "inspect" => inspect_callback,
+                "styleText" => style_text_callback
+            );
+            let mut inspect = util
+                .get_property(context, "inspect")
+                .unwrap()
+                .to_object(context)
+                .unwrap();
+            inspect
+                .set_property(context, "custom", custom_symbol(context))
+                .unwrap();
+            context
+                .evaluate_script(include_str!("js/inspect.js"), 1)
+                .expect("cannot evaluate the inspect colors")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(context, None, &[inspect.into()])
+                .expect("cannot define the inspect colors");
+            util.set_property(context, "types", types(context).into())
+                .unwrap();
+            let classes = web_streams(context);
+            for name in ["TextEncoder", "TextDecoder"] {
+                let class = classes.get_property(context, name).unwrap();
+                util.set_property(context, name, class).unwrap();
             }
-            Err(_) => return Err(()), // TODO: Complete and return errors
-        }
-    }
-    Ok(res)
+            context
+                .evaluate_script(include_str!("js/util.js"), 1)
+                .expect("cannot evaluate the util functions")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(
+                    context,
+                    None,
+                    &[
+                        util.clone().into(),
+                        internals(context).into(),
+                        script_arguments(context),
+                    ],
+                )
+                .expect("cannot define the util functions");
+            let util: JSValue = util.into();
+            util.into_protected_object(context)
+        })
+        .clone()
+        .into()
 }
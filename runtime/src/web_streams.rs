@@ -0,0 +1,67 @@
+//! The WHATWG streams: `ReadableStream`, `WritableStream`, `TransformStream`,
+//! their readers, writers and controllers, the queuing strategies and
+//! `TextEncoderStream`/`TextDecoderStream`, with `TextEncoder` and
+//! `TextDecoder`. They are globals and the `node:stream/web` module, which
+//! also gives the encoder and decoder to `node:util`.
+//!
+//! As `node:stream`, the classes are written in javascript
+//! (`js/web_streams.js`) and evaluated once. The text streams use the native
+//! encoding binding of `stream`, the errors are built by `internals`.
+
+use maybe_static::maybe_static_unsafe;
+use rusty_jsc::{JSContext, JSObject, JSProtected};
+
+use crate::{events::events, internals::internals, stream::binding};
+
+/// Get the `node:stream/web` module. The script is evaluated on the first
+/// call.
+pub fn web_streams(context: &JSContext) -> JSObject {
+    // Only used with the event loop hold, see `get_hold`.
+    let module = maybe_static_unsafe!(Option<JSObject<JSProtected>>);
+    module
+        .get_or_insert_with(|| {
+            context
+                .evaluate_script(include_str!("js/web_streams.js"), 1)
+                .expect("cannot evaluate the web streams")
+                .to_object(context)
+                .unwrap()
+                .call_as_function(
+                    context,
+                    None,
+                    &[
+                        events(context).into(),
+                        binding(context),
+                        internals(context).into(),
+                    ],
+                )
+                .expect("cannot initialize the web streams")
+                .into_protected_object(context)
+        })
+        .clone()
+        .into()
+}
+
+/// Define the classes of `node:stream/web` as globals.
+pub fn init(context: &mut JSContext) {
+    let module = web_streams(context);
+    let global = &mut context.get_global_object();
+    for name in [
+        "ReadableStream",
+        "ReadableStreamDefaultReader",
+        "ReadableStreamDefaultController",
+        "WritableStream",
+        "WritableStreamDefaultWriter",
+        "WritableStreamDefaultController",
+        "TransformStream",
+        "TransformStreamDefaultController",
+        "ByteLengthQueuingStrategy",
+        "CountQueuingStrategy",
+        "TextEncoder",
+        "TextDecoder",
+        "TextEncoderStream",
+        "TextDecoderStream",
+    ] {
+        let class = module.get_property(context, name).unwrap();
+        global.set_property(context, name, class).unwrap();
+    }
+}
@@ -0,0 +1,35 @@
+const fs = require("fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/file.txt";
+
+fs.writeFile(file, "hello", (err) => {
+  if (err) throw err;
+  fs.appendFile(file, " world", (err) => {
+    if (err) throw err;
+    fs.readFile(file, "utf8", (err, data) => {
+      console.log("read: " + err + " " + data);
+      fs.stat(file, (err, stats) => {
+        console.log("stat: " + stats.isFile() + " " + stats.size);
+        fs.exists(file, (exists) => {
+          console.log("exists: " + exists);
+          fs.readFile(tmp + "/missing", (err) => {
+            console.log(err.code + " " + err.syscall);
+            fs.opendir(tmp, (err, dir) => {
+              console.log("opendir: " + err + " " + dir.path);
+              dir.close();
+              fs.rm(tmp, { recursive: true }, (err) => {
+                console.log("removed: " + err + " " + !fs.existsSync(tmp));
+              });
+            });
+          });
+        });
+      });
+    });
+  });
+});
+
+require("fs/promises")
+  .readdir("/")
+  .then((entries) => console.log("promises: " + (entries.length > 0)));
+console.log("same: " + (require("fs").promises.readdir !== undefined));
@@ -0,0 +1,49 @@
+const fs = require("node:fs");
+const { Writable } = require("node:stream");
+
+const lines = [];
+const memory = new Writable({
+  write(chunk, encoding, callback) {
+    lines.push(new TextDecoder().decode(chunk));
+    callback();
+  },
+});
+const logger = new console.Console({ stdout: memory, groupIndentation: 4 });
+logger.log("to memory");
+logger.group("group");
+logger.error("stderr defaults to stdout");
+logger.groupEnd();
+console.log(JSON.stringify(lines));
+console.log("instanceof: " + (logger instanceof console.Console) + " " + (console instanceof console.Console));
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const out = fs.createWriteStream(tmp + "/out.log");
+const err = fs.createWriteStream(tmp + "/err.log");
+const service = console.Console(out, err);
+service.log("started on port %d", 8080);
+service.warn("disk almost full");
+out.end(() =>
+  err.end(() => {
+    console.log("out.log: " + fs.readFileSync(tmp + "/out.log", "utf8").trim());
+    console.log("err.log: " + fs.readFileSync(tmp + "/err.log", "utf8").trim());
+    fs.rmSync(tmp, { recursive: true });
+  })
+);
+
+const failing = new Writable({
+  write(chunk, encoding, callback) {
+    callback(new Error("disk full"));
+  },
+});
+new console.Console(failing).log("the error is ignored");
+const strict = new console.Console({ stdout: failing, ignoreErrors: false });
+strict.log("the error is emitted");
+failing.on("error", (error) => console.log("error event: " + error.message));
+
+for (const options of [{}, { stdout: memory, colorMode: "never" }, { stdout: memory, groupIndentation: 1001 }]) {
+  try {
+    new console.Console(options);
+  } catch (error) {
+    console.log(error.code + ": " + error.message);
+  }
+}
@@ -0,0 +1,43 @@
+console.log("log");
+console.info("info");
+console.debug("debug");
+console.error("error, on stderr");
+console.warn("warn, on stderr");
+
+const { log } = console;
+log("detached log");
+
+console.assert(true, "not printed");
+console.assert(false, "printed on stderr");
+console.assert(false);
+
+console.count();
+console.count("tasks");
+console.count("tasks");
+console.countReset("tasks");
+console.count("tasks");
+console.countReset("missing");
+
+console.group("group");
+console.log("indented");
+console.groupCollapsed("nested");
+console.log("more\nindented");
+console.groupEnd();
+console.groupEnd();
+console.log("back");
+
+console.time("timer");
+console.time("timer");
+console.timeLog("timer");
+console.timeEnd("timer");
+console.timeEnd("timer");
+
+console.table([
+  { name: "a", size: 1 },
+  { name: "bb", kind: "dir" },
+]);
+console.table(["x", "y"]);
+console.table("not tabular");
+
+console.trace("traced");
+console.clear();
@@ -0,0 +1,19 @@
+const process = require("node:process");
+
+// The console and the streams write in order, on both outputs.
+console.log("log");
+process.stdout.write("stdout write\n");
+console.error("error, on stderr");
+process.stderr.write("stderr write\n");
+process.stdout.write(new Uint8Array([98, 121, 116, 101, 115, 10]));
+process.stdout.write("café\n", "latin1");
+
+console.log(process.stdout.fd, process.stderr.fd, typeof process.stdout.isTTY);
+console.log(process.stdout.writable, process.stdout._isStdio);
+
+// A large output, done before the process exits.
+const line = "x".repeat(1023) + "\n";
+for (let i = 0; i < 256; i++) process.stdout.write(line);
+
+process.stdout.write("written", () => console.log(" and called back"));
+setTimeout(() => console.log("after a timeout"), 10);
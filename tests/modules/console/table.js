@@ -0,0 +1,14 @@
+console.table([{ a: 1, b: "y" }, { a: "z", c: { d: 1 } }, 3, "str", null]);
+console.table([[1, 2], [3, 4, 5]]);
+console.table({ x: { name: "東京", n: 1 }, y: { name: "ok", n: [1, 2, 3, 4, 5] } });
+console.table([{ a: 1, b: 2, c: 3 }, { a: { b: 1, c: 2, d: 3 } }], ["a", "c"]);
+console.table([1, 2, 3], ["a"]);
+console.table(new Map([["k", { v: 1 }], [{ k: 2 }, "s"]]));
+console.table(new Set(["a", 1, [2]]));
+console.table("primitive");
+console.table([]);
+console.table({ 10: { b: 1, 2: "x", a: 3 }, 2: { "a-b": "it's", fn() {} } });
+console.table([{ m: new Map([[1, 2]]), s: new Set([1]), d: undefined, n: -0, big: 10n, sym: Symbol("s") }]);
+class Point { constructor() { this.x = 1; } }
+console.table([{ p: new Point(), o: Object.create(null), e: {}, arr: [], h: [1, , 3], f: function named() {}, c: Point }]);
+try { console.table([], "a"); } catch (e) { console.log(e.code); }
@@ -0,0 +1,42 @@
+const fs = require("node:fs/promises");
+const { COPYFILE_EXCL, COPYFILE_FICLONE } = fs.constants;
+
+fs.mkdtemp("/tmp/needjs-").then(async (tmp) => {
+  await fs.copyFile("../files/test.txt", tmp + "/copy.txt", COPYFILE_FICLONE);
+  await fs.copyFile("../files/test.txt", tmp + "/copy.txt", COPYFILE_EXCL)
+    .catch((err) => console.log("copyFile excl: " + err.code));
+  await fs.copyFile(tmp + "/copy.txt", tmp + "/mode.txt");
+  await fs.chmod(tmp + "/copy.txt", 0o741);
+  await fs.copyFile(tmp + "/copy.txt", tmp + "/mode.txt", COPYFILE_FICLONE);
+  const { mode } = require("node:fs").statSync(tmp + "/mode.txt");
+  console.log("ficlone mode: " + (mode & 0o777).toString(8));
+
+  await fs.copyFile("../square/square.js", tmp + "/self.js");
+  await fs.copyFile(tmp + "/self.js", tmp + "/self.js");
+  await fs.link(tmp + "/self.js", tmp + "/hardlink.js");
+  await fs.copyFile(tmp + "/self.js", tmp + "/hardlink.js");
+  console.log("copied onto itself: " + require(tmp + "/self.js").name);
+  await fs.copyFile(tmp + "/self.js", tmp + "/self.js", COPYFILE_EXCL)
+    .catch((err) => console.log("copyFile excl onto itself: " + err.code));
+
+  await fs.mkdir(tmp + "/src/nested/deep", { recursive: true });
+  await fs.copyFile("../files/test.txt", tmp + "/src/nested/a.txt");
+  await fs.copyFile("../files/test.txt", tmp + "/src/b.log");
+  await fs.symlink("nested/a.txt", tmp + "/src/link");
+
+  await fs.cp(tmp + "/src", tmp + "/dest").catch((err) => console.log("cp directory: " + err.code));
+  await fs.cp(tmp + "/src", tmp + "/dest", {
+    recursive: true,
+    preserveTimestamps: true,
+    filter: async (src) => !src.endsWith(".log"),
+  });
+  console.log("copied: " + (await fs.readdir(tmp + "/dest", { recursive: true })).sort().join(","));
+  console.log("link: " + (await fs.readlink(tmp + "/dest/link")));
+
+  await fs.cp(tmp + "/src/b.log", tmp + "/dest/nested/a.txt", { force: false, errorOnExist: true })
+    .catch((err) => console.log("cp exists: " + err.code));
+  await fs.cp(tmp + "/src", tmp + "/src/nested", { recursive: true })
+    .catch((err) => console.log("cp into itself: " + err.code));
+
+  await fs.rm(tmp, { recursive: true });
+});
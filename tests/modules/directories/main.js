@@ -0,0 +1,24 @@
+const { mkdir, mkdtemp, readdir, rm, rmdir } = require("node:fs/promises");
+
+mkdtemp("/tmp/needjs-").then(async (tmp) => {
+  console.log("first created: " + (await mkdir(tmp + "/a/b/c", { recursive: true })));
+  await mkdir(tmp + "/a/d");
+  await mkdir(tmp + "/a/d").catch((err) => console.log("mkdir twice: " + err.code));
+
+  console.log("readdir: " + (await readdir(tmp + "/a")).sort().join(","));
+  console.log("recursive: " + (await readdir(tmp, { recursive: true })).sort().join(","));
+  const entries = await readdir(tmp + "/a", { withFileTypes: true });
+  entries.forEach((entry) =>
+    console.log(entry.name + " is a directory: " + entry.isDirectory())
+  );
+  const hex = await readdir(tmp + "/a", "hex");
+  console.log("hex names: " + hex.sort().join(","));
+  const buffers = await readdir(tmp + "/a", { encoding: "buffer" });
+  console.log("buffer names: " + buffers.map((name) => name[0]).sort().join(","));
+
+  await rmdir(tmp + "/a/d");
+  await rm(tmp + "/a").catch((err) => console.log("rm directory: " + err.code));
+  await rm(tmp + "/missing", { force: true });
+  await rm(tmp, { recursive: true });
+  await readdir(tmp).catch((err) => console.log("removed: " + err.code));
+});
@@ -0,0 +1,31 @@
+const { mkdtemp, mkdir, opendir, rm } = require("node:fs/promises");
+
+mkdtemp("/tmp/needjs-").then(async (tmp) => {
+  await mkdir(tmp + "/a/b", { recursive: true });
+  await mkdir(tmp + "/c");
+
+  const dir = await opendir(tmp, { bufferSize: 1 });
+  console.log("first entry: " + (await dir.read()).name);
+  await dir.close();
+  await dir.close().catch((err) => console.log("close twice: " + err.code));
+
+  // Reads not awaited are done in order, and the close after them.
+  const sequential = [];
+  const first = await opendir(tmp, { bufferSize: 1 });
+  for (let entry; (entry = await first.read()); ) sequential.push(entry.name);
+  await first.close();
+  const second = await opendir(tmp, { bufferSize: 1 });
+  const reads = [second.read(), second.read(), second.read()];
+  const closed = second.close();
+  const names = (await Promise.all(reads)).map((entry) => entry && entry.name);
+  await closed;
+  console.log("in order: " + (names.join(",") === sequential.concat(null).join(",")));
+
+  const recursive = [];
+  for await (const entry of await opendir(tmp, { recursive: true })) {
+    recursive.push(entry.name);
+  }
+  console.log("recursive: " + recursive.sort().join(","));
+
+  await rm(tmp, { recursive: true });
+});
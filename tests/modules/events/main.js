@@ -0,0 +1,64 @@
+const EventEmitter = require("node:events");
+const { once, on, EventTarget, Event } = EventEmitter;
+
+class Clock extends EventEmitter {}
+const clock = new Clock();
+
+clock.on("tick", function (n) {
+  console.log("tick " + n + ", this is clock: " + (this === clock));
+});
+clock.once("tick", (n) => console.log("first tick only " + n));
+clock.prependListener("tick", (n) => console.log("prepended " + n));
+clock.emit("tick", 1);
+clock.emit("tick", 2);
+console.log("listeners: " + clock.listenerCount("tick"));
+console.log("names: " + clock.eventNames().join(","));
+
+try {
+  clock.emit("error", new Error("boom"));
+} catch (err) {
+  console.log("thrown: " + err.message);
+}
+clock.on(EventEmitter.errorMonitor, (err) => console.log("monitor: " + err));
+clock.on("error", (err) => console.log("handled: " + err));
+clock.emit("error", "not an Error");
+
+clock.setMaxListeners(1);
+clock.on("many", () => {});
+clock.on("many", () => {});
+
+once(clock, "ready").then((args) => console.log("once: " + args.join(" ")));
+clock.emit("ready", "a", "b");
+
+(async () => {
+  const iterator = on(clock, "value");
+  setTimeout(() => {
+    clock.emit("value", 1);
+    clock.emit("value", 2);
+    clock.emit("done");
+  }, 1);
+  clock.once("done", () => iterator.return());
+  for await (const [value] of iterator) {
+    console.log("iterated: " + value);
+  }
+
+  const target = new EventTarget();
+  target.addEventListener(
+    "ping",
+    (event) => console.log("ping, target ok: " + (event.target === target)),
+    { once: true }
+  );
+  console.log("dispatched: " + target.dispatchEvent(new Event("ping")));
+  console.log("dispatched again: " + target.dispatchEvent(new Event("ping")));
+
+  // The native objects are emitters too.
+  const fs = require("node:fs");
+  const tmp = fs.mkdtempSync("/tmp/needjs-");
+  const stream = fs.createWriteStream(tmp + "/out.txt");
+  console.log("stream is an emitter: " + (stream instanceof EventEmitter));
+  stream.once("close", () => {
+    console.log("stream closed");
+    fs.rmSync(tmp, { recursive: true });
+  });
+  stream.end("done");
+})();
@@ -0,0 +1,18 @@
+const fs = require("node:fs/promises");
+
+fs.mkdtemp("/tmp/needjs-").then(async (tmp) => {
+  await fs.mkdir(tmp + "/dir");
+  await fs.symlink(tmp + "/dir", tmp + "/symlink", "dir");
+  console.log("readlink: " + ((await fs.readlink(tmp + "/symlink")) === tmp + "/dir"));
+  console.log("realpath: " + ((await fs.realpath(tmp + "/symlink")) === (await fs.realpath(tmp + "/dir"))));
+
+  await fs.rename(tmp + "/dir", tmp + "/renamed");
+  await fs.realpath(tmp + "/symlink").catch((err) => console.log("dangling: " + err.code));
+  await fs.unlink(tmp + "/symlink");
+  await fs.rename(tmp + "/missing", tmp + "/other").catch((err) => console.log(err.message.replaceAll(tmp, "")));
+
+  await fs.rm(tmp, { recursive: true });
+});
+
+// Both requires hit the same cache entry.
+console.log("cached: " + (require("../square/square.js") === require("./../square/square.js")));
@@ -0,0 +1,22 @@
+const fs = require("node:fs/promises");
+
+fs.mkdtemp("/tmp/needjs-").then(async (tmp) => {
+  const file = tmp + "/file.txt";
+  await fs.mkdir(file);
+  await fs.rmdir(file);
+  await fs.truncate(file).catch((err) => console.log("truncate missing: " + err.code));
+
+  await fs.mkdir(tmp + "/dir", 0o700);
+  await fs.chmod(tmp + "/dir", 0o755);
+  await fs.chmod(tmp + "/dir", "644");
+  await fs.chmod(tmp + "/dir", 0o100644).catch((err) => console.log("chmod file type: " + err.code));
+  await fs.lchmod(tmp + "/dir", 0o755).catch((err) => console.log("lchmod: " + err.code));
+
+  await fs.utimes(tmp + "/dir", new Date(0), 1000);
+  await fs.utimes(tmp + "/dir", "1000", "1000.5");
+  await fs.lutimes(tmp + "/dir", {}, 0).catch((err) => console.log("utimes object: " + err.code));
+  await fs.chown(tmp + "/dir", -1, -1);
+  await fs.lchown(tmp + "/dir", -2, 0).catch((err) => console.log("lchown: " + err.code));
+
+  await fs.rm(tmp, { recursive: true });
+});
@@ -0,0 +1,29 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/file.txt";
+fs.writeFileSync(file, "0123456789".repeat(10) + "é");
+
+const chunks = [];
+const stream = fs.createReadStream(file, { highWaterMark: 16, encoding: "utf8" });
+stream.on("open", (fd) => console.log("open: " + (typeof fd)));
+stream.on("ready", () => console.log("ready"));
+stream.on("data", (chunk) => chunks.push(chunk));
+stream.on("end", () => {
+  console.log("chunks: " + chunks.length + " " + chunks.join("").length);
+  console.log("bytesRead: " + stream.bytesRead);
+});
+stream.on("close", () => {
+  console.log("close");
+
+  const range = fs.createReadStream(file, { start: 2, end: 5 });
+  range.on("data", (chunk) => console.log("range: " + String.fromCharCode(...chunk)));
+  range.on("close", () => {
+    const missing = fs.createReadStream(tmp + "/missing");
+    missing.on("error", (err) => console.log(err.code + " " + err.syscall));
+    missing.on("close", () => {
+      fs.rmSync(tmp, { recursive: true });
+      console.log("done");
+    });
+  });
+});
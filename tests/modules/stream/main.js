@@ -0,0 +1,89 @@
+const fs = require("node:fs");
+const { Readable, Writable, Transform, PassThrough, pipeline, finished } = require("node:stream");
+const { pipeline: pipelinePromise } = require("node:stream/promises");
+
+class Counter extends Readable {
+  constructor() {
+    super({ objectMode: true });
+    this.count = 0;
+  }
+  _read() {
+    this.count++;
+    this.push(this.count > 3 ? null : this.count);
+  }
+}
+
+class Upper extends Transform {
+  _transform(chunk, encoding, callback) {
+    callback(null, new TextDecoder().decode(chunk).toUpperCase());
+  }
+}
+
+function collector(name) {
+  const chunks = [];
+  return new Writable({
+    write(chunk, encoding, callback) {
+      chunks.push(new TextDecoder().decode(chunk));
+      callback();
+    },
+    final(callback) {
+      console.log(name + ": " + chunks.join(""));
+      callback();
+    },
+  });
+}
+
+const counter = new Counter();
+counter.on("data", (n) => console.log("data " + n));
+counter.on("end", () => console.log("end, flowing " + counter.readableFlowing));
+
+const paused = new Readable({ read() {} });
+paused.push("abcdef");
+paused.push(null);
+paused.once("readable", () => {
+  console.log("read(4): " + new TextDecoder().decode(paused.read(4)));
+  console.log("read(): " + new TextDecoder().decode(paused.read()));
+});
+
+const slow = new Writable({
+  highWaterMark: 4,
+  write(chunk, encoding, callback) {
+    setTimeout(callback, 1);
+  },
+});
+console.log("write returns " + slow.write("12345"));
+slow.on("drain", () => console.log("drain"));
+
+pipeline(Readable.from(["a", "b", "c"]), new Upper(), collector("pipeline"), (err) =>
+  console.log("pipeline done: " + err)
+);
+
+const failing = new Readable({ read() {} });
+pipeline(failing, new PassThrough(), collector("never"), (err) =>
+  console.log("pipeline failed: " + err.message)
+);
+failing.destroy(new Error("source broken"));
+
+const ended = new Writable({ write(chunk, encoding, callback) { callback(); } });
+ended.end("x");
+ended.write("y", (err) => console.log("write after end: " + err.code));
+ended.on("error", () => {});
+finished(ended, (err) => console.log("finished: " + (err ? err.code : "ok")));
+
+(async () => {
+  const values = [];
+  for await (const n of new Counter()) values.push(n);
+  console.log("iterated: " + values.join(","));
+
+  const tmp = fs.mkdtempSync("/tmp/needjs-");
+  fs.writeFileSync(tmp + "/in.txt", "streamed through a transform\n");
+  await pipelinePromise(
+    fs.createReadStream(tmp + "/in.txt", { highWaterMark: 4 }),
+    new Upper(),
+    fs.createWriteStream(tmp + "/out.txt")
+  );
+  console.log("copied: " + fs.readFileSync(tmp + "/out.txt", "utf8").trim());
+  const file = fs.createWriteStream(tmp + "/out.txt");
+  console.log("WriteStream is a Writable: " + (file instanceof Writable));
+  file.close(() => fs.rmSync(tmp, { recursive: true }));
+})();
@@ -0,0 +1,67 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/file.txt";
+
+fs.writeFileSync(file, "hello");
+fs.appendFileSync(file, " world");
+console.log(fs.readFileSync(file, "utf8"));
+console.log("bytes: " + fs.readFileSync(file).length);
+console.log("hex: " + fs.readFileSync(file, { encoding: "hex" }).slice(0, 10));
+console.log("exists: " + fs.existsSync(file) + " " + fs.existsSync(tmp + "/missing"));
+
+const stats = fs.statSync(file);
+console.log("stat: " + stats.isFile() + " " + stats.size);
+console.log("no entry: " + fs.statSync(tmp + "/missing", { throwIfNoEntry: false }));
+
+fs.mkdirSync(tmp + "/a/b", { recursive: true });
+fs.copyFileSync(file, tmp + "/a/b/copy.txt");
+fs.renameSync(tmp + "/a/b/copy.txt", tmp + "/a/renamed.txt");
+console.log(fs.readdirSync(tmp, { recursive: true }).sort().join(","));
+
+const fd = fs.openSync(file, "r+");
+const buffer = new Uint8Array(5);
+console.log("read: " + fs.readSync(fd, buffer, 0, 5, 6) + " " + String.fromCharCode(...buffer));
+console.log("written: " + fs.writeSync(fd, "HELLO", 0));
+fs.closeSync(fd);
+console.log(fs.readFileSync(file, "utf8"));
+
+try {
+  fs.readFileSync(tmp + "/missing");
+} catch (err) {
+  console.log(err.code + " " + err.syscall);
+}
+
+const dir = fs.opendirSync(tmp + "/a", { bufferSize: 1 });
+const names = [];
+for (let entry; (entry = dir.readSync()); ) names.push(entry.name + ":" + entry.isDirectory());
+console.log("opendir: " + dir.path.endsWith("/a") + " " + names.sort().join(","));
+dir.closeSync();
+try {
+  dir.closeSync();
+} catch (err) {
+  console.log(err.code);
+}
+const busy = fs.opendirSync(tmp);
+busy.read().then(() => busy.close()).then(() => console.log("closed after read"));
+try {
+  busy.readSync();
+} catch (err) {
+  console.log(err.code);
+}
+
+fs.cpSync(tmp + "/a", tmp + "/c", { recursive: true, filter: (src) => !src.endsWith(".txt") });
+console.log("cp: " + fs.readdirSync(tmp + "/c", { recursive: true }).join(","));
+try {
+  fs.cpSync(tmp + "/a", tmp + "/d", { recursive: true, filter: async () => true });
+} catch (err) {
+  console.log(err.code);
+}
+try {
+  fs.cpSync(tmp + "/a", tmp + "/a/b", { recursive: true });
+} catch (err) {
+  console.log(err.code);
+}
+
+fs.rmSync(tmp, { recursive: true });
+console.log("removed: " + !fs.existsSync(tmp));
@@ -0,0 +1,50 @@
+const util = require("node:util");
+const { inspect, styleText } = util;
+
+// The escape sequences are printed escaped, to compare them.
+const show = (text) => console.log(JSON.stringify(text));
+const colored = (value, options) => show(inspect(value, { colors: true, ...options }));
+
+colored(1);
+colored("text");
+colored([null, undefined, true, 10n, Symbol("s"), -0]);
+colored({ a: { b: { c: { d: 1 } } }, f() {}, r: /x/g, d: new Date(0) });
+colored(new Map([["key", 1]]));
+colored([new Number(3), new String("ab")]);
+colored({ get value() { return 1; }, set only(v) {} }, { getters: true });
+colored(Array.from({ length: 30 }, (_, i) => i * 100));
+colored({ a: [1, 2, 3], b: "xy" }, { breakLength: 30 });
+colored("line\n".repeat(5) + "end", { breakLength: 20 });
+colored({ [inspect.custom]: (depth, options) => options.stylize("custom", "special") });
+colored({ [inspect.custom]: (depth, options) => `${options.colors}` });
+show(inspect({ [inspect.custom]: (depth, options) => options.stylize("plain", "special") }));
+
+console.log(Object.keys(inspect.colors).length, Object.getPrototypeOf(inspect.colors));
+console.log(inspect.colors.grey === inspect.colors.gray, "grey" in inspect.colors, Object.keys(inspect.colors).includes("grey"));
+console.log(JSON.stringify(inspect.styles), Object.getPrototypeOf(inspect.styles));
+inspect.styles.number = "red";
+colored(42);
+inspect.styles.number = "yellow";
+inspect.colors.yellow = [93, 39];
+colored(42);
+inspect.colors.yellow = [33, 39];
+
+show(util.formatWithOptions({ colors: true }, "%s %o %d", { a: 1 }, { b: 2 }, 3));
+show(util.formatWithOptions({ colors: true }, "x", 5, "s"));
+
+show(styleText("red", "hello", { validateStream: false }));
+show(styleText(["bold", "underline", "green"], "nested", { validateStream: false }));
+show(styleText("grey", "alias", { validateStream: false }));
+for (const args of [
+  ["nope", "x"],
+  [["red", "nope"], "x"],
+  ["red", 1],
+  ["red", "x", { validateStream: 1 }],
+  ["red", "x", { stream: {} }],
+]) {
+  try {
+    styleText(...args);
+  } catch (error) {
+    console.log(error.code, error.message.slice(0, 24));
+  }
+}
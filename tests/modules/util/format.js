@@ -0,0 +1,30 @@
+const util = require("node:util");
+
+console.log("%d items", 5);
+console.log("%s and %s", "one");
+console.log("%i %f %d", "42.9px", "3.5e2", -0);
+console.log("%d %i", 10n, Symbol("s"));
+console.log("%s", { a: 1, nested: { b: 2 } });
+console.log("%s %s %s", 1.5, 10n, null);
+console.log("%s", { toString() { return "custom toString"; } });
+console.log("%j", { a: [1, "x"] });
+const circular = {};
+circular.self = circular;
+console.log("%j", circular);
+console.log("%o", [1, { deep: { deeper: { deepest: {} } } }]);
+console.log("%O", { a: { b: { c: { d: 1 } } } });
+console.log("%c styled", "color: red");
+console.log("100%% sure, %x stays", "extra");
+console.log("100%%");
+console.log("appended", 1, "two", [3]);
+console.log(1, "two", { three: 3 });
+console.log();
+
+console.log(util.format("%s:%s", "a"));
+console.log(util.format("%s", "a", "b", { c: 1 }));
+console.log(util.formatWithOptions({ depth: 0 }, "%O", { a: { b: 1 } }));
+try {
+  util.formatWithOptions("no options", "%s", 1);
+} catch (error) {
+  console.log(error.code);
+}
@@ -0,0 +1,114 @@
+const util = require("node:util");
+const { inspect } = util;
+
+console.log(inspect("a'b"), inspect(-0), inspect(10n), inspect(Symbol("s")), inspect(null));
+console.log(inspect({ a: 1, "b-c": [1, "x"], [Symbol("k")]: true, __proto__: { hidden: 1 } }));
+console.log(inspect({ a: { b: { c: { d: { e: 1 } } } } }));
+console.log(inspect({ a: { b: { c: { d: { e: 1 } } } } }, { depth: 0 }));
+console.log(inspect({ a: { b: { c: { d: { e: 1 } } } } }, { depth: null }));
+console.log(inspect({ a: { b: { c: { d: {} } } } }, false, 5));
+
+const circular = { name: "root", list: [] };
+circular.self = circular;
+circular.list.push(circular, { parent: circular });
+console.log(inspect(circular));
+
+console.log(inspect(Array.from({ length: 30 }, (_, i) => i * 7)));
+console.log(inspect(Array.from({ length: 26 }, (_, i) => String.fromCharCode(97 + i).repeat(i % 4 + 1))));
+console.log(inspect(Array.from({ length: 120 }, (_, i) => i)));
+console.log(inspect([1, , 3, , , 6, "x"]));
+const sparse = [1, 2];
+sparse.extra = true;
+console.log(inspect(sparse), inspect(sparse, { showHidden: true }));
+
+console.log(inspect(new Map([["a", { b: 1 }], [{ k: 1 }, [2]]])));
+console.log(inspect(new Set([1, "two", [3]])), inspect(new Map()), inspect(new Set()));
+console.log(inspect(new Set(Array.from({ length: 5 }, (_, i) => i)), { maxArrayLength: 2 }));
+console.log(inspect(new Uint8Array([1, 2, 3])), inspect(new Float64Array(2)), inspect(new Int16Array(0)));
+console.log(inspect(new ArrayBuffer(3)), inspect(new DataView(new ArrayBuffer(2))));
+console.log(inspect(new WeakMap()), inspect(new WeakSet()));
+
+console.log(inspect(new Number(3)), inspect(new String("ab")), inspect(new Boolean(false)), inspect(Object(Symbol("q"))));
+const boxed = new Number(7);
+boxed.unit = "cm";
+console.log(inspect(boxed));
+
+console.log(inspect(function named() {}), inspect(() => {}), inspect(async function run() {}), inspect(function* gen() {}));
+class Base {}
+class Derived extends Base {
+  constructor() {
+    super();
+    this.x = 1;
+  }
+}
+console.log(inspect(Base), inspect(Derived), inspect(new Derived()), inspect(class {}));
+const fn = function withProps() {};
+fn.answer = 42;
+console.log(inspect(fn));
+console.log(inspect(Object.create(null)), inspect(Object.assign(Object.create(null), { a: 1 })));
+console.log(inspect({ [Symbol.toStringTag]: "Tagged", a: 1 }));
+class Tagged {
+  get [Symbol.toStringTag]() {
+    return "Custom";
+  }
+}
+console.log(inspect(new Tagged()));
+
+console.log(inspect(new Date(0)), inspect(/ab+c/gi), inspect(new Date(NaN)));
+const regexp = /x/;
+regexp.flag = 1;
+console.log(inspect(regexp));
+
+console.log(inspect(new Error("boom")).split("\n")[0]);
+class ValidationError extends Error {}
+console.log(inspect(new ValidationError("bad")).split("\n")[0]);
+const noStack = new RangeError("out");
+noStack.stack = undefined;
+console.log(inspect(noStack));
+const withCode = new Error("coded");
+withCode.stack = "";
+withCode.code = "E_CODE";
+console.log(inspect(withCode));
+console.log(inspect({ error: noStack }));
+
+const accessors = {
+  get value() {
+    return 1;
+  },
+  get both() {
+    return { nested: true };
+  },
+  set both(v) {},
+  set only(v) {},
+  get broken() {
+    throw new Error("nope");
+  },
+};
+console.log(inspect(accessors));
+console.log(inspect(accessors, { getters: true }));
+console.log(inspect(accessors, { getters: "set" }));
+
+const custom = {
+  [inspect.custom](depth, options, inspectFunction) {
+    return `custom ${depth} ${typeof options.stylize} ${inspectFunction === inspect}`;
+  },
+};
+console.log(inspect(custom), inspect({ inner: custom }));
+console.log(inspect(custom, { customInspect: false }).includes("Symbol(nodejs.util.inspect.custom)"));
+console.log(inspect({ [inspect.custom]: () => ({ replaced: ["a\nb"] }) }));
+console.log(inspect({ nested: { [inspect.custom]: () => "line 1\nline 2" } }));
+console.log(inspect.custom === Symbol.for("nodejs.util.inspect.custom"));
+
+const long = "word ".repeat(30) + "\nsecond line\n" + "end";
+console.log(inspect(long));
+console.log(inspect({ text: long }));
+console.log(inspect("x".repeat(20), { maxStringLength: 4 }));
+console.log(inspect({ b: 1, a: 2, c: { z: 1, y: 2 } }, { sorted: true }));
+console.log(inspect({ b: 1, a: 2 }, { sorted: (a, b) => (a < b ? 1 : -1) }));
+console.log(inspect({ a: [1, 2, { b: "c" }], d: "e" }, { compact: false }));
+console.log(inspect({ a: [1, 2, { b: "c" }], d: "e".repeat(80) }, { compact: true }));
+console.log(inspect({ a: 1, b: "two", c: [3] }, { breakLength: 10 }));
+console.log(inspect({ a: { b: { c: {} } } }, { compact: 1 }));
+console.log(inspect([1, 2, 3], { showHidden: true }));
+console.log(inspect(Object.defineProperty({}, "hidden", { value: 1 }), { showHidden: true }));
+console.log(util.format("%o", { f: function f() {} }).split("\n").length > 1);
@@ -0,0 +1,61 @@
+const { parseArgs } = require("node:util");
+
+const parse = (config) => {
+  try {
+    console.log(parseArgs(config));
+  } catch (error) {
+    console.log(error.name, error.code, error.message.split(". Received")[0]);
+  }
+};
+
+const options = {
+  verbose: { type: "boolean", short: "v" },
+  color: { type: "boolean", short: "c" },
+  file: { type: "string", short: "f" },
+  include: { type: "string", short: "I", multiple: true },
+  level: { type: "string", default: "info" },
+  tags: { type: "string", multiple: true, default: ["a"] },
+};
+
+// Long and short options, groups and values.
+parse({ args: ["--verbose", "--file", "a.txt", "--level=debug"], options });
+parse({ args: ["-v", "-f", "b.txt", "-I", "x", "-Iy", "--include=z"], options });
+parse({ args: ["-vcfout.txt"], options });
+parse({ args: ["-vc", "-f=x"], options });
+parse({ args: ["--file", "--verbose"], options, strict: false });
+parse({ args: ["--file="], options });
+
+// Positionals and the terminator.
+parse({ args: ["one", "-v", "two"], options, allowPositionals: true });
+parse({ args: ["-v", "--", "--file", "-x"], options, allowPositionals: true });
+parse({ args: ["--unknown", "-x", "value", "--other=1"], strict: false });
+parse({ args: ["-"], allowPositionals: true });
+
+// Negations.
+parse({ args: ["--no-color", "--verbose"], options, allowNegative: true });
+parse({ args: ["--no-file"], options, allowNegative: true });
+
+// Tokens.
+parse({ args: ["-vf", "x", "--", "y"], options, tokens: true, allowPositionals: true });
+console.log(parseArgs({ args: ["-ab"], strict: false, tokens: true }).tokens);
+
+// Errors.
+parse({ args: ["--unknown"], options });
+parse({ args: ["-x"], options, allowPositionals: true });
+parse({ args: ["positional"], options });
+parse({ args: ["--file"], options });
+parse({ args: ["-f", "-v"], options });
+parse({ args: ["--file", "-v"], options });
+parse({ args: ["--verbose=yes"], options });
+parse({ args: ["--no-verbose=1"], options, allowNegative: true });
+parse({ args: "-v" });
+parse({ args: [], strict: "yes" });
+parse({ args: [], tokens: 1 });
+parse({ args: [], options: { a: { type: "number" } } });
+parse({ args: [], options: { a: { type: "string", short: "ab" } } });
+parse({ args: [], options: { a: { type: "boolean", multiple: true, default: [1] } } });
+parse({ args: [], options: { a: null } });
+
+// The arguments of the script by default.
+parse({ strict: false });
+parse();
@@ -0,0 +1,150 @@
+const util = require("node:util");
+const { types } = util;
+
+const show = (label, error) =>
+  console.log(label, error.name, error.code, error.message.split(". Received")[0]);
+
+// promisify and callbackify.
+function add(a, b, callback) {
+  callback(null, a + b);
+}
+function fail(callback) {
+  callback(new Error("failed"));
+}
+const addAsync = util.promisify(add);
+addAsync(1, 2).then((sum) => console.log("promisify", sum));
+util.promisify(fail)().catch((error) => console.log("promisify rejected", error.message));
+function custom() {}
+custom[util.promisify.custom] = () => Promise.resolve("custom");
+console.log(util.promisify(custom) === custom[util.promisify.custom], util.promisify(addAsync) === addAsync);
+console.log(util.promisify.custom === Symbol.for("nodejs.util.promisify.custom"));
+try {
+  util.promisify(1);
+} catch (error) {
+  show("promisify", error);
+}
+
+util.callbackify(async (value) => value * 2)(21, (error, value) => console.log("callbackify", error, value));
+util.callbackify(async () => {
+  throw new TypeError("thrown");
+})((error) => console.log("callbackify error", error.message));
+util.callbackify(() => Promise.reject(null))((error) => show("callbackify falsy", error));
+try {
+  util.callbackify(async () => 1)(1);
+} catch (error) {
+  show("callbackify", error);
+}
+
+// inherits.
+function Animal() {}
+Animal.prototype.speak = function () {
+  return "...";
+};
+function Dog() {}
+util.inherits(Dog, Animal);
+console.log(new Dog().speak(), Dog.super_ === Animal, new Dog() instanceof Animal);
+try {
+  util.inherits(Dog, {});
+} catch (error) {
+  show("inherits", error);
+}
+
+// deprecate.
+const old = util.deprecate(() => "old", "old() is deprecated", "DEP_TEST");
+console.log(old(), old(), util.deprecate(() => "other", "other() too", "DEP_TEST")());
+
+// types.
+console.log(
+  types.isDate(new Date()),
+  types.isDate({}),
+  types.isRegExp(/a/),
+  types.isRegExp(RegExp.prototype),
+  types.isPromise(Promise.resolve()),
+  types.isMap(new Map()),
+  types.isSet(new Map()),
+  types.isWeakMap(new WeakMap()),
+  types.isWeakSet(new WeakSet())
+);
+console.log(
+  types.isNativeError(new RangeError()),
+  types.isNativeError({ name: "Error" }),
+  types.isAsyncFunction(async () => {}),
+  types.isGeneratorFunction(function* () {}),
+  types.isGeneratorObject((function* () {})()),
+  types.isMapIterator(new Map().keys()),
+  types.isSetIterator(new Set().values())
+);
+console.log(
+  types.isArrayBuffer(new ArrayBuffer(1)),
+  types.isAnyArrayBuffer(new ArrayBuffer(1)),
+  types.isArrayBufferView(new DataView(new ArrayBuffer(1))),
+  types.isDataView(new DataView(new ArrayBuffer(1))),
+  types.isTypedArray(new Float32Array()),
+  types.isUint8Array(new Uint8Array()),
+  types.isUint8Array(new Int8Array()),
+  types.isBigInt64Array(new BigInt64Array())
+);
+console.log(
+  types.isBoxedPrimitive(Object(1n)),
+  types.isNumberObject(new Number(1)),
+  types.isNumberObject(1),
+  types.isStringObject(new String("")),
+  types.isArgumentsObject(
+    (function () {
+      return arguments;
+    })()
+  )
+);
+
+// isDeepStrictEqual.
+const { isDeepStrictEqual } = util;
+console.log(
+  isDeepStrictEqual({ a: [1, { b: 2 }] }, { a: [1, { b: 2 }] }),
+  isDeepStrictEqual({ a: 1 }, { a: "1" }),
+  isDeepStrictEqual([1, 2], { 0: 1, 1: 2 }),
+  isDeepStrictEqual(new Set([1, { a: 1 }]), new Set([{ a: 1 }, 1])),
+  isDeepStrictEqual(new Map([[{ k: 1 }, 2]]), new Map([[{ k: 1 }, 2]])),
+  isDeepStrictEqual(NaN, NaN),
+  isDeepStrictEqual(0, -0),
+  isDeepStrictEqual(new Date(1), new Date(2)),
+  isDeepStrictEqual(/a/g, /a/g),
+  isDeepStrictEqual(new Uint8Array([1]), new Uint8Array([1])),
+  isDeepStrictEqual(new Number(1), new Number(2))
+);
+const cycle = { name: "a" };
+cycle.self = cycle;
+const other = { name: "a" };
+other.self = other;
+console.log(isDeepStrictEqual(cycle, other), isDeepStrictEqual(Object.create(null), {}));
+
+// TextEncoder and TextDecoder.
+const encoder = new util.TextEncoder();
+console.log(encoder.encoding, Array.from(encoder.encode("hé\ud800x")), Array.from(encoder.encode()));
+console.log(encoder.encodeInto("héllo\u{1f600}", new Uint8Array(7)));
+try {
+  encoder.encodeInto("x", []);
+} catch (error) {
+  show("encodeInto", error);
+}
+const decoder = new util.TextDecoder();
+console.log(decoder.encoding, decoder.fatal, decoder.ignoreBOM, decoder.decode() === "");
+console.log(decoder.decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x68, 0xc3, 0xa9])));
+const split = encoder.encode("\u{1f600}!");
+console.log(decoder.decode(split.subarray(0, 2), { stream: true }) + decoder.decode(split.subarray(2)));
+console.log(new util.TextDecoder(" UTF8 ").encoding, new util.TextDecoder("utf-16le").decode(new Uint16Array([104, 105])));
+try {
+  new util.TextDecoder("nope");
+} catch (error) {
+  show("TextDecoder", error);
+}
+try {
+  decoder.decode("x");
+} catch (error) {
+  show("decode", error);
+}
+try {
+  new util.TextDecoder("utf-8", { fatal: true }).decode(new Uint8Array([0xff]));
+} catch (error) {
+  show("fatal", error);
+}
+console.log(util.TextEncoder === TextEncoder, util.TextDecoder === TextDecoder);
@@ -0,0 +1,35 @@
+const fsPromises = require("node:fs/promises");
+const fs = require("node:fs");
+
+// Never closed, an unreferenced watcher doesn't keep the process alive.
+fs.watch(".").unref().ref().unref();
+
+fsPromises.mkdtemp("/tmp/needjs-").then(async (tmp) => {
+  const controller = new AbortController();
+  const watcher = fsPromises.watch(tmp, { signal: controller.signal });
+  setTimeout(() => fsPromises.mkdir(tmp + "/created"), 100);
+  try {
+    for await (const event of watcher) {
+      console.log("promises: " + event.eventType + " " + event.filename);
+      controller.abort();
+    }
+  } catch (err) {
+    console.log(err.name);
+  }
+
+  const fsWatcher = fs.watch(tmp, (eventType, filename) => {
+    console.log("watch: " + eventType + " " + filename);
+    fsWatcher.close();
+  });
+  fsWatcher.on("close", () => console.log("closed"));
+  await fsPromises.rmdir(tmp + "/created");
+
+  const file = tmp + "/polled";
+  await fsPromises.mkdir(file);
+  fs.watchFile(file, { interval: 50 }, (curr, prev) => {
+    console.log("watchFile: " + prev.isDirectory() + " " + curr.isDirectory());
+    fs.unwatchFile(file);
+    fsPromises.rm(tmp, { recursive: true });
+  });
+  setTimeout(() => fsPromises.rmdir(file), 100);
+});
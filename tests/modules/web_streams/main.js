@@ -0,0 +1,76 @@
+const fs = require("node:fs");
+const { Readable } = require("node:stream");
+
+const upper = new TransformStream({
+  transform(chunk, controller) {
+    controller.enqueue(chunk.toUpperCase());
+  },
+  flush(controller) {
+    controller.enqueue("!");
+  },
+});
+
+const written = [];
+const sink = new WritableStream(
+  {
+    write(chunk) {
+      written.push(chunk);
+    },
+    close() {
+      console.log("written: " + written.join(""));
+    },
+  },
+  new CountQueuingStrategy({ highWaterMark: 2 })
+);
+
+(async () => {
+  await ReadableStream.from(["web ", "streams"]).pipeThrough(upper).pipeTo(sink);
+
+  const reader = new ReadableStream({
+    start(controller) {
+      controller.enqueue(1);
+      controller.enqueue(2);
+      controller.close();
+    },
+  }).getReader();
+  console.log("read: " + JSON.stringify(await reader.read()));
+  console.log("read: " + JSON.stringify(await reader.read()));
+  console.log("read: " + JSON.stringify(await reader.read()));
+
+  const [left, right] = ReadableStream.from([1, 2, 3]).tee();
+  const values = [];
+  for await (const value of left) values.push(value);
+  for await (const value of right) values.push(value);
+  console.log("tee: " + values.join(","));
+
+  const failing = new ReadableStream({
+    start(controller) {
+      controller.error(new Error("broken source"));
+    },
+  });
+  try {
+    await failing.pipeTo(new WritableStream());
+  } catch (err) {
+    console.log("pipeTo: " + err.message);
+  }
+
+  const tmp = fs.mkdtempSync("/tmp/needjs-");
+  fs.writeFileSync(tmp + "/in.txt", "héllo wörld");
+  const text = [];
+  await Readable.toWeb(fs.createReadStream(tmp + "/in.txt", { highWaterMark: 2 }))
+    .pipeThrough(new TextDecoderStream())
+    .pipeTo(
+      new WritableStream({
+        write(chunk) {
+          text.push(chunk);
+        },
+      })
+    );
+  console.log("decoded: " + text.join(""));
+
+  const encoded = ReadableStream.from(["from ", "the web"]).pipeThrough(new TextEncoderStream());
+  for await (const chunk of Readable.fromWeb(encoded)) {
+    console.log("fromWeb: " + chunk.length + " bytes");
+  }
+  fs.rmSync(tmp, { recursive: true });
+})();
@@ -0,0 +1,27 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/out.txt";
+const stream = fs.createWriteStream(file, { highWaterMark: 8 });
+console.log("highWaterMark: " + stream.writableHighWaterMark);
+
+let count = 0;
+function writeMore() {
+  while (count < 10) {
+    count++;
+    if (!stream.write("chunk " + count + "\n")) {
+      console.log("wait after " + count + ", length " + stream.writableLength);
+      return;
+    }
+  }
+  stream.close();
+}
+stream.on("drain", () => {
+  console.log("drain, needDrain " + stream.writableNeedDrain);
+  writeMore();
+});
+stream.on("close", () => {
+  console.log("written: " + stream.bytesWritten);
+  fs.rmSync(tmp, { recursive: true });
+});
+writeMore();
@@ -0,0 +1,35 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/out.txt";
+const stream = fs.createWriteStream(file);
+
+stream.on("open", (fd) => console.log("open: " + typeof fd));
+stream.on("ready", () => console.log("ready"));
+stream.on("finish", () => console.log("finish, ended " + stream.writableEnded));
+stream.on("close", () => console.log("first close listener"));
+stream.on("close", () => {
+  console.log("second close listener");
+  console.log("content: " + fs.readFileSync(file, "utf8"));
+
+  const failing = fs.createWriteStream(tmp + "/missing/out.txt");
+  failing.write("lost", (err) => console.log("write callback: " + err.code));
+  failing.on("error", (err) => console.log("error: " + err.code + " " + err.syscall));
+  failing.on("close", () => {
+    console.log("failing closed, destroyed " + failing.destroyed);
+    const destroyed = fs.createWriteStream(tmp + "/destroyed.txt");
+    destroyed.on("error", (err) => console.log("destroy error: " + err.message));
+    destroyed.on("close", () => {
+      console.log("destroyed closed");
+      fs.rmSync(tmp, { recursive: true });
+    });
+    destroyed.destroy(new Error("boom"));
+  });
+});
+
+stream.write("hello ", () => console.log("first chunk written"));
+stream.write(new Uint8Array([119, 111, 114, 108, 100]), "utf8", () =>
+  console.log("second chunk written")
+);
+stream.end("!", () => console.log("end callback"));
+stream.write("too late", (err) => console.log("late write: " + err.code));
@@ -0,0 +1,19 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/out.txt";
+const stream = fs.createWriteStream(file);
+
+// A write after the end fails with its callback and an 'error' event.
+stream.on("finish", () => {
+  console.log("finish, ended " + stream.writableEnded);
+  stream.write("too late", (err) => console.log("late write: " + err.code));
+});
+stream.on("error", (err) => console.log("late error: " + err.code));
+stream.on("close", () => {
+  console.log("closed, destroyed " + stream.destroyed);
+  console.log("content: " + fs.readFileSync(file, "utf8"));
+  fs.rmSync(tmp, { recursive: true });
+});
+
+stream.end("done", () => console.log("end callback"));
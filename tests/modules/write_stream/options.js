@@ -0,0 +1,23 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/log.txt";
+fs.writeFileSync(file, "first line\n");
+
+const stream = fs.createWriteStream(file, { flags: "a" });
+console.log("path: " + (stream.path === file) + " pending: " + stream.pending);
+stream.write("second line\n");
+stream.write("7468697264206c696e650a", "hex");
+stream.on("close", () => {
+  console.log("bytesWritten: " + stream.bytesWritten);
+  console.log(fs.readFileSync(file, "utf8"));
+
+  const inPlace = fs.createWriteStream(file, { flags: "r+", start: 0 });
+  inPlace.write("FIRST");
+  inPlace.on("close", () => {
+    console.log(fs.readFileSync(file, "utf8").split("\n")[0]);
+    fs.rmSync(tmp, { recursive: true });
+  });
+  inPlace.close();
+});
+stream.close();
@@ -0,0 +1,24 @@
+const fs = require("node:fs");
+
+const tmp = fs.mkdtempSync("/tmp/needjs-");
+const file = tmp + "/ordered.txt";
+const stream = fs.createWriteStream(file);
+
+// Written right away, before the file is even opened.
+for (let i = 0; i < 1000; i++) {
+  stream.write(i + "\n");
+}
+
+stream.cork();
+stream.write("corked 1\n");
+stream.write("corked 2\n");
+console.log("corked: " + stream.writableCorked);
+stream.uncork();
+
+stream.on("close", () => {
+  const lines = fs.readFileSync(file, "utf8").trim().split("\n");
+  const ordered = lines.slice(0, 1000).every((line, i) => line === String(i));
+  console.log("ordered: " + ordered + " " + lines.slice(1000).join(","));
+  fs.rmSync(tmp, { recursive: true });
+});
+stream.close();